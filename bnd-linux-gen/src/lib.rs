@@ -1,5 +1,7 @@
 use std::path::Path;
 
+use bnd_winmd::{BindgenLayout, BindgenOptions};
+
 /// Generate the bnd-linux source tree at `output_dir`.
 ///
 /// 1. Runs bnd-winmd on `linux.toml` to produce a `.winmd`.
@@ -11,14 +13,7 @@ use std::path::Path;
 pub fn generate(output_dir: &Path) {
     let gen_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
 
-    // Step 1: Generate .winmd
-    let winmd_dir = output_dir.join("winmd");
-    std::fs::create_dir_all(&winmd_dir).expect("failed to create winmd directory");
-    let linux_winmd = winmd_dir.join("bnd-linux.winmd");
-    bnd_winmd::run(&gen_dir.join("linux.toml"), Some(&linux_winmd))
-        .expect("bnd-winmd failed to generate winmd");
-
-    // Step 2: Locate posix winmd (produced by bnd-posix-gen)
+    // Locate posix winmd (produced by bnd-posix-gen)
     let posix_winmd = gen_dir.join("../bnd-posix/winmd/bnd-posix.winmd");
     assert!(
         posix_winmd.exists(),
@@ -27,21 +22,18 @@ pub fn generate(output_dir: &Path) {
         posix_winmd.display()
     );
 
-    // Step 3: Generate crate source tree via windows-bindgen package mode
-    windows_bindgen::bindgen([
-        "--in",
-        linux_winmd.to_str().unwrap(),
-        "--in",
-        posix_winmd.to_str().unwrap(),
-        "--out",
-        output_dir.to_str().unwrap(),
-        "--filter",
-        "linux",
-        "--reference",
-        "bnd_posix,full,posix",
-        "--sys",
-        "--package",
-        "--no-toml",
-    ])
-    .unwrap();
+    bnd_winmd::generate_bindings(
+        &gen_dir.join("linux.toml"),
+        output_dir,
+        &BindgenOptions {
+            filter: vec!["linux".to_string()],
+            layout: BindgenLayout::Package,
+            sys: true,
+            no_toml: true,
+            inputs: vec![posix_winmd],
+            references: vec!["bnd_posix,full,posix".to_string()],
+            winmd_dir: Some(output_dir.join("winmd")),
+        },
+    )
+    .expect("failed to generate bindings");
 }
@@ -2,6 +2,8 @@
 
 use std::path::Path;
 
+use bnd_winmd::{BindgenLayout, BindgenOptions};
+
 /// Generate the bnd-posix source tree at `output_dir`.
 ///
 /// 1. Runs bnd-winmd on `bnd-posix.toml` to produce a `.winmd`.
@@ -10,24 +12,17 @@ use std::path::Path;
 pub fn generate(output_dir: &Path) {
     let gen_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
 
-    // Step 1: Generate .winmd
-    let winmd_dir = output_dir.join("winmd");
-    std::fs::create_dir_all(&winmd_dir).expect("failed to create winmd directory");
-    let winmd_path = winmd_dir.join("bnd-posix.winmd");
-    bnd_winmd::run(&gen_dir.join("bnd-posix.toml"), Some(&winmd_path))
-        .expect("bnd-winmd failed to generate winmd");
-
-    // Step 2: Generate crate source tree via windows-bindgen package mode
-    windows_bindgen::bindgen([
-        "--in",
-        winmd_path.to_str().unwrap(),
-        "--out",
-        output_dir.to_str().unwrap(),
-        "--filter",
-        "posix",
-        "--sys",
-        "--package",
-        "--no-toml",
-    ])
-    .unwrap();
+    bnd_winmd::generate_bindings(
+        &gen_dir.join("bnd-posix.toml"),
+        output_dir,
+        &BindgenOptions {
+            filter: vec!["posix".to_string()],
+            layout: BindgenLayout::Package,
+            sys: true,
+            no_toml: true,
+            winmd_dir: Some(output_dir.join("winmd")),
+            ..Default::default()
+        },
+    )
+    .expect("failed to generate bindings");
 }
@@ -0,0 +1,7 @@
+fn main() {
+    // The triple bnd-winmd is compiled for, used as the default clang target.
+    println!(
+        "cargo:rustc-env=BND_WINMD_HOST_TARGET={}",
+        std::env::var("TARGET").expect("TARGET is set by cargo")
+    );
+}
@@ -1,21 +1,96 @@
 //! CLI entry point for bnd-winmd.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use anyhow::Result;
-use clap::Parser;
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
 
 /// bnd-winmd — generate WinMD metadata from C headers.
 #[derive(Parser, Debug)]
-#[command(name = "bnd-winmd", version, about)]
+#[command(
+    name = "bnd-winmd",
+    version,
+    about,
+    args_conflicts_with_subcommands = true
+)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Path to the bnd-winmd.toml configuration file.
     #[arg(default_value = "bnd-winmd.toml")]
     config: PathBuf,
 
-    /// Output file path (overrides config).
+    /// Output file path (overrides config; single `[output]` only).
     #[arg(short, long)]
     output: Option<PathBuf>,
+
+    /// Also write the extracted intermediate model as JSON to this path
+    /// (written before the winmd, so it is available even if emit fails).
+    #[arg(long, value_name = "PATH")]
+    dump_model: Option<PathBuf>,
+
+    /// Print per-partition declaration counts and the type registry size,
+    /// then exit without writing any output.
+    #[arg(long, conflicts_with_all = ["output", "dump_model"])]
+    stats: bool,
+
+    /// Check that the config's headers, type imports and namespace
+    /// overrides resolve, without running clang, then exit.
+    #[arg(long, conflicts_with_all = ["output", "dump_model", "stats"])]
+    check: bool,
+
+    /// After writing the winmd, run windows-bindgen on it and write the
+    /// Rust bindings to this directory (`bindings.rs` with `--flat`).
+    #[arg(long, value_name = "DIR", conflicts_with_all = ["stats", "check"])]
+    emit_rust: Option<PathBuf>,
+
+    /// Namespace for windows-bindgen to generate (default: every
+    /// partition). May be given multiple times.
+    #[arg(long, value_name = "NAMESPACE", requires = "emit_rust")]
+    filter: Vec<String>,
+
+    /// Write a single `bindings.rs` instead of a crate source tree.
+    #[arg(long, requires = "emit_rust")]
+    flat: bool,
+
+    /// Generate raw `sys`-style bindings.
+    #[arg(long, requires = "emit_rust")]
+    sys: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Re-read a generated winmd and check it for dangling type references,
+    /// duplicate type names and `Apis` methods without an `ImplMap`.
+    Verify {
+        /// Path to the `.winmd` file to check.
+        winmd: PathBuf,
+
+        /// External winmd whose types the checked file may reference (as
+        /// with `[[type_import]]`). May be given multiple times.
+        #[arg(long, value_name = "WINMD")]
+        import: Vec<PathBuf>,
+    },
+    /// Generate a winmd from a single header, without a config file.
+    Header {
+        /// The header to parse; only its own declarations are emitted.
+        header: PathBuf,
+
+        /// Namespace of the generated types (default: the header's file
+        /// stem).
+        #[arg(long)]
+        namespace: Option<String>,
+
+        /// Library the functions are imported from (default: the header's
+        /// file stem).
+        #[arg(long)]
+        library: Option<String>,
+
+        /// Output file path (default: `<namespace>.winmd`).
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
 }
 
 fn main() -> Result<()> {
@@ -27,6 +102,77 @@ fn main() -> Result<()> {
         .init();
 
     let cli = Cli::parse();
-    bnd_winmd::run(&cli.config, cli.output.as_deref())?;
+    if let Some(Command::Verify { winmd, import }) = &cli.command {
+        let registry = bnd_winmd::verify::imported_types(import)?;
+        bnd_winmd::verify::verify_file(winmd, &registry)?;
+        tracing::info!(path = %winmd.display(), "winmd verified");
+        return Ok(());
+    }
+    if let Some(Command::Header {
+        header,
+        namespace,
+        library,
+        output,
+    }) = &cli.command
+    {
+        let cfg =
+            bnd_winmd::config::header_config(header, namespace.as_deref(), library.as_deref())?;
+        let winmd = bnd_winmd::generate_from_config(&cfg, Path::new("."))?;
+        let path = output.as_ref().unwrap_or(&cfg.output[0].file);
+        std::fs::write(path, &winmd)
+            .with_context(|| format!("writing output to {}", path.display()))?;
+        tracing::info!(path = %path.display(), size = winmd.len(), "wrote winmd");
+        return Ok(());
+    }
+    if cli.check {
+        let cfg = bnd_winmd::config::load_config(&cli.config)
+            .with_context(|| format!("loading config from {}", cli.config.display()))?;
+        let base_dir = cli.config.parent().unwrap_or_else(|| Path::new("."));
+        let warnings = bnd_winmd::config::validate_config(&cfg, base_dir)?;
+        for warning in &warnings {
+            eprintln!("{}: warning: {warning}", cli.config.display());
+        }
+        if !warnings.is_empty() {
+            anyhow::bail!(
+                "{} problem(s) found in {}",
+                warnings.len(),
+                cli.config.display()
+            );
+        }
+        tracing::info!(path = %cli.config.display(), "config OK");
+        return Ok(());
+    }
+    if cli.stats {
+        let cfg = bnd_winmd::config::load_config(&cli.config)
+            .with_context(|| format!("loading config from {}", cli.config.display()))?;
+        let base_dir = cli.config.parent().unwrap_or_else(|| Path::new("."));
+        println!("{}", bnd_winmd::stats(&cfg, base_dir)?);
+        return Ok(());
+    }
+    if let Some(out_dir) = &cli.emit_rust {
+        let options = bnd_winmd::BindgenOptions {
+            filter: cli.filter.clone(),
+            layout: if cli.flat {
+                bnd_winmd::BindgenLayout::Flat
+            } else {
+                bnd_winmd::BindgenLayout::Package
+            },
+            sys: cli.sys,
+            ..Default::default()
+        };
+        bnd_winmd::run_with_bindings(
+            &cli.config,
+            cli.output.as_deref(),
+            cli.dump_model.as_deref(),
+            out_dir,
+            &options,
+        )?;
+        return Ok(());
+    }
+    bnd_winmd::run_with_model_dump(
+        &cli.config,
+        cli.output.as_deref(),
+        cli.dump_model.as_deref(),
+    )?;
     Ok(())
 }
@@ -0,0 +1,190 @@
+//! One-call pipeline for generator crates and `build.rs` scripts: write the
+//! winmd, then run windows-bindgen on it.
+//!
+//! ```no_run
+//! use std::path::Path;
+//!
+//! use bnd_winmd::{BindgenLayout, BindgenOptions};
+//!
+//! let out = bnd_winmd::generate_bindings(
+//!     Path::new("zlib.toml"),
+//!     Path::new("src"),
+//!     &BindgenOptions {
+//!         filter: vec!["Zlib".to_string()],
+//!         layout: BindgenLayout::Flat,
+//!         sys: true,
+//!         ..Default::default()
+//!     },
+//! )
+//! .unwrap();
+//! assert!(out.bindings.ends_with("bindings.rs"));
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use tracing::info;
+
+use crate::config;
+
+/// How windows-bindgen lays out the generated code.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BindgenLayout {
+    /// A single `bindings.rs` in the output directory (`--flat`).
+    #[default]
+    Flat,
+    /// A crate source tree, one module per namespace under `src/`
+    /// (`--package`). The output directory is the crate root.
+    Package,
+}
+
+/// windows-bindgen settings for [`generate_bindings`].
+#[derive(Debug, Clone, Default)]
+pub struct BindgenOptions {
+    /// Namespaces to generate (`--filter`). When empty, every partition
+    /// namespace of the config is generated.
+    pub filter: Vec<String>,
+    pub layout: BindgenLayout,
+    /// Emit raw `sys`-style bindings (`--sys`).
+    pub sys: bool,
+    /// Leave the crate's `Cargo.toml` features alone in package layout
+    /// (`--no-toml`).
+    pub no_toml: bool,
+    /// Additional winmds to read, e.g. one whose types the config imports
+    /// through `[[type_import]]`.
+    pub inputs: Vec<PathBuf>,
+    /// `--reference` entries (e.g. `bnd_posix,full,posix`) for types that
+    /// another crate already generates.
+    pub references: Vec<String>,
+    /// Directory the winmd files are written to. Defaults to the output
+    /// directory.
+    pub winmd_dir: Option<PathBuf>,
+}
+
+/// Paths written by [`generate_bindings`].
+#[derive(Debug, Clone)]
+pub struct GeneratedBindings {
+    /// One winmd per `[[output]]`, in config order.
+    pub winmds: Vec<PathBuf>,
+    /// The `bindings.rs` file in flat layout, the crate root in package
+    /// layout.
+    pub bindings: PathBuf,
+}
+
+/// Generate the winmd for `config_path` and run windows-bindgen on it,
+/// writing the Rust bindings into `out_dir`.
+///
+/// Each `[[output]]` winmd keeps the file name from its `file` setting
+/// and is written to [`BindgenOptions::winmd_dir`]. Any windows-bindgen
+/// warning is returned as an error.
+pub fn generate_bindings(
+    config_path: &Path,
+    out_dir: &Path,
+    options: &BindgenOptions,
+) -> Result<GeneratedBindings> {
+    let cfg = config::load_config(config_path)
+        .with_context(|| format!("loading config from {}", config_path.display()))?;
+    let base_dir = config_path.parent().unwrap_or_else(|| Path::new("."));
+
+    let winmd_dir = options.winmd_dir.as_deref().unwrap_or(out_dir);
+    std::fs::create_dir_all(winmd_dir)
+        .with_context(|| format!("creating {}", winmd_dir.display()))?;
+    std::fs::create_dir_all(out_dir).with_context(|| format!("creating {}", out_dir.display()))?;
+
+    let mut winmds = Vec::with_capacity(cfg.output.len());
+    for output in &cfg.output {
+        let file_name = output
+            .file
+            .file_name()
+            .with_context(|| format!("output `{}` has no file name", output.name))?;
+        winmds.push(winmd_dir.join(file_name));
+    }
+    for (path, winmd_bytes) in winmds
+        .iter()
+        .zip(crate::generate_all_from_config(&cfg, base_dir)?)
+    {
+        crate::write_winmd(path, &winmd_bytes)?;
+    }
+
+    let bindings = bindgen(&cfg, config_path, &winmds, out_dir, options)?;
+    info!(bindings = %bindings.display(), "generated bindings");
+    Ok(GeneratedBindings { winmds, bindings })
+}
+
+/// Like [`crate::run_with_model_dump`], then run windows-bindgen on the
+/// winmds it wrote, writing the Rust bindings into `out_dir`.
+///
+/// The winmds go where [`crate::run`] puts them (the config's `file`
+/// settings, or `output`), so [`BindgenOptions::winmd_dir`] is ignored.
+/// This is the CLI's `--emit-rust`.
+pub fn run_with_bindings(
+    config_path: &Path,
+    output: Option<&Path>,
+    dump_path: Option<&Path>,
+    out_dir: &Path,
+    options: &BindgenOptions,
+) -> Result<GeneratedBindings> {
+    let winmds = crate::run_with_model_dump(config_path, output, dump_path)?;
+    let cfg = config::load_config(config_path)
+        .with_context(|| format!("loading config from {}", config_path.display()))?;
+    std::fs::create_dir_all(out_dir).with_context(|| format!("creating {}", out_dir.display()))?;
+
+    let bindings = bindgen(&cfg, config_path, &winmds, out_dir, options)?;
+    info!(bindings = %bindings.display(), "generated bindings");
+    Ok(GeneratedBindings { winmds, bindings })
+}
+
+/// Run windows-bindgen on `winmds` and return the path it wrote.
+fn bindgen(
+    cfg: &config::Config,
+    config_path: &Path,
+    winmds: &[PathBuf],
+    out_dir: &Path,
+    options: &BindgenOptions,
+) -> Result<PathBuf> {
+    let bindings = match options.layout {
+        BindgenLayout::Flat => out_dir.join("bindings.rs"),
+        BindgenLayout::Package => out_dir.to_path_buf(),
+    };
+
+    let mut args = Vec::new();
+    for input in winmds.iter().chain(&options.inputs) {
+        args.push("--in".to_string());
+        args.push(input.display().to_string());
+    }
+    args.push("--out".to_string());
+    args.push(bindings.display().to_string());
+    args.push("--filter".to_string());
+    if options.filter.is_empty() {
+        args.extend(cfg.partition.iter().map(|p| p.namespace.clone()));
+    } else {
+        args.extend(options.filter.iter().cloned());
+    }
+    for reference in &options.references {
+        args.push("--reference".to_string());
+        args.push(reference.clone());
+    }
+    args.push(
+        match options.layout {
+            BindgenLayout::Flat => "--flat",
+            BindgenLayout::Package => "--package",
+        }
+        .to_string(),
+    );
+    if options.sys {
+        args.push("--sys".to_string());
+    }
+    if options.no_toml {
+        args.push("--no-toml".to_string());
+    }
+
+    let warnings = windows_bindgen::bindgen(&args);
+    if !warnings.is_empty() {
+        anyhow::bail!(
+            "windows-bindgen failed for {}:\n{warnings}",
+            config_path.display()
+        );
+    }
+
+    Ok(bindings)
+}
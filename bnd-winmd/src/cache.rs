@@ -0,0 +1,81 @@
+//! Stamp files for skipping regeneration when inputs are unchanged.
+//!
+//! [`run_cached`](crate::run_cached) writes a `<output>.stamp` sidecar next to
+//! the `.winmd` holding a fingerprint of everything that affects the output.
+//! When the fingerprint still matches on the next run, the clang pass is
+//! skipped and the existing file is reused.
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use tracing::debug;
+
+use crate::config::{self, Config};
+
+/// Whether [`run_cached`](crate::run_cached) may reuse an existing `.winmd`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CachePolicy {
+    /// Always regenerate, like [`run`](crate::run), but still refresh the
+    /// stamp.
+    Regenerate,
+    /// Reuse the existing output when the stamp matches the current inputs.
+    #[default]
+    ReuseIfUnchanged,
+}
+
+/// Path of the stamp sidecar for `output` (e.g. `foo.winmd.stamp`).
+pub fn stamp_path(output: &Path) -> PathBuf {
+    let mut name = output.as_os_str().to_owned();
+    name.push(".stamp");
+    PathBuf::from(name)
+}
+
+/// Fingerprint the inputs of a generation run: the tool version, the config
+/// file, every `headers` / `traverse` entry and every `type_import` winmd.
+///
+/// The config file covers `clang_args`, `include_paths` and all other
+/// settings. Headers reached only through `#include` are not tracked — list
+/// them in `headers` or `traverse` if edits to them must trigger a rebuild.
+pub fn fingerprint(cfg: &Config, config_path: &Path, base_dir: &Path) -> String {
+    let mut hasher = DefaultHasher::new();
+    env!("CARGO_PKG_VERSION").hash(&mut hasher);
+    hash_file(&mut hasher, config_path);
+
+    for partition in &cfg.partition {
+        let include_paths = partition.search_paths(base_dir, &cfg.include_paths);
+        for header in partition.headers.iter().chain(&partition.traverse) {
+            let path = config::resolve_header(header, base_dir, &include_paths);
+            hash_file(&mut hasher, &path);
+        }
+    }
+    for ti in &cfg.type_import {
+        let path = config::resolve_header(&ti.winmd, base_dir, &cfg.include_paths);
+        hash_file(&mut hasher, &path);
+    }
+
+    format!("{:016x}", hasher.finish())
+}
+
+/// Returns true if `output` exists and its stamp matches `fingerprint`.
+pub fn is_fresh(output: &Path, fingerprint: &str) -> bool {
+    if !output.exists() {
+        return false;
+    }
+    match std::fs::read_to_string(stamp_path(output)) {
+        Ok(stamp) => stamp.trim() == fingerprint,
+        Err(e) => {
+            debug!(path = %output.display(), err = %e, "no readable stamp");
+            false
+        }
+    }
+}
+
+/// Hash a file's path and contents. Unreadable files hash as missing, so
+/// they still change the fingerprint once they appear.
+fn hash_file(hasher: &mut DefaultHasher, path: &Path) {
+    path.hash(hasher);
+    match std::fs::read(path) {
+        Ok(bytes) => bytes.hash(hasher),
+        Err(_) => "<missing>".hash(hasher),
+    }
+}
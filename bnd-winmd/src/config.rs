@@ -1,42 +1,166 @@
 //! Configuration types for `bnd-winmd.toml`.
 
-use serde::Deserialize;
-use std::collections::HashMap;
+use anyhow::Context;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+
+use crate::metadata::{AssemblyIdentity, AssemblyVersion};
+use crate::model::ParamDirection;
 
 /// Root configuration.
 #[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct Config {
-    pub output: OutputConfig,
+    /// Output assemblies. A single `[output]` table receives every
+    /// partition; `[[output]]` tables each write a separate winmd from the
+    /// partitions they select, all from one parse.
+    #[serde(deserialize_with = "one_or_many")]
+    pub output: Vec<OutputConfig>,
     /// Additional directories to search when resolving header and traverse
-    /// paths.  Each entry is tried in order after `base_dir` (the TOML
-    /// file's parent directory).  Also injected as `-I` flags for clang.
+    /// paths, relative to `base_dir` (the TOML file's parent directory).
+    /// Each entry is tried in order after `base_dir` and the partition's
+    /// own `include_paths`.  Also injected as `-I` flags for clang.
     #[serde(default)]
     pub include_paths: Vec<PathBuf>,
     #[serde(default)]
     pub partition: Vec<PartitionConfig>,
     #[serde(default)]
     pub namespace_overrides: HashMap<String, String>,
+    /// Namespace owning each type extracted by several partitions, keyed
+    /// by type name, e.g. `uid_t = "posix.types"`. The other partitions drop
+    /// their copy and refer to this one, whatever the partition order.
+    #[serde(default)]
+    pub canonical_types: HashMap<String, String>,
+    /// New names for individual symbols (types, functions or constants),
+    /// keyed by their C name, e.g. `stat = "FileStat"`. Applied in every
+    /// partition; a renamed symbol is not also prefix-stripped.
+    #[serde(default)]
+    pub renames: HashMap<String, String>,
+    /// Resources and the functions that release them, as `[resource, free]`
+    /// pairs of C names: a handle typedef (`["BIO", "BIO_free"]`) or an
+    /// allocating function (`["opendir", "closedir"]`).
+    #[serde(default)]
+    pub resource_pairs: Vec<(String, String)>,
     #[serde(default)]
     pub type_import: Vec<TypeImportConfig>,
+    /// Default C data model for all partitions. It is always inferred from
+    /// each partition's `--target` clang argument, or the host; a model set
+    /// here must match it.
+    #[serde(default)]
+    pub data_model: Option<DataModel>,
+    /// Attach C doc comments to structs, enums and functions as
+    /// `DocumentationAttribute`s. Off by default since it bloats the winmd.
+    #[serde(default)]
+    pub emit_docs: bool,
+    /// Attach the header and line of each struct, enum, typedef and
+    /// function as a `SourceLocationAttribute`. Off by default.
+    #[serde(default)]
+    pub emit_source_locations: bool,
+    /// Emit an empty `Apis` class for partitions with no functions or
+    /// constants. Off by default, so types-only namespaces have none.
+    #[serde(default)]
+    pub emit_empty_apis: bool,
+    /// Target triple (e.g. `x86_64-unknown-linux-gnu`) to parse every
+    /// partition for, unless its `clang_args` name another. Recorded in each
+    /// output winmd as a `TargetAttribute`.
+    #[serde(default)]
+    pub target: Option<String>,
+}
+
+impl Config {
+    /// The optional attributes to emit.
+    pub fn emit_options(&self) -> crate::emit::EmitOptions<'_> {
+        crate::emit::EmitOptions {
+            docs: self.emit_docs,
+            source_locations: self.emit_source_locations,
+            empty_apis: self.emit_empty_apis,
+            target: self
+                .target
+                .as_deref()
+                .map(|triple| (triple, DataModel::from_triple(triple))),
+        }
+    }
 }
 
 /// Output file settings.
 #[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct OutputConfig {
     /// Assembly name written into the winmd.
     pub name: String,
     /// Output file path (e.g. `MyLib.winmd`).
     #[serde(default = "default_output_file")]
     pub file: PathBuf,
+    /// Assembly version stamped into the winmd (e.g. `"1.2.0.0"`), which
+    /// consumers can pin with `[[type_import]] version`. Defaults to the
+    /// writer's `255.255.255.255`.
+    #[serde(default)]
+    pub version: Option<AssemblyVersion>,
+    /// Namespace prefixes of the partitions written to this output (e.g.
+    /// `["posix.net"]` selects `posix.net` and `posix.net.*`). A partition
+    /// goes to the first output that selects it; an output without
+    /// `partitions` takes every partition no other output selects.
+    #[serde(default)]
+    pub partitions: Vec<String>,
+}
+
+impl OutputConfig {
+    /// Returns true if `namespace` is one of `partitions` or nested under
+    /// one of them.
+    pub fn selects(&self, namespace: &str) -> bool {
+        self.partitions
+            .iter()
+            .any(|prefix| namespace_matches(namespace, prefix))
+    }
+
+    /// The identity this output's assembly is written with.
+    pub fn identity(&self) -> AssemblyIdentity {
+        AssemblyIdentity {
+            name: self.name.clone(),
+            version: self.version.unwrap_or(AssemblyVersion::WRITER_DEFAULT),
+        }
+    }
+}
+
+/// Returns true if `namespace` is `prefix` or nested under it
+/// (`posix.net` matches `posix.net` and `posix.net.if`, not `posix.netdb`).
+pub fn namespace_matches(namespace: &str, prefix: &str) -> bool {
+    namespace
+        .strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
 }
 
 fn default_output_file() -> PathBuf {
     PathBuf::from("output.winmd")
 }
 
+/// Accept `output` as either one `[output]` table or an `[[output]]` array.
+///
+/// Dispatches on the TOML shape rather than through an untagged enum, which
+/// would replace an error inside a table (such as a malformed `version`)
+/// with "data did not match any variant".
+fn one_or_many<'de, D>(deserializer: D) -> Result<Vec<OutputConfig>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::de::Error;
+
+    match toml::Value::deserialize(deserializer)? {
+        toml::Value::Array(outputs) => outputs
+            .into_iter()
+            .map(|output| output.try_into().map_err(D::Error::custom))
+            .collect(),
+        output => Ok(vec![output.try_into().map_err(D::Error::custom)?]),
+    }
+}
+
 /// A single partition — maps a set of headers to one namespace.
 #[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct PartitionConfig {
     /// ECMA-335 namespace (e.g. `MyLib.Graphics`).
     pub namespace: String,
@@ -51,9 +175,199 @@ pub struct PartitionConfig {
     /// Extra clang arguments (e.g. `-I/usr/include`).
     #[serde(default)]
     pub clang_args: Vec<String>,
+    /// Preprocessor macros to define before parsing, such as feature-test
+    /// macros (e.g. `{ _GNU_SOURCE = true, _FILE_OFFSET_BITS = 64 }`).
+    /// Passed to clang as `-D` / `-U` flags ahead of `clang_args`.
+    #[serde(default)]
+    pub defines: HashMap<String, Define>,
+    /// Include directories for this partition only, relative to the TOML
+    /// file's directory. Searched before the top-level `include_paths`
+    /// when resolving headers, and passed to clang as `-I` ahead of them.
+    #[serde(default)]
+    pub include_paths: Vec<PathBuf>,
+    /// Source language the headers are parsed as (`-x`). Defaults to C.
+    #[serde(default)]
+    pub language: Language,
+    /// C++ namespaces whose declarations are extracted as if declared at
+    /// the top level, named by their `::`-separated path (e.g.
+    /// `["mylib", "mylib::v2"]`). Other namespaces are skipped.
+    #[serde(default)]
+    pub cpp_namespaces: Vec<String>,
+    /// Language standard (e.g. `c11`, `gnu17`, `c++17`), passed as `-std=`.
+    /// When unset, clang's default for the language applies.
+    #[serde(default)]
+    pub std: Option<String>,
+    /// C data model this partition expects (takes precedence over the
+    /// top-level `data_model`). Must match the partition's target.
+    #[serde(default)]
+    pub data_model: Option<DataModel>,
+    /// Parameter direction overrides keyed by `function:param` (C names),
+    /// e.g. `{ "read_config:buf" = "out" }`. Parameters not listed get a
+    /// direction inferred from their type and name.
+    #[serde(default)]
+    pub param_directions: HashMap<String, ParamDirection>,
+    /// Pointer parameters that point at an array whose element count is
+    /// passed in another parameter of the same function (e.g. `RAND_bytes`'s
+    /// `buf` sized by `num`). Each gets a `NativeArrayInfoAttribute`.
+    #[serde(default)]
+    pub array_params: Vec<ArrayParamConfig>,
+    /// Enums to always emit as bitmask (`[Flags]`) enums, regardless of the
+    /// power-of-two heuristic.
+    #[serde(default)]
+    pub flags_enums: Vec<String>,
+    /// Enums to never emit as bitmask enums, even if their values look like
+    /// flags.
+    #[serde(default)]
+    pub plain_enums: Vec<String>,
+    /// Allowlist of regex patterns. If non-empty, only symbols whose name
+    /// fully matches one of them are extracted.
+    #[serde(default)]
+    pub include_symbols: Vec<String>,
+    /// Blocklist of regex patterns. Symbols whose name fully matches one of
+    /// them are never extracted (wins over `include_symbols`).
+    #[serde(default)]
+    pub exclude_symbols: Vec<String>,
+    /// Prefixes removed from extracted type, function and constant names
+    /// (e.g. `["EVP_"]` turns `EVP_sha256` into `sha256`). The first
+    /// matching prefix wins.
+    #[serde(default)]
+    pub strip_prefix: Vec<String>,
+    /// Fold object-like macros whose body is a constant expression
+    /// (`#define PAGE_MASK (PAGE_SIZE - 1)`) by letting clang evaluate them.
+    /// Costs a second parse of the partition's headers.
+    #[serde(default)]
+    pub evaluate_macros: bool,
+    /// Typedefs of incomplete structs (e.g. `EVP_MD_CTX`) to emit as opaque
+    /// handles: a `Value: *mut c_void` wrapper that functions take by value
+    /// in place of `EVP_MD_CTX *`.
+    #[serde(default)]
+    pub opaque_handles: Vec<String>,
+    /// Name prefixes of `#define` constants to move off the partition's
+    /// `Apis` class into a sub-namespace named after the prefix (e.g.
+    /// `["SSL_OP_"]` puts `SSL_OP_ALL` in `<namespace>.SSL_OP`). The first
+    /// matching prefix wins.
+    #[serde(default)]
+    pub constant_group_prefixes: Vec<String>,
+    /// Globals that C only exposes through a macro around an accessor
+    /// function (e.g. `errno`, which is `(*__errno_location())`), keyed by
+    /// the name to emit. Each becomes a zero-argument function on `Apis`
+    /// importing the accessor symbol.
+    #[serde(default)]
+    pub globals: BTreeMap<String, GlobalConfig>,
+    /// What to do with `static` and `inline` functions, which have no
+    /// exported symbol to import.
+    #[serde(default)]
+    pub inline_functions: InlineFunctions,
+    /// How enums whose C base type is narrower than `int` are passed to
+    /// and returned from functions.
+    #[serde(default)]
+    pub enum_repr: EnumRepr,
+    /// Drop (and report) typedefs whose underlying type can't be mapped,
+    /// and functions whose signature uses one, instead of mapping the type
+    /// to `void`.
+    #[serde(default)]
+    pub strict_signatures: bool,
+    /// Emit `char *` parameters and return types as win32metadata's C
+    /// string types, `Windows.Win32.Foundation.PSTR` (`PCSTR` when the
+    /// characters are `const`), which windows-bindgen wraps ergonomically.
+    #[serde(default)]
+    pub string_pointers: bool,
+    /// Libraries for functions exported by another shared object than
+    /// `library`, keyed by C function name (e.g. `{ SSL_new = "ssl" }`).
+    #[serde(default)]
+    pub function_libraries: HashMap<String, String>,
+    /// Functions (C names) that report failure through `errno` /
+    /// `GetLastError`. Their `ImplMap` gets `SupportsLastError`, so
+    /// generated bindings can capture the error right after the call.
+    #[serde(default)]
+    pub last_error_functions: Vec<String>,
+    /// Precompiled header to load before parsing (`-include-pch`), relative
+    /// to the TOML file's directory. Build it with [`crate::build_pch`] from
+    /// the same `clang_args`; clang rejects a PCH built with different ones.
+    /// The headers are still included after it, so they need include guards
+    /// or `#pragma once`.
+    #[serde(default)]
+    pub pch: Option<PathBuf>,
 }
 
 impl PartitionConfig {
+    /// Returns the data model the clang `--target` argument implies, or the
+    /// [`host_triple`] without one. An explicit model, this partition's or
+    /// `default` (the top-level setting), must agree with it, since clang
+    /// lays types out for the target either way.
+    pub fn data_model(&self, default: Option<DataModel>) -> anyhow::Result<DataModel> {
+        let triple = target_triple(&self.clang_args).map_or_else(host_triple, str::to_string);
+        let implied = DataModel::from_triple(&triple);
+        match self.data_model.or(default) {
+            Some(explicit) if explicit != implied => anyhow::bail!(
+                "partition `{}`: data_model `{}` does not match target `{triple}`, which is `{}`",
+                self.namespace,
+                explicit.as_str(),
+                implied.as_str()
+            ),
+            _ => Ok(implied),
+        }
+    }
+
+    /// Compile `include_symbols` / `exclude_symbols` into a [`SymbolFilter`].
+    pub fn symbol_filter(&self) -> anyhow::Result<SymbolFilter> {
+        SymbolFilter::new(&self.include_symbols, &self.exclude_symbols)
+            .with_context(|| format!("invalid symbol filter in partition `{}`", self.namespace))
+    }
+
+    /// Returns the clang arguments for this partition: `-x` and `-std=` from
+    /// `language` / `std`, then `defines` in name order, followed by
+    /// `clang_args`. A `-x` or `-std=` already present in `clang_args` takes
+    /// precedence over the typed setting.
+    ///
+    /// Without a `--target` (from `clang_args` or the top-level `target`),
+    /// the [`host_triple`] is passed, so `#ifdef __x86_64__` and friends
+    /// resolve the same way the data model does rather than following
+    /// libclang's own default.
+    pub fn clang_args(&self) -> Vec<String> {
+        let has_language = self.clang_args.iter().any(|a| a.starts_with("-x"));
+        let has_std = self
+            .clang_args
+            .iter()
+            .any(|a| a.starts_with("-std=") || a.starts_with("--std="));
+
+        let mut args = Vec::with_capacity(self.clang_args.len() + 3);
+        if !has_language {
+            args.push("-x".to_string());
+            args.push(self.language.clang_input_kind().to_string());
+        }
+        if let Some(std) = &self.std {
+            if has_std {
+                warn!(
+                    namespace = %self.namespace,
+                    std = %std,
+                    "`std` ignored, `clang_args` already sets -std"
+                );
+            } else {
+                args.push(format!("-std={std}"));
+            }
+        }
+        let mut defines: Vec<_> = self.defines.iter().collect();
+        defines.sort_by_key(|(name, _)| *name);
+        args.extend(defines.into_iter().map(|(name, value)| value.flag(name)));
+        args.extend(self.clang_args.iter().cloned());
+        if target_triple(&self.clang_args).is_none() {
+            args.push(format!("--target={}", host_triple()));
+        }
+        args
+    }
+
+    /// Returns the include directories for this partition: its own
+    /// `include_paths`, then the top-level `global` ones, all resolved
+    /// against `base_dir`.
+    pub fn search_paths(&self, base_dir: &Path, global: &[PathBuf]) -> Vec<PathBuf> {
+        self.include_paths
+            .iter()
+            .chain(global)
+            .map(|p| base_dir.join(p))
+            .collect()
+    }
+
     /// Returns the traverse list, falling back to `headers` if empty.
     pub fn traverse_files(&self) -> &[PathBuf] {
         if self.traverse.is_empty() {
@@ -63,69 +377,457 @@ impl PartitionConfig {
         }
     }
 
-    /// Returns the translation unit file to parse.
+    /// Returns the translation unit file to parse, plus its contents when it
+    /// is a generated wrapper.
     ///
     /// If there's a single header/source file, returns it directly.
-    /// If there are multiple, generates a wrapper `.c` file in `out_dir`
-    /// that `#include`s all of them — mimicking the scraper `.c` files
-    /// that win32metadata uses.
-    pub fn wrapper_header(&self, base_dir: &Path, include_paths: &[PathBuf]) -> PathBuf {
-        if self.headers.len() == 1 {
-            resolve_header(&self.headers[0], base_dir, include_paths)
+    /// If there are multiple, returns a wrapper path in `base_dir` (`.c`,
+    /// `.cpp` or `.m` to match `language`) and
+    /// source that `#include`s all of them — mimicking the scraper `.c`
+    /// files that win32metadata uses. The wrapper is handed to clang as an
+    /// unsaved file and never written to disk, so concurrent runs can't
+    /// clobber each other's wrapper.
+    ///
+    /// With a `pch`, a single header gets a wrapper too: as the main file it
+    /// would be parsed again on top of the PCH, since `#pragma once` is not
+    /// applied to the main file.
+    pub fn translation_unit(
+        &self,
+        base_dir: &Path,
+        include_paths: &[PathBuf],
+    ) -> (PathBuf, Option<String>) {
+        if self.headers.len() == 1 && self.pch.is_none() {
+            return (
+                resolve_header(&self.headers[0], base_dir, include_paths),
+                None,
+            );
+        }
+
+        let safe_name = self.namespace.replace('.', "_");
+        let wrapper_path = base_dir.join(format!(
+            "__bnd_{safe_name}_wrapper.{}",
+            self.language.extension()
+        ));
+
+        let mut content = String::new();
+        for h in &self.headers {
+            let abs = resolve_header(h, base_dir, include_paths);
+            content.push_str(&format!("#include \"{}\"\n", abs.display()));
+        }
+        (wrapper_path, Some(content))
+    }
+}
+
+/// Value of a partition `defines` entry.
+///
+/// ```toml
+/// defines = { _GNU_SOURCE = true, _FILE_OFFSET_BITS = 64, NDEBUG = false }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum Define {
+    /// `true` defines the macro without a value (`-DNAME`), `false`
+    /// undefines it (`-UNAME`).
+    Flag(bool),
+    /// Defined to an integer (`-DNAME=64`).
+    Int(i64),
+    /// Defined to this replacement text (`-DNAME=text`).
+    Text(String),
+}
+
+impl Define {
+    /// The clang flag setting macro `name` to this value.
+    pub fn flag(&self, name: &str) -> String {
+        match self {
+            Define::Flag(true) => format!("-D{name}"),
+            Define::Flag(false) => format!("-U{name}"),
+            Define::Int(value) => format!("-D{name}={value}"),
+            Define::Text(text) => format!("-D{name}={text}"),
+        }
+    }
+}
+
+/// An accessor-backed global, declared under a partition's `globals`.
+///
+/// ```toml
+/// [partition.globals]
+/// errno = { accessor = "__errno_location", type = "int *" }
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GlobalConfig {
+    /// The exported function returning the global's address.
+    pub accessor: String,
+    /// C spelling of the accessor's return type: a builtin or a type
+    /// extracted or imported elsewhere, followed by its `*`s.
+    #[serde(rename = "type")]
+    pub ty: String,
+}
+
+/// A pointer parameter sized by a companion length parameter, declared
+/// under a partition's `array_params`.
+///
+/// ```toml
+/// [[partition.array_params]]
+/// function = "RAND_bytes"
+/// ptr_param = "buf"
+/// len_param = "num"
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ArrayParamConfig {
+    /// C name of the function.
+    pub function: String,
+    /// The pointer parameter holding the array.
+    pub ptr_param: String,
+    /// The parameter holding the array's element count.
+    pub len_param: String,
+}
+
+/// Compiled per-partition symbol allowlist/blocklist.
+///
+/// ```toml
+/// include_symbols = ["SSL_.*"]
+/// exclude_symbols = ["SSL_.*_ex"]
+/// ```
+#[derive(Debug, Default)]
+pub struct SymbolFilter {
+    include: Vec<Regex>,
+    exclude: Vec<Regex>,
+    /// Names rejected so far, reported by [`SymbolFilter::filtered`].
+    rejected: RefCell<BTreeSet<String>>,
+}
+
+impl SymbolFilter {
+    /// Compile the patterns. Each one is anchored so it must match the
+    /// whole symbol name.
+    pub fn new(include: &[String], exclude: &[String]) -> anyhow::Result<Self> {
+        let compile = |patterns: &[String]| -> anyhow::Result<Vec<Regex>> {
+            patterns
+                .iter()
+                .map(|p| {
+                    Regex::new(&format!("^(?:{p})$"))
+                        .with_context(|| format!("invalid symbol pattern `{p}`"))
+                })
+                .collect()
+        };
+        Ok(Self {
+            include: compile(include)?,
+            exclude: compile(exclude)?,
+            rejected: RefCell::default(),
+        })
+    }
+
+    /// Returns true if `name` should be extracted. Filtered symbols are
+    /// logged at `debug!` with the reason and remembered for
+    /// [`SymbolFilter::filtered`].
+    pub fn allows(&self, name: &str) -> bool {
+        if let Some(re) = self.exclude.iter().find(|re| re.is_match(name)) {
+            debug!(name, pattern = %re, "symbol filtered: matches exclude_symbols");
+            self.rejected.borrow_mut().insert(name.to_string());
+            return false;
+        }
+        if !self.include.is_empty() && !self.include.iter().any(|re| re.is_match(name)) {
+            debug!(name, "symbol filtered: not matched by include_symbols");
+            self.rejected.borrow_mut().insert(name.to_string());
+            return false;
+        }
+        true
+    }
+
+    /// Every name [`SymbolFilter::allows`] has rejected, sorted.
+    pub fn filtered(&self) -> Vec<String> {
+        self.rejected.borrow().iter().cloned().collect()
+    }
+}
+
+/// Handling of functions without an exported symbol (`static` functions and
+/// `inline` definitions in headers), which a P/Invoke import can't bind.
+///
+/// ```toml
+/// inline_functions = "mark"
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InlineFunctions {
+    /// Leave them out (logged).
+    #[default]
+    Skip,
+    /// Emit them with an `InlineFunctionAttribute`, for tooling that
+    /// generates shims for them.
+    Mark,
+}
+
+/// Width at which enums whose C base type is narrower than `int`
+/// (`enum E : unsigned char`) are passed to and returned from functions.
+///
+/// ```toml
+/// enum_repr = "int"
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EnumRepr {
+    /// Keep the C base type.
+    #[default]
+    Exact,
+    /// Pass and return them as `i32`. The enum type, and struct fields of
+    /// it, keep the C width so layouts still match. Bases of `int` width
+    /// and wider are kept.
+    Int,
+}
+
+/// Source language a partition's headers are parsed as.
+///
+/// ```toml
+/// language = "c++"
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub enum Language {
+    #[default]
+    #[serde(rename = "c")]
+    C,
+    #[serde(rename = "c++", alias = "cpp")]
+    Cpp,
+    #[serde(rename = "objc")]
+    ObjC,
+}
+
+impl Language {
+    /// The input kind passed to clang's `-x` flag. The header kinds keep
+    /// clang from flagging `#pragma once` in the main file.
+    pub fn clang_input_kind(self) -> &'static str {
+        match self {
+            Language::C => "c-header",
+            Language::Cpp => "c++-header",
+            Language::ObjC => "objective-c-header",
+        }
+    }
+
+    /// File extension for generated source files in this language.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Language::C => "c",
+            Language::Cpp => "cpp",
+            Language::ObjC => "m",
+        }
+    }
+}
+
+/// C integer data model — decides the width of `long` / `unsigned long`.
+///
+/// ```toml
+/// data_model = "llp64"
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DataModel {
+    /// `long` is 64-bit (Linux, macOS, BSDs).
+    Lp64,
+    /// `long` is 32-bit (64-bit Windows).
+    Llp64,
+    /// `long` and pointers are 32-bit (32-bit Linux, Android, WebAssembly).
+    Ilp32,
+    /// `long` and pointers are 32-bit and `wchar_t` is 16-bit (32-bit
+    /// Windows).
+    Win32,
+}
+
+impl DataModel {
+    /// Infer the data model from a `--target=<triple>` / `-target <triple>`
+    /// clang argument, falling back to the host platform.
+    pub fn from_clang_args(clang_args: &[String]) -> Self {
+        match target_triple(clang_args) {
+            Some(triple) => Self::from_triple(triple),
+            None => Self::host(),
+        }
+    }
+
+    /// The data model of the host platform.
+    pub fn host() -> Self {
+        if cfg!(all(windows, target_pointer_width = "32")) {
+            DataModel::Win32
+        } else if cfg!(windows) {
+            DataModel::Llp64
+        } else if cfg!(target_pointer_width = "32") {
+            DataModel::Ilp32
         } else {
-            // Generate a wrapper .c file that #includes all headers.
-            let wrapper_dir = std::env::temp_dir().join("bnd_winmd_wrappers");
-            std::fs::create_dir_all(&wrapper_dir).expect("create wrapper dir");
-
-            // Use namespace as a stable filename
-            let safe_name = self.namespace.replace('.', "_");
-            let wrapper_path = wrapper_dir.join(format!("{safe_name}_wrapper.c"));
-
-            let mut content = String::new();
-            for h in &self.headers {
-                let abs = resolve_header(h, base_dir, include_paths);
-                content.push_str(&format!("#include \"{}\"\n", abs.display()));
+            DataModel::Lp64
+        }
+    }
+
+    /// Size of a pointer (and of `size_t`) in bytes.
+    pub fn pointer_size(self) -> usize {
+        match self {
+            DataModel::Lp64 | DataModel::Llp64 => 8,
+            DataModel::Ilp32 | DataModel::Win32 => 4,
+        }
+    }
+
+    /// The config spelling, `"lp64"`, `"llp64"`, `"ilp32"` or `"win32"`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DataModel::Lp64 => "lp64",
+            DataModel::Llp64 => "llp64",
+            DataModel::Ilp32 => "ilp32",
+            DataModel::Win32 => "win32",
+        }
+    }
+
+    /// Data model implied by a target triple (e.g. `x86_64-pc-windows-msvc`).
+    pub fn from_triple(triple: &str) -> Self {
+        let arch = triple.split('-').next().unwrap_or(triple);
+        let ilp32_arch = matches!(arch, "i386" | "i486" | "i586" | "i686" | "x86")
+            || arch.ends_with("32")
+            || (arch.starts_with("arm") && !arch.starts_with("arm64"))
+            || arch.starts_with("thumb")
+            || matches!(
+                arch,
+                "mips" | "mipsel" | "powerpc" | "powerpcle" | "sparc" | "hexagon" | "m68k"
+            );
+        if triple.contains("windows") || triple.contains("mingw") {
+            if ilp32_arch {
+                DataModel::Win32
+            } else {
+                DataModel::Llp64
             }
-            std::fs::write(&wrapper_path, &content).expect("write wrapper file");
-            wrapper_path
+        } else if ilp32_arch || triple.ends_with("gnux32") {
+            DataModel::Ilp32
+        } else {
+            DataModel::Lp64
         }
     }
 }
 
-/// Resolve a header path by searching `base_dir` first, then each
-/// `include_paths` entry.  Absolute paths are returned as-is.  If the
-/// file is not found anywhere, falls back to `base_dir.join(path)` so
-/// that the caller gets a meaningful error from clang.
-pub fn resolve_header(path: &Path, base_dir: &Path, include_paths: &[PathBuf]) -> PathBuf {
-    if path.is_absolute() {
-        return path.to_path_buf();
+/// The triple of the first `--target=<triple>` / `-target <triple>` clang
+/// argument, if any.
+pub fn target_triple(clang_args: &[String]) -> Option<&str> {
+    let mut args = clang_args.iter();
+    while let Some(arg) = args.next() {
+        match arg.strip_prefix("--target=") {
+            Some(triple) => return Some(triple),
+            None if arg == "-target" || arg == "--target" => {
+                return args.next().map(|s| s.as_str());
+            }
+            None => {}
+        }
     }
-    let candidate = base_dir.join(path);
-    if candidate.exists() {
-        return candidate;
+    None
+}
+
+/// Whether plain `char` is signed for the clang arguments: an explicit
+/// `-fsigned-char` / `-funsigned-char` wins, otherwise it follows the target
+/// (or host) architecture's ABI. Arm, PowerPC, RISC-V and s390x default to
+/// unsigned `char`, except on Apple and Windows targets.
+pub fn char_is_signed(clang_args: &[String]) -> bool {
+    if let Some(flag) = clang_args
+        .iter()
+        .rev()
+        .find(|a| *a == "-fsigned-char" || *a == "-funsigned-char")
+    {
+        return flag == "-fsigned-char";
+    }
+    let (arch, os) = match target_triple(clang_args) {
+        Some(triple) => (triple.split('-').next().unwrap_or(triple), triple),
+        None => (std::env::consts::ARCH, std::env::consts::OS),
+    };
+    let unsigned_arch = ["aarch64", "arm", "powerpc", "riscv", "s390x"]
+        .iter()
+        .any(|prefix| arch.starts_with(prefix));
+    let signed_os = ["apple", "darwin", "macos", "ios", "windows"]
+        .iter()
+        .any(|name| os.contains(name));
+    !unsigned_arch || signed_os
+}
+
+/// The target triple of the host bnd-winmd runs on, e.g.
+/// `x86_64-unknown-linux-gnu`: the Rust target it was compiled for, which
+/// keeps the ABI suffix (`gnueabihf`, `powerpc64le`, `mips64el`, ...).
+pub fn host_triple() -> String {
+    let target = env!("BND_WINMD_HOST_TARGET");
+    // Rust spells RISC-V architectures with their ISA extensions
+    // (`riscv64gc`), which clang does not accept.
+    match target.split_once('-') {
+        Some((arch, rest)) if arch.starts_with("riscv") && arch.len() > 7 => {
+            format!("{}-{rest}", &arch[..7])
+        }
+        _ => target.to_string(),
     }
-    for inc in include_paths {
-        let candidate = inc.join(path);
-        if candidate.exists() {
-            return candidate;
+}
+
+/// Whether `triple` names the host's architecture and operating system.
+pub fn target_matches_host(triple: &str) -> bool {
+    let mut parts = triple.split('-');
+    let arch = match parts.next().unwrap_or_default() {
+        "amd64" => "x86_64",
+        "arm64" => "aarch64",
+        "i386" | "i486" | "i586" | "i686" => "x86",
+        arch => arch,
+    };
+    let os_names: &[&str] = match std::env::consts::OS {
+        "macos" => &["apple", "darwin", "macos"],
+        os => &[os],
+    };
+    arch == std::env::consts::ARCH && os_names.iter().any(|os| triple.contains(os))
+}
+
+/// Find a header the way a compiler finds an `#include`: try `base_dir`
+/// first, then each `include_paths` entry in order. Absolute paths are
+/// only checked for existence. The error lists every root tried.
+pub fn find_header(
+    path: &Path,
+    base_dir: &Path,
+    include_paths: &[PathBuf],
+) -> anyhow::Result<PathBuf> {
+    if path.is_absolute() {
+        if !path.exists() {
+            anyhow::bail!("header {} not found", path.display());
         }
+        return Ok(path.to_path_buf());
     }
-    // Fall back — clang will report the error with context.
-    base_dir.join(path)
+    let roots: Vec<&Path> = std::iter::once(base_dir)
+        .chain(include_paths.iter().map(PathBuf::as_path))
+        .collect();
+    if let Some(found) = roots
+        .iter()
+        .map(|root| root.join(path))
+        .find(|c| c.exists())
+    {
+        return Ok(found);
+    }
+    let tried: Vec<String> = roots
+        .iter()
+        .map(|root| root.display().to_string())
+        .collect();
+    anyhow::bail!(
+        "header {} not found in any of: {} (check `headers` and `include_paths`)",
+        path.display(),
+        tried.join(", ")
+    )
+}
+
+/// [`find_header`], falling back to `base_dir.join(path)` when the file is
+/// not found anywhere, so that the caller gets a meaningful error from
+/// clang or the filesystem.
+pub fn resolve_header(path: &Path, base_dir: &Path, include_paths: &[PathBuf]) -> PathBuf {
+    find_header(path, base_dir, include_paths).unwrap_or_else(|_| base_dir.join(path))
 }
 
 /// External winmd type imports (cross-winmd references).
 ///
 /// Pre-seeds the `TypeRegistry` with types from an external winmd so that
 /// `ctype_to_wintype()` emits TypeRef rows instead of falling back to the
-/// resolved canonical type.
+/// resolved canonical type. Any number of imports may be listed; they are
+/// all loaded.
 ///
 /// ```toml
 /// [[type_import]]
 /// winmd = "../bnd-posix/winmd/bnd-posix.winmd"
 /// namespace = "posix"
+/// assembly = "posix"
+/// version = "1.0.0.0"
 /// ```
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct TypeImportConfig {
     /// Path to the external `.winmd` file (resolved relative to the TOML
     /// file's directory, i.e. `base_dir`).
@@ -133,13 +835,220 @@ pub struct TypeImportConfig {
     /// Root namespace filter — only types under this namespace tree are
     /// imported into the registry.
     pub namespace: String,
+    /// How `namespace` is matched against each type's namespace.
+    #[serde(default)]
+    pub namespace_match: NamespaceMatch,
+    /// Expected assembly name of the winmd. Import fails if it differs.
+    #[serde(default)]
+    pub assembly: Option<String>,
+    /// Expected assembly version of the winmd (see `[output] version`).
+    /// Import fails if it differs.
+    #[serde(default)]
+    pub version: Option<AssemblyVersion>,
+}
+
+impl TypeImportConfig {
+    /// Returns true if types in `namespace` should be imported.
+    pub fn matches_namespace(&self, namespace: &str) -> bool {
+        match self.namespace_match {
+            NamespaceMatch::Prefix => namespace.starts_with(&self.namespace),
+            NamespaceMatch::Exact => namespace == self.namespace,
+        }
+    }
+}
+
+/// How a `[[type_import]]` namespace filter is matched.
+///
+/// ```toml
+/// namespace_match = "exact"
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NamespaceMatch {
+    /// The type's namespace starts with the filter (`posix` matches
+    /// `posix.stat`).
+    #[default]
+    Prefix,
+    /// The type's namespace equals the filter.
+    Exact,
+}
+
+/// Build the config of a single-partition run over one `header`, as if
+/// from a minimal `bnd-winmd.toml`.
+///
+/// `namespace` and `library` default to the header's file stem (with
+/// characters other than ASCII alphanumerics and `_` replaced by `_`); the
+/// output is named after the namespace and written to `<namespace>.winmd`.
+pub fn header_config(
+    header: &Path,
+    namespace: Option<&str>,
+    library: Option<&str>,
+) -> anyhow::Result<Config> {
+    let stem: String = header
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .with_context(|| format!("header {} has no file name", header.display()))?
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    let namespace = namespace.unwrap_or(&stem);
+    let library = library.unwrap_or(&stem);
+    let header = toml::Value::from(header.to_string_lossy().into_owned());
+
+    let mut output = toml::Table::new();
+    output.insert("name".into(), namespace.into());
+    output.insert("file".into(), format!("{namespace}.winmd").into());
+    let mut partition = toml::Table::new();
+    partition.insert("namespace".into(), namespace.into());
+    partition.insert("library".into(), library.into());
+    partition.insert("headers".into(), vec![header.clone()].into());
+    partition.insert("traverse".into(), vec![header].into());
+
+    let mut config = toml::Table::new();
+    config.insert("output".into(), output.into());
+    config.insert(
+        "partition".into(),
+        vec![toml::Value::from(partition)].into(),
+    );
+    config
+        .try_into()
+        .context("failed to build a config for the header")
 }
 
 /// Load and parse a `bnd-winmd.toml` configuration file.
 pub fn load_config(path: &Path) -> anyhow::Result<Config> {
     let content = std::fs::read_to_string(path)
         .map_err(|e| anyhow::anyhow!("failed to read config file {}: {}", path.display(), e))?;
-    let config: Config = toml::from_str(&content)
+    let mut config: Config = toml::from_str(&content)
         .map_err(|e| anyhow::anyhow!("failed to parse config file {}: {}", path.display(), e))?;
+    if let Some(target) = &config.target {
+        for partition in &mut config.partition {
+            match target_triple(&partition.clang_args) {
+                None => partition.clang_args.push(format!("--target={target}")),
+                Some(own) if own != target => warn!(
+                    namespace = %partition.namespace,
+                    target = %own,
+                    "partition overrides the top-level target"
+                ),
+                Some(_) => {}
+            }
+        }
+    }
+    if config.output.is_empty() {
+        anyhow::bail!("config file {} has no `[output]`", path.display());
+    }
+    for (i, output) in config.output.iter().enumerate() {
+        if let Some(other) = config.output[..i]
+            .iter()
+            .find(|o| o.name == output.name || o.file == output.file)
+        {
+            anyhow::bail!(
+                "config file {}: outputs `{}` and `{}` share a name or file",
+                path.display(),
+                other.name,
+                output.name
+            );
+        }
+    }
     Ok(config)
 }
+
+/// A problem [`validate_config`] found in a configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    pub message: String,
+}
+
+impl std::fmt::Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// Check a loaded config without running clang: every `headers` /
+/// `traverse` file, `pch` and `[[type_import]]` winmd resolves, partitions
+/// sharing a namespace link the same library, and each
+/// `namespace_overrides` name occurs in some partition's `headers` or
+/// `traverse` files (includes are not followed). Returns the problems
+/// found; invalid `include_symbols` / `exclude_symbols` patterns are an
+/// error.
+pub fn validate_config(cfg: &Config, base_dir: &Path) -> anyhow::Result<Vec<Warning>> {
+    let mut warnings = Vec::new();
+    let mut warn = |message: String| warnings.push(Warning { message });
+
+    let mut header_text = String::new();
+    for (i, partition) in cfg.partition.iter().enumerate() {
+        partition.symbol_filter()?;
+        let search = partition.search_paths(base_dir, &cfg.include_paths);
+        for (list, paths) in [
+            ("headers", &partition.headers),
+            ("traverse", &partition.traverse),
+        ] {
+            for path in paths {
+                let resolved = resolve_header(path, base_dir, &search);
+                match std::fs::read_to_string(&resolved) {
+                    Ok(text) => header_text.push_str(&text),
+                    Err(_) => warn(format!(
+                        "partition `{}`: {list} entry `{}` not found (looked for {})",
+                        partition.namespace,
+                        path.display(),
+                        resolved.display()
+                    )),
+                }
+            }
+        }
+        if let Some(other) = cfg.partition[..i]
+            .iter()
+            .find(|other| other.namespace == partition.namespace)
+            && other.library != partition.library
+        {
+            warn(format!(
+                "namespace `{}` is shared by partitions linking `{}` and `{}`; \
+                 their functions can't be merged into one `Apis`",
+                partition.namespace, other.library, partition.library
+            ));
+        }
+        if let Some(pch) = &partition.pch
+            && !base_dir.join(pch).is_file()
+        {
+            warn(format!(
+                "partition `{}`: pch `{}` not found",
+                partition.namespace,
+                pch.display()
+            ));
+        }
+    }
+
+    for import in &cfg.type_import {
+        let resolved = resolve_header(&import.winmd, base_dir, &cfg.include_paths);
+        if !resolved.is_file() {
+            warn(format!(
+                "type_import winmd `{}` not found (looked for {})",
+                import.winmd.display(),
+                resolved.display()
+            ));
+        }
+    }
+
+    let mut overrides: Vec<&String> = cfg.namespace_overrides.keys().collect();
+    overrides.sort();
+    for name in overrides {
+        if !contains_identifier(&header_text, name) {
+            warn(format!(
+                "namespace_overrides: `{name}` does not occur in any partition's headers"
+            ));
+        }
+    }
+
+    Ok(warnings)
+}
+
+/// Whether `text` contains `name` as a whole C identifier.
+fn contains_identifier(text: &str, name: &str) -> bool {
+    let is_ident = |c: char| c == '_' || c.is_ascii_alphanumeric();
+    text.match_indices(name).any(|(start, _)| {
+        let before = text[..start].chars().next_back();
+        let after = text[start + name.len()..].chars().next();
+        !before.is_some_and(is_ident) && !after.is_some_and(is_ident)
+    })
+}
@@ -1,51 +1,219 @@
 //! Emitter — model types → `windows-metadata` writer calls → winmd bytes.
 
+use std::collections::{BTreeMap, BTreeSet};
+
 use anyhow::Result;
-use tracing::debug;
+use tracing::{debug, debug_span, warn};
 use windows_metadata::{
     FieldAttributes, MethodAttributes, MethodCallAttributes, MethodImplAttributes,
     PInvokeAttributes, ParamAttributes, Signature, Type, TypeAttributes, Value,
-    writer::{File, HasConstant, MemberRefParent, TypeDefOrRef},
+    writer::{AttributeType, File, HasAttribute, HasConstant, MemberRefParent, TypeDefOrRef},
 };
 
+use crate::config::DataModel;
+use crate::metadata::{self, AssemblyIdentity};
 use crate::model::*;
 
+/// Optional attributes and classes [`emit_winmd`] writes.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EmitOptions<'a> {
+    /// Extracted doc comments, as `DocumentationAttribute`s.
+    pub docs: bool,
+    /// Declaration sites, as `SourceLocationAttribute(file, line)`s.
+    pub source_locations: bool,
+    /// Target triple and data model the layouts were computed for, recorded
+    /// as a `TargetAttribute` (see [`TARGET_TYPE`]).
+    pub target: Option<(&'a str, DataModel)>,
+    /// Emit an `Apis` class even for partitions with no functions or
+    /// constants.
+    pub empty_apis: bool,
+}
+
+/// Name of the member-less class carrying the `TargetAttribute`, in the
+/// namespace of the assembly's first partition. ECMA-335 has an `Assembly`
+/// attribute parent, but the writer doesn't support it. windows-bindgen
+/// ignores classes other than `Apis`.
+pub const TARGET_TYPE: &str = "<Target>";
+
+/// Name of the class holding a partition's `extern` globals as static
+/// fields, each with a `DataImportAttribute(library, symbol)`. ImplMap
+/// rows can only forward methods, and windows-bindgen ignores this class.
+pub const GLOBALS_TYPE: &str = "Globals";
+
 /// Emit all partitions into a single winmd byte stream.
+///
+/// `options` selects the optional attributes. `TypeRef`s to types imported
+/// from another winmd are scoped to an `AssemblyRef` carrying that winmd's
+/// assembly name and version.
 pub fn emit_winmd(
     assembly_name: &str,
     partitions: &[Partition],
     registry: &TypeRegistry,
+    options: EmitOptions,
 ) -> Result<Vec<u8>> {
     let mut file = File::new(assembly_name);
 
     for partition in partitions {
-        emit_partition(&mut file, partition, registry)?;
+        emit_partition(&mut file, partition, registry, options)?;
+    }
+    if let (Some((triple, data_model)), Some(first)) = (options.target, partitions.first()) {
+        emit_target(&mut file, &first.namespace, triple, data_model);
     }
 
-    Ok(file.into_stream())
+    let mut bytes = file.into_stream();
+    scope_imported_type_refs(&mut bytes, partitions, registry)?;
+    set_string_charsets(&mut bytes, partitions)?;
+    debug!(
+        assembly = assembly_name,
+        partitions = partitions.len(),
+        bytes = bytes.len(),
+        "emitted winmd"
+    );
+    Ok(bytes)
+}
+
+/// Record each function's [`CharSet`] in its ImplMap row.
+///
+/// `PInvokeAttributes` has no charset constants, so the bits are patched
+/// into the written bytes. Every function gets exactly one ImplMap row, in
+/// emission order, so rows line up with the partitions' functions.
+fn set_string_charsets(bytes: &mut [u8], partitions: &[Partition]) -> Result<()> {
+    let flags: Vec<u16> = partitions
+        .iter()
+        .flat_map(|p| &p.functions)
+        .map(|f| match f.charset {
+            Some(CharSet::Ansi) => metadata::CHARSET_ANSI,
+            Some(CharSet::Unicode) => metadata::CHARSET_UNICODE,
+            None => 0,
+        })
+        .collect();
+    if flags.iter().all(|&f| f == 0) {
+        return Ok(());
+    }
+    metadata::add_impl_map_flags(bytes, &flags)
+}
+
+/// Point the `AssemblyRef`s of imported types at their source assembly.
+///
+/// The writer scopes every `TypeRef` to a synthetic `AssemblyRef` named
+/// after the type's root namespace (`posix.time.tm` → `posix`, version
+/// `255.255.255.255`), and offers no way to choose it. Types imported from
+/// the same root namespace therefore share one `AssemblyRef`, which is
+/// patched to the identity recorded by `[[type_import]]`.
+fn scope_imported_type_refs(
+    bytes: &mut [u8],
+    partitions: &[Partition],
+    registry: &TypeRegistry,
+) -> Result<()> {
+    let mut scopes: BTreeMap<&str, BTreeSet<&AssemblyIdentity>> = BTreeMap::new();
+    for (name, assembly) in &registry.imported {
+        if let Some(namespace) = registry.types.get(name) {
+            let root = namespace.split('.').next().unwrap_or(namespace);
+            scopes.entry(root).or_default().insert(assembly);
+        }
+    }
+
+    for (root, assemblies) in scopes {
+        // Sorted, so the choice is stable across runs.
+        let mut assemblies = assemblies.into_iter();
+        let Some(assembly) = assemblies.next() else {
+            continue;
+        };
+        for ignored in assemblies {
+            warn!(
+                namespace = root,
+                kept = %assembly.name,
+                ignored = %ignored.name,
+                "types from two assemblies share a root namespace; \
+                 their TypeRefs can only be scoped to one"
+            );
+        }
+        // No AssemblyRef means no type from this import was referenced.
+        if !crate::metadata::retarget_assembly_ref(bytes, root, assembly)? {
+            continue;
+        }
+        if partitions
+            .iter()
+            .any(|p| p.namespace.split('.').next() == Some(root))
+        {
+            warn!(
+                namespace = root,
+                assembly = %assembly.name,
+                "local partitions share the root namespace of an imported assembly; \
+                 their cross-partition TypeRefs are scoped to that assembly too"
+            );
+        }
+        debug!(
+            namespace = root,
+            assembly = %assembly.name,
+            version = %assembly.version,
+            "scoped imported TypeRefs"
+        );
+    }
+    Ok(())
+}
+
+/// Stamp the target the winmd was generated for as
+/// `TargetAttribute(triple, data_model)` on a [`TARGET_TYPE`] class.
+fn emit_target(file: &mut File, namespace: &str, triple: &str, data_model: DataModel) {
+    let object_ref = file.TypeRef("System", "Object");
+    let td = file.TypeDef(
+        namespace,
+        TARGET_TYPE,
+        TypeDefOrRef::TypeRef(object_ref),
+        TypeAttributes::Abstract | TypeAttributes::Sealed,
+    );
+    emit_attribute(
+        file,
+        HasAttribute::TypeDef(td),
+        "Windows.Win32.Foundation.Metadata",
+        "TargetAttribute",
+        &[
+            (String::new(), Value::Utf8(triple.to_string())),
+            (String::new(), Value::Utf8(data_model.as_str().to_string())),
+        ],
+    );
+    debug!(
+        namespace,
+        triple,
+        data_model = data_model.as_str(),
+        "stamped target"
+    );
 }
 
 /// Emit a single partition's declarations into the writer.
-fn emit_partition(file: &mut File, partition: &Partition, registry: &TypeRegistry) -> Result<()> {
+fn emit_partition(
+    file: &mut File,
+    partition: &Partition,
+    registry: &TypeRegistry,
+    options: EmitOptions,
+) -> Result<()> {
     let ns = &partition.namespace;
+    // One span per partition and per TypeDef / method, so the layout
+    // decisions logged below can be traced back to their declaration.
+    let _partition = debug_span!("partition", namespace = %ns).entered();
 
     // Emit enums
     for en in &partition.enums {
-        emit_enum(file, ns, en)?;
+        let _span = debug_span!("enum", name = %en.name).entered();
+        emit_enum(file, ns, en, partition.data_model, options)?;
     }
 
     // Emit structs
     for s in &partition.structs {
-        emit_struct(file, ns, s, registry)?;
+        let _span = debug_span!("struct", name = %s.name).entered();
+        emit_struct(file, ns, s, registry, options)?;
     }
 
     // Emit typedefs
     for td in &partition.typedefs {
-        emit_typedef(file, ns, td, registry)?;
+        let _span = debug_span!("typedef", name = %td.name).entered();
+        emit_typedef(file, ns, td, registry, options)?;
     }
 
-    // Emit functions (P/Invoke) — all go under a single "Apis" TypeDef
-    if !partition.functions.is_empty() || !partition.constants.is_empty() {
+    // Emit functions (P/Invoke) — all go under a single "Apis" TypeDef,
+    // which a types-only partition leaves out unless asked for.
+    if options.empty_apis || !partition.functions.is_empty() || !partition.constants.is_empty() {
         let object_ref = file.TypeRef("System", "Object");
         let _apis_td = file.TypeDef(
             ns,
@@ -55,12 +223,32 @@ fn emit_partition(file: &mut File, partition: &Partition, registry: &TypeRegistr
         );
 
         for f in &partition.functions {
-            emit_function(file, ns, f, &partition.library, registry)?;
+            let _span = debug_span!("method", name = %f.name).entered();
+            emit_function(file, ns, f, &partition.library, registry, options)?;
         }
 
         // Emit #define constants as static literal fields on the Apis class
         for c in &partition.constants {
-            emit_constant(file, c)?;
+            emit_constant(file, c, partition.data_model, options)?;
+        }
+    }
+
+    if !partition.variables.is_empty() {
+        if partition.type_names().any(|name| name == GLOBALS_TYPE) {
+            anyhow::bail!(
+                "namespace `{ns}` declares a type named `{GLOBALS_TYPE}`, which would \
+                 clash with the class holding its globals"
+            );
+        }
+        let object_ref = file.TypeRef("System", "Object");
+        file.TypeDef(
+            ns,
+            GLOBALS_TYPE,
+            TypeDefOrRef::TypeRef(object_ref),
+            TypeAttributes::Public | TypeAttributes::Abstract | TypeAttributes::Sealed,
+        );
+        for g in &partition.variables {
+            emit_variable(file, ns, g, &partition.library, registry, options);
         }
     }
 
@@ -71,17 +259,42 @@ fn emit_partition(file: &mut File, partition: &Partition, registry: &TypeRegistr
 // Enum emission
 // ---------------------------------------------------------------------------
 
-fn emit_enum(file: &mut File, namespace: &str, en: &EnumDef) -> Result<()> {
-    let underlying_wintype =
-        ctype_to_wintype(&en.underlying_type, namespace, &TypeRegistry::default());
+fn emit_enum(
+    file: &mut File,
+    namespace: &str,
+    en: &EnumDef,
+    dm: DataModel,
+    options: EmitOptions,
+) -> Result<()> {
+    let storage = enum_storage_type(&en.underlying_type, dm);
+    let underlying_wintype = ctype_to_wintype(&storage, namespace, &TypeRegistry::default());
 
     let enum_ref = file.TypeRef("System", "Enum");
-    let _td = file.TypeDef(
+    let td = file.TypeDef(
         namespace,
         &en.name,
         TypeDefOrRef::TypeRef(enum_ref),
         TypeAttributes::Public | TypeAttributes::Sealed,
     );
+    if en.is_flags {
+        emit_attribute(
+            file,
+            HasAttribute::TypeDef(td),
+            "System",
+            "FlagsAttribute",
+            &[],
+        );
+    }
+    if options.docs
+        && let Some(doc) = &en.doc
+    {
+        emit_documentation(file, HasAttribute::TypeDef(td), doc);
+    }
+    if options.source_locations
+        && let Some(source) = &en.source
+    {
+        emit_source_location(file, HasAttribute::TypeDef(td), source);
+    }
 
     // value__ field (the underlying storage)
     file.Field(
@@ -97,26 +310,61 @@ fn emit_enum(file: &mut File, namespace: &str, en: &EnumDef) -> Result<()> {
             &underlying_wintype,
             FieldAttributes::Public | FieldAttributes::Static | FieldAttributes::Literal,
         );
-        let value = constant_value_for_enum(&en.underlying_type, variant);
+        let value =
+            constant_value_for_enum(&storage, variant.signed_value, variant.unsigned_value, dm);
         file.Constant(HasConstant::Field(field), &value);
     }
 
-    debug!(name = %en.name, variants = en.variants.len(), "emitted enum");
+    debug!(name = %en.name, variants = en.variants.len(), flags = en.is_flags, "emitted enum");
     Ok(())
 }
 
-/// Convert an enum variant to a `Value` matching the underlying type.
-fn constant_value_for_enum(underlying: &CType, variant: &EnumVariant) -> Value {
+/// The ECMA-335 integer an enum with base type `underlying` is stored as.
+/// Typedef'd bases resolve to their integer, and `bool` / char-like bases
+/// to the unsigned integer of their size, and pointer-sized bases to the
+/// integer of `dm`'s pointer width. Both `value__` and the variant literals
+/// use this type, so they always agree on signedness.
+fn enum_storage_type(underlying: &CType, dm: DataModel) -> CType {
     match underlying {
-        CType::I8 => Value::I8(variant.signed_value as i8),
-        CType::U8 => Value::U8(variant.unsigned_value as u8),
-        CType::I16 => Value::I16(variant.signed_value as i16),
-        CType::U16 => Value::U16(variant.unsigned_value as u16),
-        CType::I32 => Value::I32(variant.signed_value as i32),
-        CType::U32 => Value::U32(variant.unsigned_value as u32),
-        CType::I64 => Value::I64(variant.signed_value),
-        CType::U64 => Value::U64(variant.unsigned_value),
-        _ => Value::I32(variant.signed_value as i32),
+        CType::I8
+        | CType::U8
+        | CType::I16
+        | CType::U16
+        | CType::I32
+        | CType::U32
+        | CType::I64
+        | CType::U64 => underlying.clone(),
+        CType::Bool => CType::U8,
+        CType::Char16 => CType::U16,
+        CType::Char32 => CType::U32,
+        CType::ISize if dm.pointer_size() == 4 => CType::I32,
+        CType::USize if dm.pointer_size() == 4 => CType::U32,
+        CType::ISize => CType::I64,
+        CType::USize => CType::U64,
+        CType::Named {
+            resolved: Some(resolved),
+            ..
+        } => enum_storage_type(resolved, dm),
+        other => {
+            warn!(underlying = ?other, "enum base type is not an integer, storing as i32");
+            CType::I32
+        }
+    }
+}
+
+/// Convert an enum variant's (signed, unsigned) value pair to a `Value`
+/// of the enum's storage type: the unsigned interpretation for unsigned
+/// bases, the signed one otherwise.
+fn constant_value_for_enum(underlying: &CType, signed: i64, unsigned: u64, dm: DataModel) -> Value {
+    match enum_storage_type(underlying, dm) {
+        CType::I8 => Value::I8(signed as i8),
+        CType::U8 => Value::U8(unsigned as u8),
+        CType::I16 => Value::I16(signed as i16),
+        CType::U16 => Value::U16(unsigned as u16),
+        CType::U32 => Value::U32(unsigned as u32),
+        CType::I64 => Value::I64(signed),
+        CType::U64 => Value::U64(unsigned),
+        _ => Value::I32(signed as i32),
     }
 }
 
@@ -129,12 +377,13 @@ fn emit_struct(
     namespace: &str,
     s: &StructDef,
     registry: &TypeRegistry,
+    options: EmitOptions,
 ) -> Result<()> {
     let valuetype_ref = file.TypeRef("System", "ValueType");
-    let layout_attr = if s.is_union {
-        TypeAttributes::ExplicitLayout
+    let (layout_attr, layout) = if s.is_union {
+        (TypeAttributes::ExplicitLayout, "explicit")
     } else {
-        TypeAttributes::SequentialLayout
+        (TypeAttributes::SequentialLayout, "sequential")
     };
     let td = file.TypeDef(
         namespace,
@@ -142,15 +391,71 @@ fn emit_struct(
         TypeDefOrRef::TypeRef(valuetype_ref),
         TypeAttributes::Public | layout_attr,
     );
-    file.ClassLayout(td, s.align as u16, s.size as u32);
+    let packing = packing_size(&s.name, s.align);
+    file.ClassLayout(td, packing, s.size as u32);
+    // ClassLayout's packing is capped, so the C alignment is only here.
+    // A size of 0 means clang couldn't lay the record out.
+    if s.size > 0 {
+        emit_attribute(
+            file,
+            HasAttribute::TypeDef(td),
+            "Windows.Win32.Foundation.Metadata",
+            "NativeStructSizeAttribute",
+            &[
+                (String::new(), Value::I64(s.size as i64)),
+                (String::new(), Value::I64(s.align as i64)),
+            ],
+        );
+    }
+    if options.docs
+        && let Some(doc) = &s.doc
+    {
+        emit_documentation(file, HasAttribute::TypeDef(td), doc);
+    }
+    if options.source_locations
+        && let Some(source) = &s.source
+    {
+        emit_source_location(file, HasAttribute::TypeDef(td), source);
+    }
+    if let Some(message) = &s.deprecated {
+        emit_obsolete(file, HasAttribute::TypeDef(td), message);
+    }
 
     for field in &s.fields {
         let wintype = ctype_to_wintype(&field.ty, namespace, registry);
-        file.Field(&field.name, &wintype, FieldAttributes::Public);
-        // TODO: emit NativeBitfieldAttribute for bitfield fields
+        let field_id = file.Field(&field.name, &wintype, FieldAttributes::Public);
+        // ExplicitLayout requires a FieldLayout row per field; union members
+        // all overlap at offset 0.
+        if s.is_union {
+            file.FieldLayout(field_id, 0);
+        }
+        if let Some(c_name) = field.ty.wide_char_name() {
+            emit_native_type_name(file, HasAttribute::Field(field_id), c_name);
+        }
+        for bitfield in &field.bitfields {
+            emit_attribute(
+                file,
+                HasAttribute::Field(field_id),
+                "Windows.Win32.Foundation.Metadata",
+                "NativeBitfieldAttribute",
+                &[
+                    (String::new(), Value::Utf8(bitfield.name.clone())),
+                    (String::new(), Value::I64(bitfield.offset as i64)),
+                    (String::new(), Value::I64(bitfield.width as i64)),
+                ],
+            );
+        }
     }
 
-    debug!(name = %s.name, fields = s.fields.len(), size = s.size, "emitted struct");
+    debug!(
+        name = %s.name,
+        fields = s.fields.len(),
+        size = s.size,
+        align = s.align,
+        layout,
+        packing,
+        "emitted struct"
+    );
     Ok(())
 }
 
@@ -163,34 +468,23 @@ fn emit_typedef(
     namespace: &str,
     td: &TypedefDef,
     registry: &TypeRegistry,
+    options: EmitOptions,
 ) -> Result<()> {
-    // Check if the typedef is wrapping a function pointer → emit as delegate
-    // In C, function pointer typedefs are `typedef ret (*Name)(...)` which maps to
-    // Ptr { pointee: FnPtr { ... } }. Also handle direct FnPtr.
-    let fnptr = match &td.underlying_type {
-        CType::FnPtr {
-            return_type,
-            params,
-            calling_convention: _,
-        } => Some((return_type.as_ref(), params.as_slice())),
-        CType::Ptr { pointee, .. } => match pointee.as_ref() {
-            CType::FnPtr {
-                return_type,
-                params,
-                calling_convention: _,
-            } => Some((return_type.as_ref(), params.as_slice())),
-            _ => None,
-        },
-        _ => None,
-    };
-    if let Some((return_type, params)) = fnptr {
-        emit_delegate(file, namespace, &td.name, return_type, params, registry)?;
+    // A typedef naming a callback → emit as delegate. See delegate_signature
+    // for the spellings recognised.
+    if let Some((return_type, params)) = delegate_signature(&td.underlying_type) {
+        let delegate_td = emit_delegate(file, namespace, &td.name, return_type, params, registry)?;
+        if options.source_locations
+            && let Some(source) = &td.source
+        {
+            emit_source_location(file, HasAttribute::TypeDef(delegate_td), source);
+        }
         return Ok(());
     }
 
     // Otherwise emit as a struct wrapper with NativeTypedefAttribute
     let valuetype_ref = file.TypeRef("System", "ValueType");
-    let _td = file.TypeDef(
+    let typedef_td = file.TypeDef(
         namespace,
         &td.name,
         TypeDefOrRef::TypeRef(valuetype_ref),
@@ -204,21 +498,37 @@ fn emit_typedef(
         CType::Void => Type::ISize,
         other => ctype_to_wintype(other, namespace, registry),
     };
+    // An array typedef is a fixed buffer: pin its size like a struct's so
+    // the wrapper is exactly as large as the C array.
+    if let Some((size, align)) = td.array_layout {
+        file.ClassLayout(typedef_td, packing_size(&td.name, align), size as u32);
+    }
     file.Field("Value", &wintype, FieldAttributes::Public);
 
-    // Add NativeTypedefAttribute custom attribute
-    // We need a MemberRef to the attribute constructor
-    let attr_typeref = file.TypeRef(
+    emit_attribute(
+        file,
+        HasAttribute::TypeDef(typedef_td),
         "Windows.Win32.Foundation.Metadata",
         "NativeTypedefAttribute",
+        &[],
     );
-    let _attr_ctor = file.MemberRef(
-        ".ctor",
-        &Signature::default(),
-        MemberRefParent::TypeRef(attr_typeref),
-    );
+    // windows-bindgen implements `Free` for handles carrying this.
+    if let Some(free) = &td.free_with {
+        emit_attribute(
+            file,
+            HasAttribute::TypeDef(typedef_td),
+            "Windows.Win32.Foundation.Metadata",
+            "RAIIFreeAttribute",
+            &[(String::new(), Value::Utf8(free.clone()))],
+        );
+    }
+    if options.source_locations
+        && let Some(source) = &td.source
+    {
+        emit_source_location(file, HasAttribute::TypeDef(typedef_td), source);
+    }
 
-    debug!(name = %td.name, "emitted typedef");
+    debug!(name = %td.name, value = ?wintype, "emitted typedef");
     Ok(())
 }
 
@@ -226,6 +536,38 @@ fn emit_typedef(
 // Delegate (function pointer) emission
 // ---------------------------------------------------------------------------
 
+/// The `Invoke` signature of a typedef whose underlying type is a callback:
+/// a function pointer (`typedef int (*cb)(void *)`), a function type used
+/// through pointers (`typedef int cb(void *)`, as in OpenSSL's
+/// `pem_password_cb`), a pointer to such a function type, or an alias of
+/// any of these.
+fn delegate_signature(ty: &CType) -> Option<(&CType, &[CType])> {
+    match ty {
+        CType::Ptr { pointee, .. } => function_type(pointee),
+        CType::Named {
+            resolved: Some(resolved),
+            ..
+        } => delegate_signature(resolved),
+        other => function_type(other),
+    }
+}
+
+/// The signature of a function type, looking through typedefs of it.
+fn function_type(ty: &CType) -> Option<(&CType, &[CType])> {
+    match ty {
+        CType::FnPtr {
+            return_type,
+            params,
+            calling_convention: _,
+        } => Some((return_type, params)),
+        CType::Named {
+            resolved: Some(resolved),
+            ..
+        } => function_type(resolved),
+        _ => None,
+    }
+}
+
 fn emit_delegate(
     file: &mut File,
     namespace: &str,
@@ -233,9 +575,9 @@ fn emit_delegate(
     return_type: &CType,
     params: &[CType],
     registry: &TypeRegistry,
-) -> Result<()> {
+) -> Result<windows_metadata::writer::TypeDef> {
     let delegate_ref = file.TypeRef("System", "MulticastDelegate");
-    let _td = file.TypeDef(
+    let td = file.TypeDef(
         namespace,
         name,
         TypeDefOrRef::TypeRef(delegate_ref),
@@ -275,7 +617,25 @@ fn emit_delegate(
     }
 
     debug!(name, params = params.len(), "emitted delegate");
-    Ok(())
+    Ok(td)
+}
+
+/// ClassLayout packing for a struct with C alignment `align`.
+///
+/// windows-bindgen turns the packing into `#[repr(C, packed(N))]`, so a
+/// `#pragma pack(1)` struct keeps its alignment of 1 and is not re-padded.
+/// ECMA-335 only allows powers of two up to 128; anything else (including
+/// an unknown alignment of 0) falls back to the natural packing of 8.
+fn packing_size(name: &str, align: usize) -> u16 {
+    if align.is_power_of_two() && align <= 128 {
+        align as u16
+    } else {
+        warn!(
+            name,
+            align, "alignment not expressible as winmd packing, using 8"
+        );
+        8
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -288,6 +648,7 @@ fn emit_function(
     f: &FunctionDef,
     library: &str,
     registry: &TypeRegistry,
+    options: EmitOptions,
 ) -> Result<()> {
     let ret_wintype = ctype_to_wintype(&f.return_type, namespace, registry);
     let param_wintypes: Vec<Type> = f
@@ -296,17 +657,24 @@ fn emit_function(
         .map(|p| ctype_to_wintype(&p.ty, namespace, registry))
         .collect();
 
+    // Variadic functions keep their fixed params and carry the VARARG
+    // calling convention; windows-bindgen appends `...` in sys mode.
+    let flags = if f.is_variadic {
+        MethodCallAttributes::VARARG
+    } else {
+        MethodCallAttributes::default()
+    };
     let sig = Signature {
-        flags: MethodCallAttributes::default(),
+        flags,
         return_type: ret_wintype,
         types: param_wintypes,
     };
 
-    let pinvoke_flags = match f.calling_convention {
-        CallConv::Cdecl => PInvokeAttributes::CallConvCdecl,
-        CallConv::Stdcall => PInvokeAttributes::CallConvPlatformapi,
-        CallConv::Fastcall => PInvokeAttributes::CallConvPlatformapi,
-    };
+    // The charset bits are patched in afterwards, see set_string_charsets().
+    let mut pinvoke_flags = pinvoke_calling_convention(&f.name, f.calling_convention);
+    if f.sets_last_error {
+        pinvoke_flags |= PInvokeAttributes::SupportsLastError;
+    }
 
     let method = file.MethodDef(
         &f.name,
@@ -314,32 +682,190 @@ fn emit_function(
         MethodAttributes::Public | MethodAttributes::HideBySig,
         MethodImplAttributes::PreserveSig,
     );
-    file.ImplMap(method, pinvoke_flags, &f.name, library);
+    let import_name = f.import_name.as_deref().unwrap_or(&f.name);
+    // As in win32metadata, functions without a symbol import from the
+    // pseudo-library `FORCEINLINE`, which windows-bindgen skips.
+    let library = if f.inline {
+        "FORCEINLINE"
+    } else {
+        f.library.as_deref().unwrap_or(library)
+    };
+    file.ImplMap(method, pinvoke_flags, import_name, library);
+    if options.docs
+        && let Some(doc) = &f.doc
+    {
+        emit_documentation(file, HasAttribute::MethodDef(method), doc);
+    }
+    if options.source_locations
+        && let Some(source) = &f.source
+    {
+        emit_source_location(file, HasAttribute::MethodDef(method), source);
+    }
+    if let Some(message) = &f.deprecated {
+        emit_obsolete(file, HasAttribute::MethodDef(method), message);
+    }
+    if f.inline {
+        emit_attribute(
+            file,
+            HasAttribute::MethodDef(method),
+            "Windows.Win32.Foundation.Metadata",
+            "InlineFunctionAttribute",
+            &[],
+        );
+    }
+    if let Some(free) = &f.free_with {
+        emit_attribute(
+            file,
+            HasAttribute::MethodDef(method),
+            "Windows.Win32.Foundation.Metadata",
+            "FreeWithAttribute",
+            &[(String::new(), Value::Utf8(free.clone()))],
+        );
+    }
 
     for (i, param) in f.params.iter().enumerate() {
-        // windows-bindgen treats non-Out parameters as input and applies
-        // to_const_ptr(), converting PtrMut → PtrConst → `*const`.
-        // Set ParamAttributes::Out on mutable pointer params so that
-        // windows-bindgen preserves `*mut` in the generated Rust.
-        let attrs = if param.ty.is_outer_ptr_mut() {
-            ParamAttributes::Out
-        } else {
-            ParamAttributes::default()
+        let direction = param
+            .direction
+            .or_else(|| infer_param_direction(f, i, param));
+        let attrs = match direction {
+            Some(ParamDirection::In) => ParamAttributes::In,
+            Some(ParamDirection::Out | ParamDirection::RetVal) => ParamAttributes::Out,
+            Some(ParamDirection::InOut) => ParamAttributes::In | ParamAttributes::Out,
+            None => ParamAttributes::default(),
         };
-        file.Param(&param.name, (i + 1) as u16, attrs);
+        let param_id = file.Param(&param.name, (i + 1) as u16, attrs);
+        if param.ty.is_outer_ptr_const() {
+            emit_attribute(
+                file,
+                HasAttribute::Param(param_id),
+                "Windows.Win32.Foundation.Metadata",
+                "ConstAttribute",
+                &[],
+            );
+        }
+        if direction == Some(ParamDirection::RetVal) {
+            emit_attribute(
+                file,
+                HasAttribute::Param(param_id),
+                "Windows.Win32.Foundation.Metadata",
+                "RetValAttribute",
+                &[],
+            );
+        }
+        if let Some(len) = param.array_len {
+            emit_attribute(
+                file,
+                HasAttribute::Param(param_id),
+                "Windows.Win32.Foundation.Metadata",
+                "NativeArrayInfoAttribute",
+                &[("CountParamIndex".to_string(), Value::I16(len as i16))],
+            );
+        }
+        // The full C spelling also names any wide char type it contains.
+        if let Some(spelling) = &param.native_type {
+            emit_native_type_name(file, HasAttribute::Param(param_id), spelling);
+        } else if let Some(c_name) = param.ty.wide_char_name() {
+            emit_native_type_name(file, HasAttribute::Param(param_id), c_name);
+        }
     }
 
-    debug!(name = %f.name, params = f.params.len(), "emitted function");
+    debug!(name = %f.name, params = f.params.len(), variadic = f.is_variadic, "emitted function");
     Ok(())
 }
 
+/// Direction of a parameter without a `param_directions` override.
+///
+/// Direction follows the constness of the pointed-to data, not of the
+/// pointer itself: `const T *` is `[In]`, while `T *` and `T *const` are
+/// `[In, Out]`. For `T **` the pointee is a mutable pointer, so it is
+/// `[In, Out]`; `T *const *` is `[In]`. A mutable pointer is `[Out]` only
+/// when it looks like the result slot of a function returning an error
+/// code: the last parameter, named like an output (`out`, `out_len`,
+/// `result`, ...), of a function returning an integer. Non-pointers get no
+/// direction.
+///
+/// windows-bindgen treats non-Out parameters as input and applies
+/// to_const_ptr(), converting PtrMut → PtrConst → `*const`; Out preserves
+/// `*mut` in the generated Rust.
+fn infer_param_direction(
+    f: &FunctionDef,
+    index: usize,
+    param: &ParamDef,
+) -> Option<ParamDirection> {
+    if param.ty.is_outer_ptr_const() {
+        return Some(ParamDirection::In);
+    }
+    if !param.ty.is_outer_ptr_mut() {
+        return None;
+    }
+    let is_last = index + 1 == f.params.len();
+    if is_last && f.return_type.is_integer() && is_output_name(&param.name) {
+        Some(ParamDirection::Out)
+    } else {
+        Some(ParamDirection::InOut)
+    }
+}
+
+/// Parameter names conventionally used for output-only pointers.
+fn is_output_name(name: &str) -> bool {
+    let camel_out = |prefix: &str| {
+        name.strip_prefix(prefix)
+            .and_then(|rest| rest.chars().next())
+            .is_some_and(|c| c.is_ascii_uppercase())
+    };
+    matches!(name, "out" | "pOut" | "result" | "retval")
+        || name.starts_with("out_")
+        || name.ends_with("_out")
+        || camel_out("out")
+        || camel_out("pOut")
+}
+
+/// Map a calling convention onto the P/Invoke flags windows-bindgen reads.
+///
+/// Only `CallConvCdecl` (`extern "C"`) and `CallConvPlatformapi`
+/// (`extern "system"`) are understood downstream, so conventions that
+/// neither expresses are emitted as the nearest one with a warning.
+fn pinvoke_calling_convention(name: &str, cc: CallConv) -> PInvokeAttributes {
+    match cc {
+        CallConv::Cdecl => PInvokeAttributes::CallConvCdecl,
+        CallConv::Stdcall | CallConv::Win64 => PInvokeAttributes::CallConvPlatformapi,
+        // MSVC register conventions: `system` is the closest ABI on x64.
+        CallConv::Fastcall
+        | CallConv::Thiscall
+        | CallConv::Vectorcall
+        | CallConv::Regcall
+        | CallConv::Pascal => {
+            warn!(name, convention = ?cc, "calling convention not representable in winmd, emitting as platformapi");
+            PInvokeAttributes::CallConvPlatformapi
+        }
+        CallConv::SysV64
+        | CallConv::Aapcs
+        | CallConv::AapcsVfp
+        | CallConv::Swift
+        | CallConv::PreserveMost
+        | CallConv::PreserveAll
+        | CallConv::IntelOcl => {
+            warn!(name, convention = ?cc, "calling convention not representable in winmd, emitting as cdecl");
+            PInvokeAttributes::CallConvCdecl
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // #define constant emission
 // ---------------------------------------------------------------------------
 
-fn emit_constant(file: &mut File, c: &ConstantDef) -> Result<()> {
+fn emit_constant(
+    file: &mut File,
+    c: &ConstantDef,
+    dm: DataModel,
+    options: EmitOptions,
+) -> Result<()> {
     let (wintype, value) = match &c.value {
-        ConstantValue::Signed(v) => (Type::I32, Value::I32(*v as i32)),
+        ConstantValue::Signed(v) => match i32::try_from(*v) {
+            Ok(v) => (Type::I32, Value::I32(v)),
+            Err(_) => (Type::I64, Value::I64(*v)),
+        },
         ConstantValue::Unsigned(v) => {
             if *v <= u32::MAX as u64 {
                 (Type::U32, Value::U32(*v as u32))
@@ -348,6 +874,15 @@ fn emit_constant(file: &mut File, c: &ConstantDef) -> Result<()> {
             }
         }
         ConstantValue::Float(v) => (Type::F64, Value::F64(*v)),
+        ConstantValue::Typed {
+            ty,
+            signed,
+            unsigned,
+        } => {
+            let value = constant_value_for_enum(ty, *signed, *unsigned, dm);
+            (value.ty(), value)
+        }
+        ConstantValue::Wide(literal) => (Type::String, Value::Utf16(literal.clone())),
     };
 
     let field = file.Field(
@@ -359,11 +894,150 @@ fn emit_constant(file: &mut File, c: &ConstantDef) -> Result<()> {
             | FieldAttributes::HasDefault,
     );
     file.Constant(HasConstant::Field(field), &value);
+    // ECMA-335 has no 128-bit integer; name the C type the string holds.
+    if let ConstantValue::Wide(literal) = &c.value {
+        let c_name = if literal.starts_with('-') {
+            "__int128"
+        } else {
+            "unsigned __int128"
+        };
+        emit_native_type_name(file, HasAttribute::Field(field), c_name);
+    }
+    if options.docs
+        && let Some(doc) = &c.doc
+    {
+        emit_documentation(file, HasAttribute::Field(field), doc);
+    }
 
     debug!(name = %c.name, "emitted constant");
     Ok(())
 }
 
+// ---------------------------------------------------------------------------
+// Global variable emission
+// ---------------------------------------------------------------------------
+
+fn emit_variable(
+    file: &mut File,
+    namespace: &str,
+    g: &VariableDef,
+    library: &str,
+    registry: &TypeRegistry,
+    options: EmitOptions,
+) {
+    let ty = ctype_to_wintype(&g.ty, namespace, registry);
+    let field = file.Field(
+        &g.name,
+        &ty,
+        FieldAttributes::Public | FieldAttributes::Static,
+    );
+    let symbol = g.import_name.as_deref().unwrap_or(&g.name);
+    emit_attribute(
+        file,
+        HasAttribute::Field(field),
+        "Windows.Win32.Foundation.Metadata",
+        "DataImportAttribute",
+        &[
+            (String::new(), Value::Utf8(library.to_string())),
+            (String::new(), Value::Utf8(symbol.to_string())),
+        ],
+    );
+    if g.is_const {
+        emit_attribute(
+            file,
+            HasAttribute::Field(field),
+            "Windows.Win32.Foundation.Metadata",
+            "ConstAttribute",
+            &[],
+        );
+    }
+    if options.docs
+        && let Some(doc) = &g.doc
+    {
+        emit_documentation(file, HasAttribute::Field(field), doc);
+    }
+    if options.source_locations
+        && let Some(source) = &g.source
+    {
+        emit_source_location(file, HasAttribute::Field(field), source);
+    }
+    debug!(name = %g.name, symbol, "emitted global");
+}
+
+// ---------------------------------------------------------------------------
+// Custom attributes
+// ---------------------------------------------------------------------------
+
+/// Attach a custom attribute `namespace.name` to `parent`. The attribute
+/// constructor signature is derived from the positional (unnamed) `args`.
+fn emit_attribute(
+    file: &mut File,
+    parent: HasAttribute,
+    namespace: &str,
+    name: &str,
+    args: &[(String, Value)],
+) {
+    let attr_ref = file.TypeRef(namespace, name);
+    let ctor_sig = Signature {
+        types: args
+            .iter()
+            .take_while(|(arg_name, _)| arg_name.is_empty())
+            .map(|(_, value)| value.ty())
+            .collect(),
+        ..Signature::default()
+    };
+    let ctor = file.MemberRef(".ctor", &ctor_sig, MemberRefParent::TypeRef(attr_ref));
+    file.Attribute(parent, AttributeType::MemberRef(ctor), args);
+}
+
+/// Record the original C type name (e.g. `char32_t`) on a field or param whose
+/// winmd type is a plain integer, so downstream generators can recover it.
+fn emit_native_type_name(file: &mut File, parent: HasAttribute, c_name: &str) {
+    emit_attribute(
+        file,
+        parent,
+        "Windows.Win32.Foundation.Metadata",
+        "NativeTypeNameAttribute",
+        &[(String::new(), Value::Utf8(c_name.to_string()))],
+    );
+}
+
+/// Attach a C doc comment to a type, method or constant.
+fn emit_documentation(file: &mut File, parent: HasAttribute, doc: &str) {
+    emit_attribute(
+        file,
+        parent,
+        "Windows.Win32.Foundation.Metadata",
+        "DocumentationAttribute",
+        &[(String::new(), Value::Utf8(doc.to_string()))],
+    );
+}
+
+/// Record where a type or method was declared.
+fn emit_source_location(file: &mut File, parent: HasAttribute, source: &SourceLocation) {
+    emit_attribute(
+        file,
+        parent,
+        "Windows.Win32.Foundation.Metadata",
+        "SourceLocationAttribute",
+        &[
+            (String::new(), Value::Utf8(source.file.clone())),
+            (String::new(), Value::U32(source.line)),
+        ],
+    );
+}
+
+/// Mark a deprecated type or method with `System.ObsoleteAttribute`, passing
+/// the C attribute's message when it has one.
+fn emit_obsolete(file: &mut File, parent: HasAttribute, message: &str) {
+    let args = if message.is_empty() {
+        Vec::new()
+    } else {
+        vec![(String::new(), Value::Utf8(message.to_string()))]
+    };
+    emit_attribute(file, parent, "System", "ObsoleteAttribute", &args);
+}
+
 // ---------------------------------------------------------------------------
 // CType → windows_metadata::Type mapping
 // ---------------------------------------------------------------------------
@@ -384,6 +1058,35 @@ fn ctype_to_wintype(ctype: &CType, default_namespace: &str, registry: &TypeRegis
         CType::F64 => Type::F64,
         CType::ISize => Type::ISize,
         CType::USize => Type::USize,
+        CType::Char16 => Type::U16,
+        CType::Char32 => Type::U32,
+
+        // There is no ECMA-335 primitive wider than R8, so `long double` is
+        // emitted as an opaque byte array of the target's exact size. This
+        // keeps struct layouts (and `size_of` in generated bindings) correct
+        // even though the value itself cannot be used as a float.
+        CType::LongDouble { size } => Type::ArrayFixed(Box::new(Type::U8), *size),
+
+        // Likewise for `__int128`: windows-bindgen has no 128-bit integer,
+        // so it becomes `[u8; 16]`. Its 16-byte alignment is restored by
+        // the padding fields `pad_to_c_offsets` inserts in structs.
+        CType::I128 | CType::U128 => Type::ArrayFixed(Box::new(Type::U8), 16),
+
+        // `cb *` where `cb` is an emitted function-type typedef: its delegate
+        // is already pointer-shaped, so the `*` is not a second indirection.
+        CType::Ptr { pointee, .. }
+            if matches!(pointee.as_ref(), CType::Named { name, .. } if registry.contains(name))
+                && function_type(pointee).is_some() =>
+        {
+            ctype_to_wintype(pointee, default_namespace, registry)
+        }
+
+        // `IFoo *` where `IFoo` is an imported COM-style interface: the
+        // interface type is itself a reference, so `IFoo **` ends up as
+        // `*mut IFoo`.
+        CType::Ptr { pointee, .. } if matches!(pointee.as_ref(), CType::Named { name, .. } if registry.is_interface(name)) => {
+            ctype_to_wintype(pointee, default_namespace, registry)
+        }
 
         CType::Ptr {
             pointee,
@@ -410,11 +1113,11 @@ fn ctype_to_wintype(ctype: &CType, default_namespace: &str, registry: &TypeRegis
                 let ns = registry.namespace_for(name, default_namespace);
                 Type::named(&ns, name)
             } else if let Some(resolved) = resolved {
-                // System typedef not in any partition — use the canonical type
-                // that clang resolved during extraction.
+                // Typedef not in any partition — fall back to the aliased
+                // type, which may itself be a registered typedef.
                 ctype_to_wintype(resolved, default_namespace, registry)
             } else {
-                // Record/enum not in registry — emit as TypeRef and
+                // Record not in registry — emit as TypeRef and
                 // let windows-bindgen report the error with context.
                 let ns = registry.namespace_for(name, default_namespace);
                 Type::named(&ns, name)
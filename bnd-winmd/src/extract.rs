@@ -3,44 +3,125 @@
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use clang::{
-    CallingConvention, Entity, EntityKind, Index, Type as ClangType, TypeKind,
+    Availability, CallingConvention, Entity, EntityKind, EvaluationResult, Index, Linkage,
+    StorageClass, TranslationUnit, Type as ClangType, TypeKind, Unsaved,
+    diagnostic::Severity,
     sonar::{self, Declaration, DefinitionValue},
+    token::TokenKind,
 };
-use tracing::{debug, trace, warn};
+use tracing::{debug, info, trace, warn};
 
-use crate::config::{self, PartitionConfig};
+use crate::config::{
+    self, ArrayParamConfig, DataModel, EnumRepr, InlineFunctions, PartitionConfig, SymbolFilter,
+};
 use crate::model::*;
 
+/// Build clang arguments: language/std + user-specified args + -I flags
+/// from include_paths.
+fn parse_args(partition: &PartitionConfig, include_paths: &[PathBuf]) -> Vec<String> {
+    let mut all_args: Vec<String> = partition.clang_args();
+    for inc in include_paths {
+        let flag = format!("-I{}", inc.display());
+        if !all_args.contains(&flag) {
+            all_args.push(flag);
+        }
+    }
+    all_args
+}
+
+/// Parse a partition's headers with its clang arguments and save the result
+/// as a precompiled header at `output`, for the partition's `pch` setting.
+///
+/// The partition's own `pch` is not loaded, so a stale one can be rebuilt.
+pub fn build_pch(
+    index: &Index,
+    partition: &PartitionConfig,
+    base_dir: &Path,
+    include_paths: &[PathBuf],
+    output: &Path,
+) -> Result<()> {
+    let (header_path, wrapper) = partition.translation_unit(base_dir, include_paths);
+    let unsaved: Vec<Unsaved> = wrapper
+        .iter()
+        .map(|source| Unsaved::new(&header_path, source))
+        .collect();
+    let all_args = parse_args(partition, include_paths);
+    debug!(
+        header = %header_path.display(),
+        output = %output.display(),
+        "building precompiled header"
+    );
+
+    let tu = index
+        .parser(header_path.to_str().unwrap())
+        .arguments(&all_args.iter().map(|s| s.as_str()).collect::<Vec<_>>())
+        .unsaved(&unsaved)
+        .detailed_preprocessing_record(true)
+        .incomplete(true)
+        .parse()
+        .map_err(|e| anyhow::anyhow!("failed to parse {}: {:?}", header_path.display(), e))?;
+    check_diagnostics(&tu, &partition.namespace)?;
+    tu.save(output)
+        .map_err(|e| anyhow::anyhow!("failed to save {}: {e}", output.display()))
+}
+
 /// Extract all declarations from a single partition into model types.
+///
+/// The first returned partition is the configured one. Symbols named in
+/// `namespace_overrides` are moved into one extra partition per override
+/// namespace, which [`merge_overridden`] later folds into the partition
+/// configured for that namespace.
 pub fn extract_partition(
     index: &Index,
     partition: &PartitionConfig,
     base_dir: &Path,
     include_paths: &[PathBuf],
-    namespace_overrides: &std::collections::HashMap<String, String>,
-) -> Result<Partition> {
-    let _ = namespace_overrides; // reserved for future per-API namespace overrides
-    let header_path = partition.wrapper_header(base_dir, include_paths);
-    debug!(header = %header_path.display(), namespace = %partition.namespace, "parsing partition");
-
-    // Build clang arguments: user-specified args + -I flags from include_paths
-    let mut all_args: Vec<String> = partition.clang_args.clone();
-    for inc in include_paths {
-        let flag = format!("-I{}", inc.display());
-        if !all_args.contains(&flag) {
-            all_args.push(flag);
+    namespace_overrides: &HashMap<String, String>,
+    data_model: DataModel,
+) -> Result<Vec<Partition>> {
+    let (header_path, wrapper) = partition.translation_unit(base_dir, include_paths);
+    let unsaved: Vec<Unsaved> = wrapper
+        .iter()
+        .map(|source| Unsaved::new(&header_path, source))
+        .collect();
+    debug!(
+        header = %header_path.display(),
+        namespace = %partition.namespace,
+        data_model = ?data_model,
+        "parsing partition"
+    );
+
+    // clang only searches include paths for `#include`s, never for the file
+    // being parsed, so a header that didn't resolve can't be parsed at all.
+    for header in &partition.headers {
+        config::find_header(header, base_dir, include_paths)
+            .with_context(|| format!("partition `{}`", partition.namespace))?;
+    }
+
+    let mut all_args = parse_args(partition, include_paths);
+    if let Some(pch) = &partition.pch {
+        let pch = base_dir.join(pch);
+        if !pch.exists() {
+            anyhow::bail!(
+                "precompiled header {} not found, build it with `build_pch`",
+                pch.display()
+            );
         }
+        all_args.push("-include-pch".to_string());
+        all_args.push(pch.display().to_string());
     }
 
     let tu = index
         .parser(header_path.to_str().unwrap())
         .arguments(&all_args.iter().map(|s| s.as_str()).collect::<Vec<_>>())
+        .unsaved(&unsaved)
         .detailed_preprocessing_record(true)
         .parse()
         .map_err(|e| anyhow::anyhow!("failed to parse {}: {:?}", header_path.display(), e))?;
+    check_diagnostics(&tu, &partition.namespace)?;
 
     // Resolve traverse files through include_paths so relative names work
     let resolved_traverse: Vec<PathBuf> = partition
@@ -48,19 +129,84 @@ pub fn extract_partition(
         .iter()
         .map(|t| config::resolve_header(t, base_dir, include_paths))
         .collect();
-    let entities = tu.get_entity().get_children();
+    let entities = top_level_entities(&tu.get_entity(), &partition.cpp_namespaces);
 
     let in_scope = |e: &Entity| should_emit(e, &resolved_traverse, base_dir);
 
-    let structs = collect_structs(&entities, &in_scope);
-    let (enums, anon_enum_constants) = collect_enums(&entities, &in_scope);
-    let functions = collect_functions(&entities, &in_scope);
-    let typedefs = collect_typedefs(&entities, &in_scope);
-    let mut constants = collect_constants(&entities, &in_scope);
+    let filter = partition.symbol_filter()?;
+
+    let mut dropped = Vec::new();
+    let structs = collect_structs(&entities, &in_scope, &filter, data_model, &mut dropped);
+    let (mut enums, anon_enum_constants) =
+        collect_enums(&entities, &in_scope, &filter, data_model, &mut dropped);
+    let mut functions = collect_functions(
+        &entities,
+        &in_scope,
+        &filter,
+        data_model,
+        partition.inline_functions,
+        partition.string_pointers,
+        &mut dropped,
+    );
+    let mut typedefs = collect_typedefs(
+        &entities,
+        &in_scope,
+        &filter,
+        data_model,
+        partition.strict_signatures,
+        &mut dropped,
+    );
+    if partition.strict_signatures {
+        drop_unmappable_functions(&mut functions, &mut dropped);
+    }
+    typedefs.extend(forward_declared_handles(
+        &entities,
+        &in_scope,
+        &partition.opaque_handles,
+        &typedefs,
+    ));
+    let variables = collect_variables(&entities, &in_scope, &filter, data_model, &mut dropped);
+    let char_signed = config::char_is_signed(&all_args);
+    let mut constants = collect_constants(&entities, &in_scope, &filter, char_signed);
 
     // Merge in constants extracted from anonymous enums
     constants.extend(anon_enum_constants);
 
+    if partition.evaluate_macros {
+        let known: HashSet<String> = constants.iter().map(|c| c.name.clone()).collect();
+        let evaluated = evaluate_macro_constants(
+            index,
+            &header_path,
+            &all_args,
+            &unsaved,
+            &entities,
+            &in_scope,
+            &known,
+        );
+        constants.extend(evaluated.into_iter().filter(|c| filter.allows(&c.name)));
+    }
+
+    attach_macro_docs(&entities, &mut constants);
+
+    apply_param_directions(&mut functions, &partition.param_directions)?;
+    apply_array_params(&mut functions, &partition.array_params);
+    functions.extend(global_accessors(partition, data_model)?);
+    apply_function_libraries(&mut functions, &partition.function_libraries);
+    apply_last_error_functions(&mut functions, &partition.last_error_functions);
+
+    if partition.enum_repr == EnumRepr::Int {
+        promote_small_enum_args(&mut functions, &enums);
+    }
+
+    // Explicit config wins over the bitmask heuristic
+    for en in &mut enums {
+        if partition.flags_enums.contains(&en.name) {
+            en.is_flags = true;
+        } else if partition.plain_enums.contains(&en.name) {
+            en.is_flags = false;
+        }
+    }
+
     tracing::info!(
         namespace = %partition.namespace,
         structs = structs.len(),
@@ -68,27 +214,608 @@ pub fn extract_partition(
         functions = functions.len(),
         typedefs = typedefs.len(),
         constants = constants.len(),
+        variables = variables.len(),
+        dropped = dropped.len(),
         "partition extraction complete"
     );
 
-    Ok(Partition {
+    let mut home = Partition {
         namespace: partition.namespace.clone(),
         library: partition.library.clone(),
+        data_model,
         structs,
         enums,
         functions,
         typedefs,
         constants,
+        variables,
+        filtered: filter.filtered(),
+        dropped,
+    };
+    relativize_sources(&mut home, base_dir);
+    add_complex_structs(&mut home);
+    let mut moved = split_namespace_overrides(&mut home, namespace_overrides);
+    moved.extend(split_constant_groups(
+        &mut home,
+        &partition.constant_group_prefixes,
+    )?);
+
+    let mut partitions = vec![home];
+    partitions.extend(moved);
+    Ok(partitions)
+}
+
+/// Move constants whose name starts with one of `prefixes` out of `home`
+/// into a sub-namespace per prefix: `SSL_OP_ALL` with prefix `SSL_OP_` in
+/// `OpenSsl.Ssl` lands in `OpenSsl.Ssl.SSL_OP`. The first matching prefix
+/// wins; constants matching none stay in `home`.
+fn split_constant_groups(home: &mut Partition, prefixes: &[String]) -> Result<Vec<Partition>> {
+    let mut groups: Vec<Partition> = Vec::new();
+    if prefixes.is_empty() {
+        return Ok(groups);
+    }
+
+    let mut names = Vec::with_capacity(prefixes.len());
+    for prefix in prefixes {
+        let group = prefix.trim_end_matches('_');
+        if group.is_empty() || group.contains('.') {
+            anyhow::bail!(
+                "constant_group_prefixes entry `{prefix}` in partition `{}` does not \
+                 name a namespace segment",
+                home.namespace
+            );
+        }
+        names.push(group);
+    }
+
+    for c in std::mem::take(&mut home.constants) {
+        let Some(group) = prefixes
+            .iter()
+            .zip(&names)
+            .find(|(prefix, _)| c.name.starts_with(prefix.as_str()))
+            .map(|(_, group)| *group)
+        else {
+            home.constants.push(c);
+            continue;
+        };
+        let ns = format!("{}.{group}", home.namespace);
+        debug!(name = %c.name, to = %ns, "grouping constant");
+        let idx = match groups.iter().position(|p| p.namespace == ns) {
+            Some(idx) => idx,
+            None => {
+                groups.push(Partition {
+                    namespace: ns,
+                    library: home.library.clone(),
+                    data_model: home.data_model,
+                    structs: Vec::new(),
+                    enums: Vec::new(),
+                    functions: Vec::new(),
+                    typedefs: Vec::new(),
+                    constants: Vec::new(),
+                    variables: Vec::new(),
+                    filtered: Vec::new(),
+                    dropped: Vec::new(),
+                });
+                groups.len() - 1
+            }
+        };
+        groups[idx].constants.push(c);
+    }
+
+    Ok(groups)
+}
+
+/// Apply `param_directions` overrides, keyed `function:param` by C names.
+fn apply_param_directions(
+    functions: &mut [FunctionDef],
+    overrides: &HashMap<String, ParamDirection>,
+) -> Result<()> {
+    let mut overrides: Vec<_> = overrides.iter().collect();
+    overrides.sort_by_key(|(key, _)| *key);
+    for (key, direction) in overrides {
+        let Some((function, param)) = key.split_once(':') else {
+            anyhow::bail!("param_directions key `{key}` is not of the form `function:param`");
+        };
+        let target = functions
+            .iter_mut()
+            .filter(|f| f.name == function)
+            .flat_map(|f| &mut f.params)
+            .find(|p| p.name == param);
+        match target {
+            Some(p) => p.direction = Some(*direction),
+            None => warn!(key = %key, "param_directions entry matches no parameter, ignoring"),
+        }
+    }
+    Ok(())
+}
+
+/// Apply `array_params`, linking each pointer parameter to its length
+/// parameter by index.
+fn apply_array_params(functions: &mut [FunctionDef], arrays: &[ArrayParamConfig]) {
+    for array in arrays {
+        let Some(f) = functions.iter_mut().find(|f| f.name == array.function) else {
+            warn!(function = %array.function, "array_params entry matches no function, ignoring");
+            continue;
+        };
+        let ptr = f.params.iter().position(|p| p.name == array.ptr_param);
+        let len = f.params.iter().position(|p| p.name == array.len_param);
+        match (ptr, len) {
+            (Some(ptr), Some(len)) if ptr != len => f.params[ptr].array_len = Some(len),
+            _ => warn!(
+                function = %array.function,
+                ptr_param = %array.ptr_param,
+                len_param = %array.len_param,
+                "array_params entry matches no parameter pair, ignoring"
+            ),
+        }
+    }
+}
+
+/// Apply `function_libraries` overrides, keyed by C function name.
+fn apply_function_libraries(functions: &mut [FunctionDef], libraries: &HashMap<String, String>) {
+    let mut libraries: Vec<_> = libraries.iter().collect();
+    libraries.sort();
+    for (function, library) in libraries {
+        match functions.iter_mut().find(|f| f.name == *function) {
+            Some(f) => f.library = Some(library.clone()),
+            None => warn!(
+                function = %function,
+                "function_libraries entry matches no function, ignoring"
+            ),
+        }
+    }
+}
+
+/// Mark the functions listed in `last_error_functions` (C names).
+fn apply_last_error_functions(functions: &mut [FunctionDef], names: &[String]) {
+    for name in names {
+        match functions.iter_mut().find(|f| f.name == *name) {
+            // No symbol means no call to capture the error after.
+            Some(f) if f.inline => warn!(
+                function = %name,
+                "last_error_functions entry is an inline function, ignoring"
+            ),
+            Some(f) => f.sets_last_error = true,
+            None => warn!(
+                function = %name,
+                "last_error_functions entry matches no function, ignoring"
+            ),
+        }
+    }
+}
+
+/// Turn the partition's `globals` into zero-argument functions named after
+/// the global, each importing its accessor symbol.
+fn global_accessors(partition: &PartitionConfig, dm: DataModel) -> Result<Vec<FunctionDef>> {
+    let mut functions = Vec::with_capacity(partition.globals.len());
+    for (name, global) in &partition.globals {
+        let Some(return_type) = ctype_by_spelling(&global.ty, dm) else {
+            anyhow::bail!(
+                "global `{name}` in partition `{}`: cannot parse type `{}`",
+                partition.namespace,
+                global.ty
+            );
+        };
+        debug!(name = %name, accessor = %global.accessor, "global accessor");
+        functions.push(FunctionDef {
+            name: name.clone(),
+            import_name: (*name != global.accessor).then(|| global.accessor.clone()),
+            return_type,
+            params: Vec::new(),
+            calling_convention: CallConv::Cdecl,
+            is_variadic: false,
+            doc: None,
+            source: None,
+            deprecated: None,
+            inline: false,
+            free_with: None,
+            library: None,
+            sets_last_error: false,
+            charset: None,
+        });
+    }
+    Ok(functions)
+}
+
+/// Parse a C type spelled in the config, e.g. `int *` or `const char **`:
+/// `void`, a builtin, or a type name (resolved through the registry),
+/// followed by any number of `*`s.
+fn ctype_by_spelling(spelling: &str, dm: DataModel) -> Option<CType> {
+    let spelling = spelling.trim();
+    if let Some(pointee) = spelling.strip_suffix('*') {
+        let pointee = pointee.trim_end();
+        // Only the innermost pointer can point at a const base type.
+        let is_const = !pointee.ends_with('*')
+            && (pointee.starts_with("const ") || pointee.ends_with(" const"));
+        return Some(CType::Ptr {
+            pointee: Box::new(ctype_by_spelling(pointee, dm)?),
+            is_const,
+        });
+    }
+
+    let base = strip_qualifiers(spelling);
+    let base = base.strip_suffix(" const").unwrap_or(base).trim_end();
+    if base == "void" {
+        return Some(CType::Void);
+    }
+    if let Some(ctype) = builtin_by_spelling(base, dm) {
+        return Some(ctype);
+    }
+    let is_identifier = base
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && base.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    is_identifier.then(|| CType::Named {
+        name: base.to_string(),
+        resolved: None,
     })
 }
 
+/// Move every symbol named in `namespace_overrides` out of `home` into a
+/// partition for its override namespace. Moved functions keep linking
+/// against `home`'s library.
+fn split_namespace_overrides(
+    home: &mut Partition,
+    namespace_overrides: &HashMap<String, String>,
+) -> Vec<Partition> {
+    let mut moved: Vec<Partition> = Vec::new();
+    if namespace_overrides.is_empty() {
+        return moved;
+    }
+
+    let home_ns = home.namespace.clone();
+    let library = home.library.clone();
+    let data_model = home.data_model;
+    let target = |moved: &mut Vec<Partition>, name: &str| -> Option<usize> {
+        let ns = namespace_overrides.get(name).filter(|ns| **ns != home_ns)?;
+        debug!(name, from = %home_ns, to = %ns, "applying namespace override");
+        let idx = match moved.iter().position(|p| &p.namespace == ns) {
+            Some(idx) => idx,
+            None => {
+                moved.push(Partition {
+                    namespace: ns.clone(),
+                    library: library.clone(),
+                    data_model,
+                    structs: Vec::new(),
+                    enums: Vec::new(),
+                    functions: Vec::new(),
+                    typedefs: Vec::new(),
+                    constants: Vec::new(),
+                    variables: Vec::new(),
+                    filtered: Vec::new(),
+                    dropped: Vec::new(),
+                });
+                moved.len() - 1
+            }
+        };
+        Some(idx)
+    };
+
+    for s in std::mem::take(&mut home.structs) {
+        match target(&mut moved, &s.name) {
+            Some(i) => moved[i].structs.push(s),
+            None => home.structs.push(s),
+        }
+    }
+    for e in std::mem::take(&mut home.enums) {
+        match target(&mut moved, &e.name) {
+            Some(i) => moved[i].enums.push(e),
+            None => home.enums.push(e),
+        }
+    }
+    for f in std::mem::take(&mut home.functions) {
+        match target(&mut moved, &f.name) {
+            Some(i) => moved[i].functions.push(f),
+            None => home.functions.push(f),
+        }
+    }
+    for td in std::mem::take(&mut home.typedefs) {
+        match target(&mut moved, &td.name) {
+            Some(i) => moved[i].typedefs.push(td),
+            None => home.typedefs.push(td),
+        }
+    }
+    for c in std::mem::take(&mut home.constants) {
+        match target(&mut moved, &c.name) {
+            Some(i) => moved[i].constants.push(c),
+            None => home.constants.push(c),
+        }
+    }
+    for g in std::mem::take(&mut home.variables) {
+        match target(&mut moved, &g.name) {
+            Some(i) => moved[i].variables.push(g),
+            None => home.variables.push(g),
+        }
+    }
+
+    moved
+}
+
+/// Fold partitions produced by `namespace_overrides` into the configured
+/// partition with the same namespace, or append them as new partitions.
+pub fn merge_overridden(partitions: &mut Vec<Partition>, moved: Vec<Partition>) -> Result<()> {
+    for extra in moved {
+        let Some(target) = partitions
+            .iter_mut()
+            .find(|p| p.namespace == extra.namespace)
+        else {
+            partitions.push(extra);
+            continue;
+        };
+        merge_partition(target, extra).context("namespace override")?;
+    }
+    Ok(())
+}
+
+/// Add a configured partition to `partitions`, merging it into an earlier
+/// one with the same namespace so the namespace gets a single `Apis`
+/// class.
+pub fn push_partition(partitions: &mut Vec<Partition>, partition: Partition) -> Result<()> {
+    match partitions
+        .iter_mut()
+        .find(|p| p.namespace == partition.namespace)
+    {
+        Some(target) => {
+            debug!(namespace = %partition.namespace, "merging partitions sharing a namespace");
+            merge_partition(target, partition)
+        }
+        None => {
+            partitions.push(partition);
+            Ok(())
+        }
+    }
+}
+
+/// Move `extra`'s declarations into `target`.
+///
+/// Functions can only join a partition that links the same library, since a
+/// partition carries a single `ImplMap` scope, unless `function_libraries`
+/// gave them their own.
+fn merge_partition(target: &mut Partition, extra: Partition) -> Result<()> {
+    if let Some(f) = extra.functions.iter().find(|f| f.library.is_none())
+        && target.library != extra.library
+    {
+        anyhow::bail!(
+            "function `{}` (library `{}`) can't join partition `{}`, \
+             which links library `{}`",
+            f.name,
+            extra.library,
+            target.namespace,
+            target.library
+        );
+    }
+    if let Some(g) = extra.variables.first()
+        && target.library != extra.library
+    {
+        anyhow::bail!(
+            "global `{}` (library `{}`) can't join partition `{}`, \
+             which links library `{}`",
+            g.name,
+            extra.library,
+            target.namespace,
+            target.library
+        );
+    }
+    // A shared header traversed by several partitions yields the same
+    // symbol more than once; keep the first copy.
+    extend_unique(&mut target.structs, extra.structs, |s| &s.name);
+    extend_unique(&mut target.enums, extra.enums, |e| &e.name);
+    extend_unique(&mut target.functions, extra.functions, |f| &f.name);
+    extend_unique(&mut target.typedefs, extra.typedefs, |td| &td.name);
+    extend_unique(&mut target.constants, extra.constants, |c| &c.name);
+    extend_unique(&mut target.variables, extra.variables, |g| &g.name);
+    target.filtered.extend(extra.filtered);
+    target.filtered.sort();
+    target.filtered.dedup();
+    extend_unique(&mut target.dropped, extra.dropped, |d| &d.name);
+    Ok(())
+}
+
+/// Record the free function of each `resource_pairs` entry on the handle
+/// typedef or allocating function it names.
+///
+/// Both sides are C names; the free function is recorded under the name it
+/// is emitted with. windows-bindgen looks a handle's free function up in the
+/// handle's namespace, so a pair split across namespaces is kept but warned
+/// about.
+pub fn apply_resource_pairs(
+    partitions: &mut [Partition],
+    pairs: &[(String, String)],
+    type_renames: &HashMap<String, String>,
+) {
+    for (resource, free) in pairs {
+        let Some((free_ns, free_name)) = partitions.iter().find_map(|p| {
+            p.functions
+                .iter()
+                .find(|f| f.import_name.as_ref().unwrap_or(&f.name) == free)
+                .map(|f| (p.namespace.clone(), f.name.clone()))
+        }) else {
+            warn!(resource = %resource, free = %free, "resource_pairs: free function is not an extracted function, ignoring");
+            continue;
+        };
+
+        let type_name = type_renames.get(resource).unwrap_or(resource);
+        let mut found = false;
+        for partition in partitions.iter_mut() {
+            if let Some(td) = partition
+                .typedefs
+                .iter_mut()
+                .find(|td| td.name == *type_name)
+            {
+                if partition.namespace != free_ns {
+                    warn!(
+                        resource = %resource,
+                        free = %free,
+                        "resource_pairs: handle and free function are in different namespaces"
+                    );
+                }
+                td.free_with = Some(free_name.clone());
+                found = true;
+            } else if let Some(f) = partition
+                .functions
+                .iter_mut()
+                .find(|f| f.import_name.as_ref().unwrap_or(&f.name) == resource)
+            {
+                f.free_with = Some(free_name.clone());
+                found = true;
+            }
+            if found {
+                debug!(resource = %resource, free = %free_name, "paired resource");
+                break;
+            }
+        }
+        if !found {
+            warn!(
+                resource = %resource,
+                "resource_pairs: resource is not an extracted typedef or function, ignoring"
+            );
+        }
+    }
+}
+
+fn extend_unique<T>(into: &mut Vec<T>, from: Vec<T>, name: impl Fn(&T) -> &String) {
+    for item in from {
+        if !into.iter().any(|existing| name(existing) == name(&item)) {
+            into.push(item);
+        }
+    }
+}
+
+/// Fail if two enums in one namespace declare a variant with the same name.
+///
+/// windows-bindgen emits enum variants as constants of the namespace's
+/// module, so such variants would collide in the generated bindings. C
+/// rules this out within one translation unit, but `namespace_overrides`
+/// can bring enums from different headers together. Variants of one enum
+/// sharing a value (`WHENCE_DEFAULT = WHENCE_SET`) are fine.
+pub fn check_enum_variants(partitions: &[Partition]) -> Result<()> {
+    let mut owners: HashMap<(&str, &str), &str> = HashMap::new();
+    for partition in partitions {
+        for en in &partition.enums {
+            for variant in &en.variants {
+                let key = (partition.namespace.as_str(), variant.name.as_str());
+                match owners.insert(key, &en.name) {
+                    Some(previous) if previous != en.name => anyhow::bail!(
+                        "enum variant `{}` is declared by both `{previous}` and `{}` \
+                         in namespace `{}`",
+                        variant.name,
+                        en.name,
+                        partition.namespace
+                    ),
+                    _ => {}
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The translation unit's top-level entities, with the contents of
+/// `extern "C" { ... }` blocks and of the C++ namespaces listed in
+/// `cpp_namespaces` lifted to the top level. Headers parsed as C++ commonly
+/// wrap their whole API in one.
+fn top_level_entities<'tu>(tu: &Entity<'tu>, cpp_namespaces: &[String]) -> Vec<Entity<'tu>> {
+    let mut entities = Vec::new();
+    let mut flattened = HashSet::new();
+    lift_entities(tu, "", cpp_namespaces, &mut flattened, &mut entities);
+    for namespace in cpp_namespaces {
+        if !flattened.contains(namespace.as_str()) {
+            warn!(namespace = %namespace, "cpp_namespaces entry matches no namespace, ignoring");
+        }
+    }
+    entities
+}
+
+/// Push the children of `parent`, a translation unit, linkage spec or C++
+/// namespace at `path`, onto `entities`, descending into linkage specs and
+/// the listed namespaces.
+fn lift_entities<'tu>(
+    parent: &Entity<'tu>,
+    path: &str,
+    cpp_namespaces: &[String],
+    flattened: &mut HashSet<String>,
+    entities: &mut Vec<Entity<'tu>>,
+) {
+    for entity in parent.get_children() {
+        match entity.get_kind() {
+            EntityKind::LinkageSpec => {
+                lift_entities(&entity, path, cpp_namespaces, flattened, entities)
+            }
+            EntityKind::Namespace => {
+                let name = entity.get_name().unwrap_or_default();
+                let nested = if path.is_empty() {
+                    name
+                } else {
+                    format!("{path}::{name}")
+                };
+                if cpp_namespaces.contains(&nested) {
+                    lift_entities(&entity, &nested, cpp_namespaces, flattened, entities);
+                    flattened.insert(nested);
+                } else {
+                    trace!(namespace = %nested, "skipping C++ namespace");
+                }
+            }
+            _ => entities.push(entity),
+        }
+    }
+}
+
+/// Log clang warnings and fail on errors, so a missing `#include` or an
+/// unknown type is reported instead of silently yielding an empty partition.
+fn check_diagnostics(tu: &TranslationUnit, namespace: &str) -> Result<()> {
+    let mut errors = Vec::new();
+    for diag in tu.get_diagnostics() {
+        let severity = diag.get_severity();
+        if severity < Severity::Warning {
+            continue;
+        }
+        let loc = diag.get_location().get_spelling_location();
+        let file = loc
+            .file
+            .map(|f| f.get_path().display().to_string())
+            .unwrap_or_else(|| "<unknown>".to_string());
+        let text = diag.get_text();
+        let message = format!("{file}:{}:{}: {text}", loc.line, loc.column);
+
+        if severity == Severity::Warning {
+            warn!(namespace, "{message}");
+            continue;
+        }
+        errors.push(message);
+        // `'foo.h' file not found` — point at the config knobs that fix it.
+        if let Some(missing) = text
+            .strip_prefix('\'')
+            .and_then(|t| t.strip_suffix("' file not found"))
+        {
+            errors.push(format!(
+                "  header `{missing}` not found, check `headers` and `include_paths`"
+            ));
+        }
+    }
+
+    if errors.is_empty() {
+        return Ok(());
+    }
+    anyhow::bail!(
+        "clang reported errors in partition `{namespace}`:\n{}",
+        errors.join("\n")
+    );
+}
+
 // ---------------------------------------------------------------------------
 // Collection helpers — one per declaration kind
 // ---------------------------------------------------------------------------
 
 /// Collect structs via sonar, then run a supplemental pass for StructDecl
 /// entities that sonar missed (e.g. structs that only have a pointer typedef).
-fn collect_structs(entities: &[Entity], in_scope: &impl Fn(&Entity) -> bool) -> Vec<StructDef> {
+fn collect_structs(
+    entities: &[Entity],
+    in_scope: &impl Fn(&Entity) -> bool,
+    filter: &SymbolFilter,
+    dm: DataModel,
+    dropped: &mut Vec<DroppedDecl>,
+) -> Vec<StructDef> {
     let mut structs = Vec::new();
     let mut seen = HashSet::new();
 
@@ -98,7 +825,10 @@ fn collect_structs(entities: &[Entity], in_scope: &impl Fn(&Entity) -> bool) ->
             continue;
         }
         seen.insert(decl.name.clone());
-        match extract_struct(&decl) {
+        if !filter.allows(&decl.name) {
+            continue;
+        }
+        match extract_struct(&decl, dm) {
             Ok((s, nested)) => {
                 debug!(name = %s.name, fields = s.fields.len(), size = s.size, "extracted struct");
                 for ns in nested {
@@ -108,7 +838,10 @@ fn collect_structs(entities: &[Entity], in_scope: &impl Fn(&Entity) -> bool) ->
                 }
                 structs.push(s);
             }
-            Err(e) => warn!(name = %decl.name, err = %e, "skipping struct"),
+            Err(e) => {
+                warn!(name = %decl.name, err = %e, "skipping struct");
+                dropped.push(DroppedDecl::new(&decl.name, DeclKind::Struct, &e));
+            }
         }
     }
 
@@ -132,7 +865,10 @@ fn collect_structs(entities: &[Entity], in_scope: &impl Fn(&Entity) -> bool) ->
             continue;
         }
         seen.insert(name.clone());
-        match extract_struct_from_entity(entity, &name, is_union) {
+        if !filter.allows(&name) {
+            continue;
+        }
+        match extract_struct_from_entity(entity, &name, is_union, dm) {
             Ok((s, nested)) => {
                 let kind = if is_union { "union" } else { "struct" };
                 debug!(name = %s.name, fields = s.fields.len(), size = s.size, "extracted {kind} (supplemental)");
@@ -143,7 +879,15 @@ fn collect_structs(entities: &[Entity], in_scope: &impl Fn(&Entity) -> bool) ->
                 }
                 structs.push(s);
             }
-            Err(e) => warn!(name = %name, err = %e, "skipping struct/union"),
+            Err(e) => {
+                warn!(name = %name, err = %e, "skipping struct/union");
+                let kind = if is_union {
+                    DeclKind::Union
+                } else {
+                    DeclKind::Struct
+                };
+                dropped.push(DroppedDecl::new(&name, kind, &e));
+            }
         }
     }
 
@@ -154,85 +898,300 @@ fn collect_structs(entities: &[Entity], in_scope: &impl Fn(&Entity) -> bool) ->
 fn collect_enums(
     entities: &[Entity],
     in_scope: &impl Fn(&Entity) -> bool,
+    filter: &SymbolFilter,
+    dm: DataModel,
+    dropped: &mut Vec<DroppedDecl>,
 ) -> (Vec<EnumDef>, Vec<ConstantDef>) {
     let mut enums = Vec::new();
     let mut anon_constants = Vec::new();
+    let mut seen = HashSet::new();
     for decl in sonar::find_enums(entities.to_vec()) {
         if !in_scope(&decl.entity) {
             continue;
         }
+        seen.insert(decl.entity);
         // Detect anonymous enums (e.g. `enum { DT_UNKNOWN = 0, ... }`).
         // clang gives them names like "enum (unnamed at /usr/include/dirent.h:97:1)".
         // These are just collections of integer constants in C — emit their
         // variants as standalone ConstantDef entries instead of a named enum.
         if decl.entity.is_anonymous() || decl.name.contains("(unnamed") {
-            match extract_enum(&decl) {
-                Ok(en) => {
-                    debug!(
-                        name = %decl.name,
-                        variants = en.variants.len(),
-                        "anonymous enum → emitting variants as constants"
-                    );
-                    for variant in en.variants {
-                        let value = if variant.signed_value < 0 {
-                            ConstantValue::Signed(variant.signed_value)
-                        } else {
-                            ConstantValue::Unsigned(variant.unsigned_value)
-                        };
-                        anon_constants.push(ConstantDef {
-                            name: variant.name,
-                            value,
-                        });
-                    }
-                }
-                Err(e) => warn!(name = %decl.name, err = %e, "skipping anonymous enum"),
-            }
+            collect_anonymous_enum(&decl, filter, dm, &mut anon_constants, dropped);
             continue;
         }
-        match extract_enum(&decl) {
+        if !filter.allows(&decl.name) {
+            continue;
+        }
+        match extract_enum(&decl, dm) {
             Ok(en) => {
                 debug!(name = %en.name, variants = en.variants.len(), "extracted enum");
                 enums.push(en);
             }
-            Err(e) => warn!(name = %decl.name, err = %e, "skipping enum"),
+            Err(e) => {
+                warn!(name = %decl.name, err = %e, "skipping enum");
+                dropped.push(DroppedDecl::new(&decl.name, DeclKind::Enum, &e));
+            }
+        }
+    }
+
+    // Supplemental: anonymous enums sonar skipped because libclang gave
+    // them no name at all. Ones named by a typedef were found above.
+    let typedef_targets: HashSet<Entity> = entities
+        .iter()
+        .filter(|e| e.get_kind() == EntityKind::TypedefDecl)
+        .filter_map(|e| e.get_typedef_underlying_type()?.get_declaration())
+        .collect();
+    for entity in entities {
+        if entity.get_kind() != EntityKind::EnumDecl
+            || entity.get_name().is_some()
+            || seen.contains(entity)
+            || typedef_targets.contains(entity)
+            || !in_scope(entity)
+        {
+            continue;
         }
+        let decl = Declaration {
+            name: "(anonymous enum)".to_string(),
+            entity: *entity,
+            source: None,
+        };
+        collect_anonymous_enum(&decl, filter, dm, &mut anon_constants, dropped);
     }
+
     (enums, anon_constants)
 }
 
+/// Emit the variants of an anonymous enum as constants, keeping the enum's
+/// underlying integer type.
+fn collect_anonymous_enum(
+    decl: &Declaration,
+    filter: &SymbolFilter,
+    dm: DataModel,
+    constants: &mut Vec<ConstantDef>,
+    dropped: &mut Vec<DroppedDecl>,
+) {
+    match extract_enum(decl, dm) {
+        Ok(en) => {
+            debug!(
+                name = %decl.name,
+                variants = en.variants.len(),
+                "anonymous enum → emitting variants as constants"
+            );
+            for variant in en.variants {
+                if !filter.allows(&variant.name) {
+                    continue;
+                }
+                constants.push(ConstantDef {
+                    name: variant.name,
+                    value: ConstantValue::Typed {
+                        ty: en.underlying_type.clone(),
+                        signed: variant.signed_value,
+                        unsigned: variant.unsigned_value,
+                    },
+                    doc: None,
+                });
+            }
+        }
+        Err(e) => {
+            warn!(name = %decl.name, err = %e, "skipping anonymous enum");
+            dropped.push(DroppedDecl::new(&decl.name, DeclKind::Enum, &e));
+        }
+    }
+}
+
+/// Under `strict_signatures`, drop the functions whose signature uses a
+/// typedef that was dropped for an unsupported type: mapped leniently, the
+/// typedef would have become `void` and the signature would be wrong.
+fn drop_unmappable_functions(functions: &mut Vec<FunctionDef>, dropped: &mut Vec<DroppedDecl>) {
+    let unmappable: HashSet<String> = dropped
+        .iter()
+        .filter(|d| d.kind == DeclKind::Typedef)
+        .map(|d| d.name.clone())
+        .collect();
+    if unmappable.is_empty() {
+        return;
+    }
+    functions.retain(|f| {
+        let uses = |ty: &CType| {
+            let mut found = None;
+            ty.for_each_named(&mut |name| {
+                if found.is_none() && unmappable.contains(name) {
+                    found = Some(name.to_string());
+                }
+            });
+            found
+        };
+        let reason = match uses(&f.return_type) {
+            Some(name) => anyhow::anyhow!("unsupported return type `{name}`"),
+            None => match f.params.iter().find_map(|p| Some((&p.name, uses(&p.ty)?))) {
+                Some((param, name)) => {
+                    anyhow::anyhow!("unsupported type `{name}` for parameter '{param}'")
+                }
+                None => return true,
+            },
+        };
+        warn!(name = %f.name, err = %reason, "skipping function");
+        dropped.push(DroppedDecl::new(&f.name, DeclKind::Function, &reason));
+        false
+    });
+}
+
+/// Collect `extern` global variables: top-level variable declarations with
+/// external linkage. Thread-locals are skipped, as they have no fixed
+/// address to import.
+fn collect_variables(
+    entities: &[Entity],
+    in_scope: &impl Fn(&Entity) -> bool,
+    filter: &SymbolFilter,
+    dm: DataModel,
+    dropped: &mut Vec<DroppedDecl>,
+) -> Vec<VariableDef> {
+    let mut variables = Vec::new();
+    let mut seen = HashSet::new();
+    for entity in entities {
+        if entity.get_kind() != EntityKind::VarDecl
+            || entity.get_linkage() != Some(Linkage::External)
+        {
+            continue;
+        }
+        if !in_scope(entity) {
+            continue;
+        }
+        let Some(name) = entity.get_name() else {
+            continue;
+        };
+        if !filter.allows(&name) || !seen.insert(name.clone()) {
+            continue;
+        }
+        if entity.get_tls_kind().is_some() {
+            info!(name = %name, "skipping thread-local global (no fixed address to import)");
+            continue;
+        }
+        let Some(ty) = entity.get_type() else {
+            continue;
+        };
+        match map_clang_type(&ty, dm) {
+            Ok(mapped) => {
+                debug!(name = %name, "extracted global");
+                variables.push(VariableDef {
+                    import_name: cpp_symbol(entity),
+                    name,
+                    ty: mapped,
+                    is_const: ty.is_const_qualified(),
+                    doc: doc_comment(entity),
+                    source: source_location(entity),
+                });
+            }
+            Err(e) => {
+                warn!(name = %name, err = %e, "skipping global");
+                dropped.push(DroppedDecl::new(&name, DeclKind::Variable, &e));
+            }
+        }
+    }
+    variables
+}
+
 /// Collect functions via sonar.
-fn collect_functions(entities: &[Entity], in_scope: &impl Fn(&Entity) -> bool) -> Vec<FunctionDef> {
+fn collect_functions(
+    entities: &[Entity],
+    in_scope: &impl Fn(&Entity) -> bool,
+    filter: &SymbolFilter,
+    dm: DataModel,
+    inline_functions: InlineFunctions,
+    string_pointers: bool,
+    dropped: &mut Vec<DroppedDecl>,
+) -> Vec<FunctionDef> {
     let mut functions = Vec::new();
     let mut seen = HashSet::new();
     for decl in sonar::find_functions(entities.to_vec()) {
         if !in_scope(&decl.entity) {
             continue;
         }
-        // Skip variadic functions — P/Invoke metadata cannot represent `...`
-        if decl.entity.is_variadic() {
-            warn!(name = %decl.name, "skipping variadic function");
+        if !filter.allows(&decl.name) {
+            continue;
+        }
+        let inline = lacks_symbol(&decl.entity);
+        if inline && inline_functions == InlineFunctions::Skip {
+            info!(name = %decl.name, "skipping static/inline function (no symbol to import)");
             continue;
         }
-        match extract_function(&decl) {
-            Ok(f) => {
+        match extract_function(&decl, dm, string_pointers) {
+            Ok(mut f) => {
+                f.inline = inline;
                 // Deduplicate by name — glibc __REDIRECT macros can produce
                 // multiple declarations of the same function (e.g. lockf / lockf64).
                 if !seen.insert(f.name.clone()) {
                     trace!(name = %f.name, "skipping duplicate function");
                     continue;
                 }
-                debug!(name = %f.name, params = f.params.len(), "extracted function");
+                debug!(
+                    name = %f.name,
+                    params = f.params.len(),
+                    variadic = f.is_variadic,
+                    "extracted function"
+                );
                 functions.push(f);
             }
-            Err(e) => warn!(name = %decl.name, err = %e, "skipping function"),
+            Err(e) => {
+                warn!(name = %decl.name, err = %e, "skipping function");
+                dropped.push(DroppedDecl::new(&decl.name, DeclKind::Function, &e));
+            }
         }
     }
     functions
 }
 
+/// Handle typedefs for `opaque_handles` entries that name a struct only
+/// ever forward-declared (`struct foo;`) rather than a typedef, so that
+/// `struct foo *` has a type to refer to.
+fn forward_declared_handles(
+    entities: &[Entity],
+    in_scope: &impl Fn(&Entity) -> bool,
+    handles: &[String],
+    typedefs: &[TypedefDef],
+) -> Vec<TypedefDef> {
+    let mut synthesized: Vec<TypedefDef> = Vec::new();
+    for entity in entities {
+        if !matches!(
+            entity.get_kind(),
+            EntityKind::StructDecl | EntityKind::UnionDecl
+        ) || !in_scope(entity)
+            || entity.get_definition().is_some()
+        {
+            continue;
+        }
+        let Some(name) = entity.get_name() else {
+            continue;
+        };
+        if !handles.contains(&name)
+            || typedefs
+                .iter()
+                .chain(&synthesized)
+                .any(|td| td.name == name)
+        {
+            continue;
+        }
+        debug!(name = %name, "opaque handle for forward-declared record");
+        synthesized.push(TypedefDef {
+            name,
+            underlying_type: CType::opaque_pointer(),
+            source: source_location(entity),
+            free_with: None,
+            array_layout: None,
+        });
+    }
+    synthesized
+}
+
 /// Collect typedefs via custom discovery (not sonar, which drops typedef-to-
 /// typedef aliases like `typedef Byte Bytef`).
-fn collect_typedefs(entities: &[Entity], in_scope: &impl Fn(&Entity) -> bool) -> Vec<TypedefDef> {
+fn collect_typedefs(
+    entities: &[Entity],
+    in_scope: &impl Fn(&Entity) -> bool,
+    filter: &SymbolFilter,
+    dm: DataModel,
+    strict: bool,
+    dropped: &mut Vec<DroppedDecl>,
+) -> Vec<TypedefDef> {
     let mut typedefs = Vec::new();
     let mut seen = HashSet::new();
     for entity in entities {
@@ -249,28 +1208,45 @@ fn collect_typedefs(entities: &[Entity], in_scope: &impl Fn(&Entity) -> bool) ->
         if !seen.insert(name.clone()) {
             continue;
         }
+        if !filter.allows(&name) {
+            continue;
+        }
         let underlying = match entity.get_typedef_underlying_type() {
             Some(ut) => ut,
             None => continue,
         };
+        // Wide character typedefs are mapped to char types, never referenced by name
+        if wide_char_typedef(&name, dm).is_some() {
+            trace!(name = %name, "skipping wide character typedef");
+            continue;
+        }
         // Skip trivial struct/enum/union pass-throughs like `typedef struct foo foo;`
         if is_struct_passthrough(&underlying, &name) {
             trace!(name = %name, "skipping struct/enum passthrough typedef");
             continue;
         }
-        match extract_typedef_from_entity(entity, &name) {
+        match extract_typedef_from_entity(entity, &name, dm, strict) {
             Ok(td) => {
                 debug!(name = %td.name, "extracted typedef");
                 typedefs.push(td);
             }
-            Err(e) => warn!(name = %name, err = %e, "skipping typedef"),
+            Err(e) => {
+                warn!(name = %name, err = %e, "skipping typedef");
+                dropped.push(DroppedDecl::new(&name, DeclKind::Typedef, &e));
+            }
         }
     }
     typedefs
 }
 
-/// Collect `#define` constants via sonar + supplemental hex parsing.
-fn collect_constants(entities: &[Entity], in_scope: &impl Fn(&Entity) -> bool) -> Vec<ConstantDef> {
+/// Collect `#define` constants via sonar + supplemental hex and character
+/// literal parsing. `char_signed` gives the value of plain `'\xff'`.
+fn collect_constants(
+    entities: &[Entity],
+    in_scope: &impl Fn(&Entity) -> bool,
+    filter: &SymbolFilter,
+    char_signed: bool,
+) -> Vec<ConstantDef> {
     let mut constants = Vec::new();
     let mut seen = HashSet::new();
 
@@ -279,16 +1255,16 @@ fn collect_constants(entities: &[Entity], in_scope: &impl Fn(&Entity) -> bool) -
         if !in_scope(&def.entity) {
             continue;
         }
+        if !filter.allows(&def.name) {
+            continue;
+        }
         let value = match def.value {
-            DefinitionValue::Integer(negated, val) => {
-                if negated {
-                    ConstantValue::Signed(-(val as i64))
-                } else if val <= i64::MAX as u64 {
-                    ConstantValue::Signed(val as i64)
-                } else {
-                    ConstantValue::Unsigned(val)
-                }
-            }
+            DefinitionValue::Integer(negated, val) => match integer_constant(negated, val) {
+                Some(value) => value,
+                // Below `i64::MIN`: picked up from its tokens as a wide
+                // constant below.
+                None => continue,
+            },
             DefinitionValue::Real(val) => ConstantValue::Float(val),
         };
         debug!(name = %def.name, "extracted #define constant");
@@ -296,6 +1272,7 @@ fn collect_constants(entities: &[Entity], in_scope: &impl Fn(&Entity) -> bool) -
         constants.push(ConstantDef {
             name: def.name,
             value,
+            doc: None,
         });
     }
 
@@ -329,24 +1306,363 @@ fn collect_constants(entities: &[Entity], in_scope: &impl Fn(&Entity) -> bool) -
             } else {
                 continue;
             };
+            // Filter only macros that are constants, so function-like and
+            // empty macros don't show up as filtered symbols.
             if let Some(val) = parse_hex_or_suffixed_int(number) {
-                let value = if negated {
-                    ConstantValue::Signed(-(val as i64))
-                } else if val <= i64::MAX as u64 {
-                    ConstantValue::Signed(val as i64)
-                } else {
-                    ConstantValue::Unsigned(val)
-                };
+                if !filter.allows(&name) {
+                    continue;
+                }
+                let value = integer_constant(negated, val)
+                    .unwrap_or_else(|| wide_constant(negated, number));
                 debug!(name = %name, "extracted #define hex constant");
                 seen.insert(name.clone());
-                constants.push(ConstantDef { name, value });
+                constants.push(ConstantDef {
+                    name,
+                    value,
+                    doc: None,
+                });
+            } else if is_wide_int_literal(number) {
+                if !filter.allows(&name) {
+                    continue;
+                }
+                debug!(name = %name, literal = %number, "extracted #define wide constant");
+                seen.insert(name.clone());
+                constants.push(ConstantDef {
+                    name,
+                    value: wide_constant(negated, number),
+                    doc: None,
+                });
+            } else if let Some(literal) = parse_char_literal(number) {
+                if !filter.allows(&name) {
+                    continue;
+                }
+                let value = match char_constant(&literal, char_signed) {
+                    Ok(value) => value,
+                    Err(e) => {
+                        warn!(name = %name, literal = %number, err = %e, "skipping #define char constant");
+                        continue;
+                    }
+                };
+                let value = match (negated, value) {
+                    (false, value) => value,
+                    (true, ConstantValue::Signed(v)) => ConstantValue::Signed(-v),
+                    (true, ConstantValue::Unsigned(v)) => ConstantValue::Signed(-(v as i64)),
+                    (true, _) => continue,
+                };
+                debug!(name = %name, "extracted #define char constant");
+                seen.insert(name.clone());
+                constants.push(ConstantDef {
+                    name,
+                    value,
+                    doc: None,
+                });
+            }
+        }
+    }
+
+    constants
+}
+
+/// The constant for a `#define` integer literal of magnitude `val`, negated
+/// if `negated`. Values that fit `i64` are signed, larger positive ones
+/// unsigned; a negative value below `i64::MIN` has no representation and
+/// yields `None`.
+fn integer_constant(negated: bool, val: u64) -> Option<ConstantValue> {
+    if negated {
+        0i64.checked_sub_unsigned(val).map(ConstantValue::Signed)
+    } else {
+        Some(match i64::try_from(val) {
+            Ok(val) => ConstantValue::Signed(val),
+            Err(_) => ConstantValue::Unsigned(val),
+        })
+    }
+}
+
+/// The [`ConstantValue::Wide`] for integer literal `literal`, negated if
+/// `negated`.
+fn wide_constant(negated: bool, literal: &str) -> ConstantValue {
+    let digits = literal.trim_end_matches(['u', 'U', 'l', 'L']);
+    ConstantValue::Wide(if negated {
+        format!("-{digits}")
+    } else {
+        digits.to_string()
+    })
+}
+
+/// Whether `s` is a well-formed C integer literal whose value doesn't fit
+/// `u64`, which [`parse_hex_or_suffixed_int`] rejects.
+fn is_wide_int_literal(s: &str) -> bool {
+    if parse_hex_or_suffixed_int(s).is_some() {
+        return false;
+    }
+    let s = s.trim_end_matches(['u', 'U', 'l', 'L']);
+    let (digits, radix) = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => (hex, 16),
+        None if s.starts_with('0') => (s, 8),
+        None => (s, 10),
+    };
+    !digits.is_empty() && digits.chars().all(|c| c.is_digit(radix))
+}
+
+/// A C character literal token, split into its encoding prefix and the
+/// code units between the quotes (escapes decoded).
+struct CharLiteral {
+    prefix: CharPrefix,
+    units: Vec<u32>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CharPrefix {
+    /// `'a'`: type `int`, one unit per byte (UTF-8 for non-ASCII).
+    Plain,
+    /// `u8'a'`: `unsigned char` (C23).
+    Utf8,
+    /// `L'a'`: `wchar_t`.
+    Wide,
+    /// `u'a'`: `char16_t`.
+    Utf16,
+    /// `U'a'`: `char32_t`.
+    Utf32,
+}
+
+/// Parse a character literal token such as `'/'`, `'\n'`, `'\x7f'` or
+/// `L'é'`. Returns `None` for anything else.
+fn parse_char_literal(token: &str) -> Option<CharLiteral> {
+    let (prefix, rest) = if let Some(rest) = token.strip_prefix("u8") {
+        (CharPrefix::Utf8, rest)
+    } else if let Some(rest) = token.strip_prefix('L') {
+        (CharPrefix::Wide, rest)
+    } else if let Some(rest) = token.strip_prefix('u') {
+        (CharPrefix::Utf16, rest)
+    } else if let Some(rest) = token.strip_prefix('U') {
+        (CharPrefix::Utf32, rest)
+    } else {
+        (CharPrefix::Plain, token)
+    };
+    let body = rest.strip_prefix('\'')?.strip_suffix('\'')?;
+    let bytewise = matches!(prefix, CharPrefix::Plain | CharPrefix::Utf8);
+
+    let mut units = Vec::new();
+    let mut chars = body.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            if bytewise {
+                let mut buf = [0u8; 4];
+                units.extend(c.encode_utf8(&mut buf).bytes().map(u32::from));
+            } else {
+                units.push(c as u32);
+            }
+            continue;
+        }
+        let unit = match chars.next()? {
+            'n' => 0x0a,
+            't' => 0x09,
+            'r' => 0x0d,
+            'a' => 0x07,
+            'b' => 0x08,
+            'f' => 0x0c,
+            'v' => 0x0b,
+            'e' => 0x1b,
+            c @ ('\\' | '\'' | '"' | '?') => c as u32,
+            'x' => {
+                let mut digits = String::new();
+                while let Some(d) = chars.next_if(|d| d.is_ascii_hexdigit()) {
+                    digits.push(d);
+                }
+                u32::from_str_radix(&digits, 16).ok()?
+            }
+            c @ '0'..='7' => {
+                let mut value = c.to_digit(8)?;
+                for _ in 0..2 {
+                    match chars.next_if(|d| matches!(d, '0'..='7')) {
+                        Some(d) => value = value * 8 + d.to_digit(8)?,
+                        None => break,
+                    }
+                }
+                value
+            }
+            c @ ('u' | 'U') => {
+                let len = if c == 'u' { 4 } else { 8 };
+                let mut digits = String::new();
+                for _ in 0..len {
+                    digits.push(chars.next_if(|d| d.is_ascii_hexdigit())?);
+                }
+                let c = char::from_u32(u32::from_str_radix(&digits, 16).ok()?)?;
+                if bytewise {
+                    let mut buf = [0u8; 4];
+                    units.extend(c.encode_utf8(&mut buf).bytes().map(u32::from));
+                    continue;
+                }
+                c as u32
             }
+            _ => return None,
+        };
+        units.push(unit);
+    }
+    if units.is_empty() {
+        return None;
+    }
+    Some(CharLiteral { prefix, units })
+}
+
+/// The value of a character literal, following clang and GCC for the
+/// implementation-defined cases: a plain `'\xff'` is `-1` when `char` is
+/// signed, and a multi-character `'ab'` packs its bytes big-endian into an
+/// `int` (`0x6162`). Literals of more than four bytes, and multi-character
+/// wide literals, are rejected.
+fn char_constant(literal: &CharLiteral, char_signed: bool) -> Result<ConstantValue> {
+    let max_unit: u32 = match literal.prefix {
+        CharPrefix::Plain | CharPrefix::Utf8 => 0xff,
+        CharPrefix::Utf16 => 0xffff,
+        CharPrefix::Wide | CharPrefix::Utf32 => u32::MAX,
+    };
+    if let Some(unit) = literal.units.iter().find(|&&u| u > max_unit) {
+        anyhow::bail!("escape value {unit:#x} out of range for the literal's type");
+    }
+    match (literal.prefix, literal.units.as_slice()) {
+        (CharPrefix::Plain, [byte]) if char_signed => {
+            Ok(ConstantValue::Signed(*byte as u8 as i8 as i64))
+        }
+        (CharPrefix::Plain, [byte]) => Ok(ConstantValue::Signed(*byte as i64)),
+        (CharPrefix::Plain, bytes) if bytes.len() <= 4 => {
+            let packed = bytes.iter().fold(0u32, |acc, b| (acc << 8) | b);
+            debug!(value = packed, "multi-character constant");
+            Ok(ConstantValue::Signed(packed as i32 as i64))
+        }
+        (CharPrefix::Plain, _) => anyhow::bail!("multi-character constant longer than an int"),
+        (CharPrefix::Wide, [unit]) => Ok(ConstantValue::Signed(*unit as i64)),
+        (_, [unit]) => Ok(ConstantValue::Unsigned(*unit as u64)),
+        (_, _) => anyhow::bail!("multi-character constant with an encoding prefix"),
+    }
+}
+
+/// Fold object-like macros whose body is a constant expression, e.g.
+/// `#define PAGE_MASK (PAGE_SIZE - 1)` or `#define MAP_FLAGS (MAP_A | MAP_B)`.
+///
+/// Each candidate not already in `known` becomes a
+/// `static const __typeof__((NAME)) __bnd_eval_N = (NAME);` in a scratch
+/// translation unit that includes the partition's header (or its unsaved
+/// wrapper), and clang
+/// evaluates the initializer. Macros that don't fold are skipped.
+fn evaluate_macro_constants(
+    index: &Index,
+    header_path: &Path,
+    args: &[String],
+    unsaved: &[Unsaved],
+    entities: &[Entity],
+    in_scope: &impl Fn(&Entity) -> bool,
+    known: &HashSet<String>,
+) -> Vec<ConstantDef> {
+    let mut candidates = Vec::new();
+    for entity in entities {
+        if entity.get_kind() != EntityKind::MacroDefinition
+            || entity.is_function_like_macro()
+            || entity.is_builtin_macro()
+            || !in_scope(entity)
+        {
+            continue;
         }
+        let Some(name) = entity.get_name() else {
+            continue;
+        };
+        if !known.contains(&name) && !candidates.contains(&name) && is_constant_expression(entity) {
+            candidates.push(name);
+        }
+    }
+    if candidates.is_empty() {
+        return Vec::new();
     }
 
+    let mut source = format!("#include \"{}\"\n", header_path.display());
+    for (i, name) in candidates.iter().enumerate() {
+        source.push_str(&format!(
+            "static const __typeof__(({name})) __bnd_eval_{i} = ({name});\n"
+        ));
+    }
+    // `args` always carries `-x`, so the extension doesn't pick the language.
+    let eval_path = header_path.with_file_name("__bnd_eval.c");
+    let mut unsaved = unsaved.to_vec();
+    unsaved.push(Unsaved::new(&eval_path, &source));
+    let tu = match index
+        .parser(&eval_path)
+        .arguments(args)
+        .unsaved(&unsaved)
+        .parse()
+    {
+        Ok(tu) => tu,
+        Err(e) => {
+            warn!(err = ?e, "failed to parse macro evaluation unit, skipping");
+            return Vec::new();
+        }
+    };
+
+    let mut constants = Vec::new();
+    for entity in tu.get_entity().get_children() {
+        if entity.get_kind() != EntityKind::VarDecl {
+            continue;
+        }
+        let Some(slot) = entity
+            .get_name()
+            .and_then(|n| n.strip_prefix("__bnd_eval_")?.parse::<usize>().ok())
+        else {
+            continue;
+        };
+        let Some(name) = candidates.get(slot) else {
+            continue;
+        };
+        let value = match entity.evaluate() {
+            Some(EvaluationResult::SignedInteger(v)) => ConstantValue::Signed(v),
+            Some(EvaluationResult::UnsignedInteger(v)) => ConstantValue::Unsigned(v),
+            Some(EvaluationResult::Float(v)) => ConstantValue::Float(v),
+            other => {
+                trace!(name = %name, result = ?other, "macro did not fold to a number");
+                continue;
+            }
+        };
+        debug!(name = %name, value = ?value, "extracted #define by evaluation");
+        constants.push(ConstantDef {
+            name: name.clone(),
+            value,
+            doc: None,
+        });
+    }
     constants
 }
 
+/// Returns true if a macro's body only has tokens that can form a constant
+/// expression: numbers, identifiers, operators, and type keywords for casts
+/// and `sizeof`. This keeps statements and strings out of the evaluation
+/// unit, where a parse error could derail later candidates.
+fn is_constant_expression(macro_def: &Entity) -> bool {
+    const KEYWORDS: &[&str] = &[
+        "sizeof", "char", "short", "int", "long", "signed", "unsigned", "float", "double",
+    ];
+    const PUNCTUATION: &[&str] = &[
+        "(", ")", "+", "-", "*", "/", "%", "<<", ">>", "&", "|", "^", "~", "!", "<", ">", "<=",
+        ">=", "==", "!=", "&&", "||", "?", ":",
+    ];
+    let Some(range) = macro_def.get_range() else {
+        return false;
+    };
+    let tokens = range.tokenize();
+    // The first token is the macro name; clang sometimes appends a `#`.
+    let mut body = tokens.iter().skip(1).collect::<Vec<_>>();
+    if body.last().is_some_and(|t| t.get_spelling() == "#") {
+        body.pop();
+    }
+    !body.is_empty()
+        && body.iter().all(|t| {
+            let spelling = t.get_spelling();
+            match t.get_kind() {
+                TokenKind::Literal => !spelling.contains('"'),
+                TokenKind::Identifier => true,
+                TokenKind::Keyword => KEYWORDS.contains(&spelling.as_str()),
+                TokenKind::Punctuation => PUNCTUATION.contains(&spelling.as_str()),
+                TokenKind::Comment => true,
+            }
+        })
+}
+
 /// Parse a hex literal (`0x1F`) or a suffixed integer (`1U`, `0x10UL`, etc.)
 /// that `u64::from_str` can't handle. Returns None if not parseable.
 fn parse_hex_or_suffixed_int(s: &str) -> Option<u64> {
@@ -373,22 +1689,74 @@ fn parse_hex_or_suffixed_int(s: &str) -> Option<u64> {
 // Struct extraction
 // ---------------------------------------------------------------------------
 
-fn extract_struct(decl: &Declaration) -> Result<(StructDef, Vec<StructDef>)> {
-    extract_struct_from_entity(&decl.entity, &decl.name, false)
+fn extract_struct(decl: &Declaration, dm: DataModel) -> Result<(StructDef, Vec<StructDef>)> {
+    let is_union = decl.entity.get_kind() == EntityKind::UnionDecl;
+    let (mut s, nested) = extract_struct_from_entity(&decl.entity, &decl.name, is_union, dm)?;
+    s.doc = declaration_doc(decl);
+    s.deprecated = declaration_deprecation(decl);
+    Ok((s, nested))
 }
 
 fn extract_struct_from_entity(
     entity: &Entity,
     name: &str,
     is_union: bool,
+    dm: DataModel,
 ) -> Result<(StructDef, Vec<StructDef>)> {
     let ty = entity.get_type().context("struct has no type")?;
     let size = ty.get_sizeof().unwrap_or(0);
     let align = ty.get_alignof().unwrap_or(0);
 
+    let children = entity.get_children();
+    let anonymous_members = children.iter().filter(|c| is_anonymous_member(c)).count();
+    let mut anonymous_index = 0;
+    let last_field = children
+        .iter()
+        .rposition(|c| c.get_kind() == EntityKind::FieldDecl);
+
     let mut fields = Vec::new();
+    // C `(size, align)` of each field's type, parallel to `fields`.
+    let mut field_sizes = Vec::new();
     let mut nested_types = Vec::new();
-    for child in entity.get_children() {
+    for (index, child) in children.iter().enumerate() {
+        // C11 anonymous struct/union members (`union { int a; float b; };`)
+        // have no FieldDecl — libclang skips the implicit one — so we
+        // synthesize a field named `Anonymous` (`Anonymous1`, `Anonymous2`, …
+        // when there are several) to keep the parent's layout intact.
+        if is_anonymous_member(child) {
+            anonymous_index += 1;
+            let field_name = if anonymous_members == 1 {
+                "Anonymous".to_string()
+            } else {
+                format!("Anonymous{anonymous_index}")
+            };
+            let synthetic_name =
+                extract_anonymous_record(child, name, &field_name, &mut nested_types, dm)
+                    .with_context(|| format!("unsupported anonymous member '{field_name}'"))?;
+            trace!(field = %field_name, synthetic = %synthetic_name, "  anonymous member");
+            // The member has no declarator; its offset is that of its first
+            // field, looked up through the parent.
+            let offset = child
+                .get_children()
+                .iter()
+                .find(|c| c.get_kind() == EntityKind::FieldDecl)
+                .and_then(|c| c.get_name())
+                .and_then(|first| ty.get_offsetof(first).ok())
+                .map(|bits| bits / 8);
+            fields.push(FieldDef {
+                name: field_name,
+                ty: CType::Named {
+                    name: synthetic_name,
+                    resolved: None,
+                },
+                bitfield_width: None,
+                bitfield_offset: None,
+                offset,
+                bitfields: Vec::new(),
+            });
+            field_sizes.push(child.get_type().and_then(|t| type_size_align(&t)));
+            continue;
+        }
         if child.get_kind() != EntityKind::FieldDecl {
             continue;
         }
@@ -398,15 +1766,38 @@ fn extract_struct_from_entity(
         // Check for anonymous record type (unnamed struct/union used as a field type).
         // Clang gives these names like "union (unnamed at file.h:37:5)" which can't
         // be resolved. We extract them as separate TypeDefs with synthetic names.
-        let ctype =
-            match try_extract_anonymous_field(&field_type, name, &field_name, &mut nested_types) {
-                Some(synthetic_name) => CType::Named {
-                    name: synthetic_name,
-                    resolved: None,
-                },
-                None => map_clang_type(&field_type)
-                    .with_context(|| format!("unsupported type for field '{}'", field_name))?,
-            };
+        let ctype = match try_extract_anonymous_field(
+            &field_type,
+            name,
+            &field_name,
+            &mut nested_types,
+            dm,
+        ) {
+            Some(synthetic_name) => CType::Named {
+                name: synthetic_name,
+                resolved: None,
+            },
+            None if Some(index) == last_field
+                && field_type.get_kind() == TypeKind::IncompleteArray =>
+            {
+                // Flexible array member (`char data[];`): zero-length array
+                // so the struct size matches C. Elsewhere `T[]` decays to a
+                // pointer (see map_clang_type).
+                let elem = field_type
+                    .get_element_type()
+                    .context("flexible array member has no element type")?;
+                CType::Array {
+                    element: Box::new(
+                        map_clang_type(&elem, dm).with_context(|| {
+                            format!("unsupported type for field '{}'", field_name)
+                        })?,
+                    ),
+                    len: 0,
+                }
+            }
+            None => map_clang_type(&field_type, dm)
+                .with_context(|| format!("unsupported type for field '{}'", field_name))?,
+        };
 
         let bitfield_width = if child.is_bit_field() {
             child.get_bit_field_width()
@@ -419,13 +1810,31 @@ fn extract_struct_from_entity(
             None
         };
 
-        trace!(field = %field_name, ty = ?ctype, "  field");
+        let offset = child.get_offset_of_field().ok().map(|bits| bits / 8);
+        // A flexible array member occupies no space but keeps its alignment.
+        let size_align = match ctype {
+            CType::Array { len: 0, .. } => field_type
+                .get_element_type()
+                .and_then(|elem| elem.get_alignof().ok())
+                .map(|align| (0, align)),
+            _ => type_size_align(&field_type),
+        };
+
+        trace!(field = %field_name, ty = ?ctype, offset = ?offset, "  field");
         fields.push(FieldDef {
             name: field_name,
             ty: ctype,
             bitfield_width,
             bitfield_offset,
+            offset,
+            bitfields: Vec::new(),
         });
+        field_sizes.push(size_align);
+    }
+
+    let (mut fields, field_sizes) = pack_bitfields(fields, field_sizes, is_union);
+    if !is_union {
+        fields = pad_to_c_offsets(name, fields, &field_sizes, size, align);
     }
 
     Ok((
@@ -435,11 +1844,299 @@ fn extract_struct_from_entity(
             align,
             fields,
             is_union,
+            doc: doc_comment(entity),
+            source: source_location(entity),
+            deprecated: deprecation(entity),
         },
         nested_types,
     ))
 }
 
+/// Replace bitfield members with `_bitfield` storage fields.
+///
+/// windows-bindgen has no notion of bitfields, so — like win32metadata —
+/// each storage unit becomes one unsigned integer field (or a byte array
+/// when the bits straddle units) carrying a `NativeBitfieldAttribute` per
+/// member. Consecutive bitfields share a unit while they fit in it, so four
+/// `_Bool x : 1` members occupy a single byte, and a member that runs past
+/// its unit (`char a : 4; int b : 12;`) widens it. Member offsets are
+/// relative to the storage field. In a union every bitfield starts its own
+/// unit at offset 0.
+fn pack_bitfields(
+    fields: Vec<FieldDef>,
+    sizes: Vec<Option<(usize, usize)>>,
+    is_union: bool,
+) -> (Vec<FieldDef>, Vec<Option<(usize, usize)>>) {
+    if fields.iter().all(|f| f.bitfield_width.is_none()) {
+        return (fields, sizes);
+    }
+
+    struct Unit {
+        /// Byte offset of the storage within the struct.
+        start: usize,
+        /// Storage size in bytes.
+        size: usize,
+        /// First byte not taken by the field or unit before this one.
+        floor: usize,
+        /// True if the storage is a whole, naturally placed C unit (so it
+        /// can be an integer instead of a byte array).
+        aligned: bool,
+        members: Vec<Bitfield>,
+    }
+    enum Slot {
+        Field(FieldDef, Option<(usize, usize)>),
+        Unit(Unit),
+    }
+
+    let mut slots: Vec<Slot> = Vec::new();
+    for (field, size_align) in fields.into_iter().zip(sizes) {
+        let (Some(width), Some(bit_offset)) = (field.bitfield_width, field.bitfield_offset) else {
+            slots.push(Slot::Field(field, size_align));
+            continue;
+        };
+        let end_bit = bit_offset + width;
+        let unit_bytes = size_align.map_or(1, |(size, _)| size.max(1));
+
+        let prev_end = match slots.last_mut() {
+            Some(Slot::Unit(unit)) if !is_union => {
+                let unit_end = unit.start + unit.size;
+                if bit_offset >= unit.start * 8 && end_bit <= unit_end * 8 {
+                    None
+                } else if bit_offset < unit_end * 8 {
+                    // Starts inside the unit but runs past it, e.g. `int b : 12`
+                    // after `char a : 4`: widen the unit to the member's C unit,
+                    // or cover just the bytes when that would overlap the
+                    // previous field (packed structs).
+                    let natural = bit_offset / 8 / unit_bytes * unit_bytes;
+                    if natural >= unit.floor
+                        && natural <= unit.start
+                        && natural + unit_bytes >= unit_end
+                        && end_bit <= (natural + unit_bytes) * 8
+                    {
+                        let shift = (unit.start - natural) * 8;
+                        for member in &mut unit.members {
+                            member.offset += shift;
+                        }
+                        unit.start = natural;
+                        unit.size = unit_bytes;
+                        unit.aligned = true;
+                    } else {
+                        unit.size = end_bit.div_ceil(8) - unit.start;
+                        unit.aligned = false;
+                    }
+                    None
+                } else {
+                    Some(unit_end)
+                }
+            }
+            // C may share a unit with the preceding ordinary field
+            // (`char c; int a : 3;`); the storage must start after it.
+            Some(Slot::Field(field, size_align)) if !is_union => Some(
+                field
+                    .offset
+                    .zip(*size_align)
+                    .map_or(0, |(offset, (size, _))| offset + size),
+            ),
+            _ => Some(0),
+        };
+        if let Some(prev_end) = prev_end {
+            // Start a new storage unit at the member's natural C unit, or
+            // cover just its bytes if it straddles one (packed structs).
+            let start = bit_offset / 8 / unit_bytes * unit_bytes;
+            let unit = if start >= prev_end && end_bit <= (start + unit_bytes) * 8 {
+                Unit {
+                    start,
+                    size: unit_bytes,
+                    floor: prev_end,
+                    aligned: true,
+                    members: Vec::new(),
+                }
+            } else {
+                let start = (bit_offset / 8).max(prev_end);
+                Unit {
+                    start,
+                    size: end_bit.div_ceil(8) - start,
+                    floor: prev_end,
+                    aligned: false,
+                    members: Vec::new(),
+                }
+            };
+            slots.push(Slot::Unit(unit));
+        }
+        let Some(Slot::Unit(current)) = slots.last_mut() else {
+            unreachable!("a storage unit was just ensured");
+        };
+
+        // Unnamed bitfields (`int : 3;`, `int : 0;`) only affect placement.
+        if !field.name.is_empty() && width > 0 {
+            current.members.push(Bitfield {
+                name: field.name,
+                offset: bit_offset - current.start * 8,
+                width,
+            });
+        }
+    }
+
+    let unit_count = slots.iter().filter(|s| matches!(s, Slot::Unit(_))).count();
+    let mut unit_index = 0;
+    let mut out_fields = Vec::with_capacity(slots.len());
+    let mut out_sizes = Vec::with_capacity(slots.len());
+    for slot in slots {
+        match slot {
+            Slot::Field(field, size_align) => {
+                out_fields.push(field);
+                out_sizes.push(size_align);
+            }
+            Slot::Unit(unit) => {
+                unit_index += 1;
+                let name = if unit_count == 1 {
+                    "_bitfield".to_string()
+                } else {
+                    format!("_bitfield{unit_index}")
+                };
+                let (ty, align) = match (unit.aligned, unit.size) {
+                    (true, 1) => (CType::U8, 1),
+                    (true, 2) => (CType::U16, 2),
+                    (true, 4) => (CType::U32, 4),
+                    (true, 8) => (CType::U64, 8),
+                    _ => (
+                        CType::Array {
+                            element: Box::new(CType::U8),
+                            len: unit.size,
+                        },
+                        1,
+                    ),
+                };
+                trace!(field = %name, members = unit.members.len(), "  bitfield storage");
+                out_fields.push(FieldDef {
+                    name,
+                    ty,
+                    bitfield_width: None,
+                    bitfield_offset: None,
+                    offset: Some(unit.start),
+                    bitfields: unit.members,
+                });
+                out_sizes.push(Some((unit.size, align)));
+            }
+        }
+    }
+    (out_fields, out_sizes)
+}
+
+/// `(size, align)` of a type in bytes, if it is complete.
+///
+/// The size is C's; the alignment is that of the emitted type, which for a
+/// vector (an array of its element) is the element's.
+fn type_size_align(ty: &ClangType) -> Option<(usize, usize)> {
+    Some((ty.get_sizeof().ok()?, emitted_align(ty)?))
+}
+
+/// Alignment of `ty` once emitted: vectors, and arrays of them, align like
+/// their element; everything else keeps its C alignment.
+fn emitted_align(ty: &ClangType) -> Option<usize> {
+    let canonical = ty.get_canonical_type();
+    match canonical.get_kind() {
+        TypeKind::Vector | TypeKind::ConstantArray => emitted_align(&canonical.get_element_type()?),
+        _ => canonical.get_alignof().ok(),
+    }
+}
+
+/// Insert `_paddingN: [u8; n]` fields wherever sequential layout would not
+/// reproduce clang's field offsets or total size.
+///
+/// The struct is emitted with `SequentialLayout` and packing `pack`
+/// (its C alignment), so a field lands at its natural alignment capped by
+/// `pack`. That disagrees with C when a field is over-aligned
+/// (`__attribute__((aligned))`) or when its emitted type is less aligned
+/// than the C type (`long double` and `__int128` become byte arrays, vectors
+/// arrays of their element). `ExplicitLayout` can't be used instead:
+/// windows-bindgen emits every explicit-layout type as a Rust `union`.
+///
+/// Returns `fields` unchanged if any offset or size is unknown, or if a
+/// field would have to move backwards.
+fn pad_to_c_offsets(
+    struct_name: &str,
+    fields: Vec<FieldDef>,
+    sizes: &[Option<(usize, usize)>],
+    struct_size: usize,
+    pack: usize,
+) -> Vec<FieldDef> {
+    let pack = pack.max(1);
+    let align_up = |n: usize, a: usize| n.div_ceil(a) * a;
+    let padding = |index: usize, offset: usize, len: usize| FieldDef {
+        name: format!("_padding{index}"),
+        ty: CType::Array {
+            element: Box::new(CType::U8),
+            len,
+        },
+        bitfield_width: None,
+        bitfield_offset: None,
+        offset: Some(offset),
+        bitfields: Vec::new(),
+    };
+
+    let mut placed = Vec::with_capacity(fields.len());
+    let mut pad_count = 0;
+    let mut cursor = 0;
+    let mut emitted_struct_align = 1;
+    for (field, size) in fields.iter().zip(sizes) {
+        let (Some(offset), Some((size, align))) = (field.offset, *size) else {
+            return fields;
+        };
+        let emitted_align = if field.ty.is_opaque_bytes() {
+            1
+        } else {
+            align.clamp(1, pack)
+        };
+        emitted_struct_align = emitted_struct_align.max(emitted_align);
+        let natural = align_up(cursor, emitted_align);
+        if natural > offset {
+            warn!(
+                name = struct_name,
+                field = %field.name,
+                offset,
+                sequential = natural,
+                "field offset not reproducible with sequential layout"
+            );
+            return fields;
+        }
+        if natural < offset {
+            placed.push((cursor, offset - cursor));
+        }
+        cursor = offset + size;
+    }
+
+    // Trailing padding the emitted struct would not add by itself.
+    let tail = if align_up(cursor, emitted_struct_align) == struct_size {
+        0
+    } else {
+        struct_size.saturating_sub(cursor)
+    };
+    if placed.is_empty() && tail == 0 {
+        return fields;
+    }
+    let mut out = Vec::with_capacity(fields.len() + placed.len() + 1);
+    let mut pads = placed.into_iter().peekable();
+    for field in fields {
+        let offset = field.offset.unwrap_or_default();
+        if let Some((pad_offset, len)) = pads.next_if(|&(at, len)| at + len == offset) {
+            out.push(padding(pad_count, pad_offset, len));
+            pad_count += 1;
+        }
+        out.push(field);
+    }
+    if tail > 0 {
+        out.push(padding(pad_count, cursor, tail));
+        pad_count += 1;
+    }
+    debug!(
+        name = struct_name,
+        padding = pad_count,
+        "inserted layout padding"
+    );
+    out
+}
+
 /// Try to extract an anonymous record field type as a synthetic named type.
 ///
 /// When a struct/union contains a field whose type is an anonymous record
@@ -453,6 +2150,7 @@ fn try_extract_anonymous_field(
     parent_name: &str,
     field_name: &str,
     nested_types: &mut Vec<StructDef>,
+    dm: DataModel,
 ) -> Option<String> {
     let canonical = field_type.get_canonical_type();
     if canonical.get_kind() != TypeKind::Record {
@@ -462,10 +2160,31 @@ fn try_extract_anonymous_field(
     if !decl.is_anonymous() {
         return None;
     }
+    extract_anonymous_record(&decl, parent_name, field_name, nested_types, dm).ok()
+}
+
+/// Returns true if `entity` is a C11 anonymous struct/union member, i.e. a
+/// record declared inside another record without a field declarator.
+fn is_anonymous_member(entity: &Entity) -> bool {
+    matches!(
+        entity.get_kind(),
+        EntityKind::StructDecl | EntityKind::UnionDecl
+    ) && entity.is_anonymous_record_decl()
+}
+
+/// Extract an anonymous record declaration as a separate `StructDef` named
+/// `ParentName_FieldName`, returning the synthetic name.
+fn extract_anonymous_record(
+    decl: &Entity,
+    parent_name: &str,
+    field_name: &str,
+    nested_types: &mut Vec<StructDef>,
+    dm: DataModel,
+) -> Result<String> {
     let is_nested_union = decl.get_kind() == EntityKind::UnionDecl;
     let synthetic_name = format!("{}_{}", parent_name, field_name);
 
-    match extract_struct_from_entity(&decl, &synthetic_name, is_nested_union) {
+    match extract_struct_from_entity(decl, &synthetic_name, is_nested_union, dm) {
         Ok((nested, mut more)) => {
             let kind = if is_nested_union { "union" } else { "struct" };
             debug!(
@@ -476,7 +2195,7 @@ fn try_extract_anonymous_field(
             );
             nested_types.push(nested);
             nested_types.append(&mut more); // handle deeply nested anonymous types
-            Some(synthetic_name)
+            Ok(synthetic_name)
         }
         Err(e) => {
             warn!(
@@ -485,7 +2204,7 @@ fn try_extract_anonymous_field(
                 err = %e,
                 "failed to extract anonymous nested type"
             );
-            None
+            Err(e)
         }
     }
 }
@@ -494,45 +2213,175 @@ fn try_extract_anonymous_field(
 // Enum extraction
 // ---------------------------------------------------------------------------
 
-fn extract_enum(decl: &Declaration) -> Result<EnumDef> {
-    let underlying = decl
-        .entity
-        .get_enum_underlying_type()
-        .context("enum has no underlying type")?;
-    let underlying_ctype = map_clang_type(&underlying).unwrap_or(CType::I32); // fallback to i32
+fn extract_enum(decl: &Declaration, dm: DataModel) -> Result<EnumDef> {
+    let underlying = enum_base_type(&decl.entity)?;
+    let underlying_ctype = match map_clang_type(&underlying, dm) {
+        Ok(ctype) => ctype,
+        Err(e) => {
+            let ctype = integer_by_layout(&underlying);
+            warn!(
+                name = %decl.name,
+                underlying = %underlying.get_display_name(),
+                fallback = ?ctype,
+                error = %e,
+                "unsupported enum base type, using an integer of the same size and signedness"
+            );
+            ctype
+        }
+    };
+
+    let mut variants = Vec::new();
+    for child in decl.entity.get_children() {
+        if child.get_kind() != EntityKind::EnumConstantDecl {
+            continue;
+        }
+        let name = child.get_name().unwrap_or_default();
+        let (signed, unsigned) = child.get_enum_constant_value().unwrap_or((0, 0));
+        variants.push(EnumVariant {
+            name,
+            signed_value: signed,
+            unsigned_value: unsigned,
+        });
+    }
+
+    let is_flags = looks_like_flags(&variants);
+    Ok(EnumDef {
+        name: decl.name.clone(),
+        underlying_type: underlying_ctype,
+        variants,
+        is_flags,
+        doc: declaration_doc(decl),
+        source: source_location(&decl.entity),
+    })
+}
+
+/// Pass enums with a base narrower than `int` as `i32`, for
+/// `enum_repr = "int"`: parameters and return values of such an enum
+/// declared in this partition become `i32`, the parameters keeping the
+/// enum's name as their native type. The enum itself, and so every struct
+/// field of its type, keeps the C width.
+fn promote_small_enum_args(functions: &mut [FunctionDef], enums: &[EnumDef]) {
+    let small: HashSet<&str> = enums
+        .iter()
+        .filter(|en| {
+            matches!(
+                en.underlying_type,
+                CType::I8 | CType::U8 | CType::I16 | CType::U16 | CType::Bool | CType::Char16
+            )
+        })
+        .map(|en| en.name.as_str())
+        .collect();
+    let promoted = |ty: &CType| match ty {
+        CType::Named { name, .. } if small.contains(name.as_str()) => Some(name.clone()),
+        _ => None,
+    };
+    for f in functions {
+        if let Some(name) = promoted(&f.return_type) {
+            debug!(function = %f.name, r#enum = %name, "returning enum as i32");
+            f.return_type = CType::I32;
+        }
+        for param in &mut f.params {
+            if let Some(name) = promoted(&param.ty) {
+                debug!(function = %f.name, param = %param.name, r#enum = %name, "passing enum as i32");
+                param.ty = CType::I32;
+                param.native_type.get_or_insert(name);
+            }
+        }
+    }
+}
+
+/// The integer type backing enum `decl`, in canonical form: a fixed base
+/// spelled through a typedef (`enum E : uint64_t`, or a C++ `enum class`
+/// base) is mapped as the builtin integer it names on the target, not
+/// through the typedef.
+fn enum_base_type<'tu>(decl: &Entity<'tu>) -> Result<ClangType<'tu>> {
+    let underlying = decl
+        .get_enum_underlying_type()
+        .context("enum has no underlying type")?;
+    Ok(underlying.get_canonical_type())
+}
+
+/// The tag of `ty` if it is a named record with no definition in the
+/// translation unit (`struct foo` after only `struct foo;`).
+fn incomplete_record_tag(ty: &ClangType) -> Option<String> {
+    let ty = match ty.get_kind() {
+        TypeKind::Elaborated => ty.get_elaborated_type()?,
+        _ => *ty,
+    };
+    if ty.get_kind() != TypeKind::Record || ty.get_sizeof().is_ok() {
+        return None;
+    }
+    ty.get_declaration()?
+        .get_name()
+        .filter(|name| name != "__va_list_tag")
+}
+
+/// The mangled symbol of a function or variable with C++ linkage (one
+/// declared in a C++ namespace, or outside `extern "C"` in a C++ header), which is what
+/// the library exports. `None` for C linkage.
+fn cpp_symbol(entity: &Entity) -> Option<String> {
+    let symbol = entity.get_mangled_name()?;
+    // Mach-O prefixes every symbol with `_`; the loader adds it back.
+    let symbol = match symbol.strip_prefix("__Z") {
+        Some(rest) => format!("_Z{rest}"),
+        None => symbol,
+    };
+    (symbol.starts_with("_Z") || symbol.starts_with('?')).then_some(symbol)
+}
 
-    let mut variants = Vec::new();
-    for child in decl.entity.get_children() {
-        if child.get_kind() != EntityKind::EnumConstantDecl {
-            continue;
-        }
-        let name = child.get_name().unwrap_or_default();
-        let (signed, unsigned) = child.get_enum_constant_value().unwrap_or((0, 0));
-        variants.push(EnumVariant {
-            name,
-            signed_value: signed,
-            unsigned_value: unsigned,
-        });
+/// The fixed-width integer with `ty`'s size and signedness, defaulting to
+/// `i32` when clang can't size it.
+fn integer_by_layout(ty: &ClangType) -> CType {
+    let signed = !ty.is_unsigned_integer();
+    match (ty.get_sizeof().unwrap_or(4), signed) {
+        (1, true) => CType::I8,
+        (1, false) => CType::U8,
+        (2, true) => CType::I16,
+        (2, false) => CType::U16,
+        (8, true) => CType::I64,
+        (8, false) => CType::U64,
+        (_, true) => CType::I32,
+        (_, false) => CType::U32,
     }
+}
 
-    Ok(EnumDef {
-        name: decl.name.clone(),
-        underlying_type: underlying_ctype,
-        variants,
-    })
+/// Heuristic for bitmask enums: every non-zero value is a distinct power of
+/// two, and the values are not simply a contiguous `0..n` / `1..n` run (so
+/// `enum { RED, GREEN, BLUE }` = 0, 1, 2 is not mistaken for flags).
+fn looks_like_flags(variants: &[EnumVariant]) -> bool {
+    let mut values: Vec<u64> = variants.iter().map(|v| v.unsigned_value).collect();
+    values.sort_unstable();
+    values.dedup();
+    if values.len() != variants.len() {
+        return false;
+    }
+    let nonzero: Vec<u64> = values.iter().copied().filter(|&v| v != 0).collect();
+    if nonzero.len() < 2 || !nonzero.iter().all(|v| v.is_power_of_two()) {
+        return false;
+    }
+    let contiguous = values.windows(2).all(|w| w[1] == w[0] + 1);
+    !contiguous
 }
 
 // ---------------------------------------------------------------------------
 // Function extraction
 // ---------------------------------------------------------------------------
 
-fn extract_function(decl: &Declaration) -> Result<FunctionDef> {
+fn extract_function(
+    decl: &Declaration,
+    dm: DataModel,
+    string_pointers: bool,
+) -> Result<FunctionDef> {
     let fn_type = decl.entity.get_type().context("function has no type")?;
 
     let ret_type = fn_type
         .get_result_type()
         .context("function has no return type")?;
-    let return_ctype = map_clang_type(&ret_type).unwrap_or(CType::Void);
+    reject_by_value(&ret_type).context("unsupported return type")?;
+    let mut return_ctype = map_clang_type(&ret_type, dm).context("unsupported return type")?;
+    if string_pointers {
+        return_ctype = string_pointer(&ret_type, return_ctype);
+    }
 
     let calling_convention = fn_type
         .get_calling_convention()
@@ -547,46 +2396,517 @@ fn extract_function(decl: &Declaration) -> Result<FunctionDef> {
         let name = arg_entity
             .get_name()
             .unwrap_or_else(|| format!("param{}", i));
-        let ty = if i < arg_types.len() {
-            map_clang_type(&arg_types[i]).unwrap_or(CType::Void)
-        } else {
-            CType::Void
-        };
-        // C array parameters decay to pointers (e.g. `const struct timespec t[2]` → `*timespec`).
-        // We must do this here because ELEMENT_TYPE_ARRAY blobs in method signatures can confuse
-        // windows-bindgen's reader which doesn't consume all ArrayShape fields.
-        let ty = match ty {
-            CType::Array { element, .. } => CType::Ptr {
-                pointee: element,
-                is_const: false,
-            },
-            other => other,
+        let (ty, spelling) = match arg_types.get(i) {
+            Some(arg_type) => {
+                let mut ty = map_param_type(arg_type, dm)
+                    .with_context(|| format!("unsupported type for parameter '{name}'"))?;
+                if string_pointers {
+                    ty = string_pointer(arg_type, ty);
+                }
+                (ty, Some(arg_type.get_display_name()))
+            }
+            None => (CType::Void, None),
         };
-        params.push(ParamDef { name, ty });
+        let native_type = spelling.filter(|_| ty.has_inner_ptr_const());
+        params.push(ParamDef {
+            name,
+            ty,
+            direction: None,
+            native_type,
+            array_len: None,
+        });
     }
 
     Ok(FunctionDef {
         name: decl.name.clone(),
+        import_name: cpp_symbol(&decl.entity),
         return_type: return_ctype,
         params,
         calling_convention,
+        is_variadic: fn_type.is_variadic(),
+        doc: declaration_doc(decl),
+        source: source_location(&decl.entity),
+        deprecated: declaration_deprecation(decl),
+        inline: false,
+        free_with: None,
+        library: None,
+        sets_last_error: false,
+        charset: string_charset(std::iter::once(&ret_type).chain(&arg_types)),
+    })
+}
+
+/// A `char *` spelled as such (not through a typedef) as win32metadata's
+/// `PSTR`, or `PCSTR` for `const char *`, under `string_pointers`. Any other
+/// type, including `signed` / `unsigned char *` byte buffers, is returned
+/// unchanged.
+fn string_pointer(ty: &ClangType, ctype: CType) -> CType {
+    let is_char_pointer = ty.get_kind() == TypeKind::Pointer
+        && ty.get_pointee_type().is_some_and(|pointee| {
+            matches!(
+                pointee.get_canonical_type().get_kind(),
+                TypeKind::CharS | TypeKind::CharU
+            )
+        });
+    match ctype {
+        CType::Ptr { is_const, .. } if is_char_pointer => CType::Named {
+            name: if is_const { "PCSTR" } else { "PSTR" }.to_string(),
+            resolved: Some(Box::new(ctype)),
+        },
+        other => other,
+    }
+}
+
+/// The charset of a function whose string pointers (`char *` or
+/// `wchar_t *`, at any pointer depth) all have one width. `None` when it
+/// has no strings or mixes both.
+fn string_charset<'a, 'tu: 'a>(types: impl Iterator<Item = &'a ClangType<'tu>>) -> Option<CharSet> {
+    let (mut narrow, mut wide) = (false, false);
+    for ty in types {
+        let mut pointee = None;
+        let mut ty = *ty;
+        while let Some(inner) = ty
+            .get_pointee_type()
+            .or_else(|| ty.get_canonical_type().get_pointee_type())
+        {
+            pointee = Some(inner);
+            ty = inner;
+        }
+        let Some(pointee) = pointee else {
+            continue;
+        };
+        if is_wchar(pointee) {
+            wide = true;
+        } else if matches!(
+            pointee.get_canonical_type().get_kind(),
+            TypeKind::CharS | TypeKind::CharU
+        ) {
+            narrow = true;
+        }
+    }
+    match (narrow, wide) {
+        (true, false) => Some(CharSet::Ansi),
+        (false, true) => Some(CharSet::Unicode),
+        _ => None,
+    }
+}
+
+/// Whether `ty` is `wchar_t`: the C++ builtin, or the C typedef of that
+/// name (whose canonical type is a plain integer).
+fn is_wchar(mut ty: ClangType) -> bool {
+    loop {
+        ty = match ty.get_kind() {
+            TypeKind::WChar => return true,
+            TypeKind::Elaborated => match ty.get_elaborated_type() {
+                Some(inner) => inner,
+                None => return false,
+            },
+            TypeKind::Typedef => {
+                let Some(decl) = ty.get_declaration() else {
+                    return false;
+                };
+                if decl.get_name().as_deref() == Some("wchar_t") {
+                    return true;
+                }
+                match decl.get_typedef_underlying_type() {
+                    Some(inner) => inner,
+                    None => return false,
+                }
+            }
+            _ => return false,
+        };
+    }
+}
+
+/// Whether a function has no exported symbol: it is `static`, or an
+/// `inline` definition not declared `extern` (C99 inline definitions and
+/// C++ inline functions are only emitted where used).
+fn lacks_symbol(entity: &Entity) -> bool {
+    match entity.get_storage_class() {
+        Some(StorageClass::Static) => true,
+        Some(StorageClass::Extern) => false,
+        _ => entity.is_inline_function(),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Deprecation
+// ---------------------------------------------------------------------------
+
+/// Deprecation message for a sonar declaration, checking the originating
+/// typedef too (see [`declaration_doc`]).
+fn declaration_deprecation(decl: &Declaration) -> Option<String> {
+    deprecation(&decl.entity).or_else(|| decl.source.as_ref().and_then(deprecation))
+}
+
+/// Return `Some(message)` if `entity` is marked `deprecated` (via
+/// `__attribute__((deprecated))` or `[[deprecated]]`). The message is empty
+/// when the attribute has none.
+fn deprecation(entity: &Entity) -> Option<String> {
+    if entity.get_availability() != Availability::Deprecated {
+        return None;
+    }
+    // libclang has no API for the attribute argument, so read the string
+    // literal(s) from the attribute's tokens.
+    let message = entity
+        .get_children()
+        .iter()
+        .filter(|c| c.get_kind() == EntityKind::UnexposedAttr)
+        .filter_map(|attr| attr.get_range())
+        .map(|range| range.tokenize())
+        .find(|tokens| {
+            tokens
+                .iter()
+                .any(|t| t.get_spelling().contains("deprecated"))
+        })
+        .map(|tokens| {
+            tokens
+                .iter()
+                .filter(|t| t.get_kind() == TokenKind::Literal)
+                .filter_map(|t| {
+                    let spelling = t.get_spelling();
+                    spelling
+                        .strip_prefix('"')
+                        .and_then(|s| s.strip_suffix('"'))
+                        .map(str::to_string)
+                })
+                .collect::<String>()
+        })
+        .unwrap_or_default();
+    Some(message)
+}
+
+// ---------------------------------------------------------------------------
+// Doc comments
+// ---------------------------------------------------------------------------
+
+/// Doc comment for a sonar declaration. For `typedef struct { … } Name;`
+/// clang attaches the comment to the typedef, which sonar keeps as `source`.
+fn declaration_doc(decl: &Declaration) -> Option<String> {
+    doc_comment(&decl.entity).or_else(|| decl.source.as_ref().and_then(doc_comment))
+}
+
+/// Return the doc comment (`///`, `/** */`, …) attached to `entity`, with
+/// comment markers and leading `*` gutters stripped.
+fn doc_comment(entity: &Entity) -> Option<String> {
+    clean_comment(&entity.get_comment()?)
+}
+
+/// Set the doc of every constant that came from a `#define`. clang attaches
+/// no comments to macros, so they are read from the header: a doc comment
+/// (`///`, `/** */`) on the lines right above the macro, or else any
+/// comment trailing its definition (`#define FOO 1 // the foo flag`).
+fn attach_macro_docs(entities: &[Entity], constants: &mut [ConstantDef]) {
+    let macros: HashMap<String, &Entity> = entities
+        .iter()
+        .filter(|e| e.get_kind() == EntityKind::MacroDefinition)
+        .filter_map(|e| Some((e.get_name()?, e)))
+        .collect();
+    let mut sources: HashMap<PathBuf, Vec<String>> = HashMap::new();
+    for constant in constants.iter_mut() {
+        let Some(entity) = macros.get(&constant.name) else {
+            continue;
+        };
+        constant.doc = macro_doc(entity, &mut sources);
+        if let Some(doc) = &constant.doc {
+            trace!(name = %constant.name, doc = %doc, "#define doc comment");
+        }
+    }
+}
+
+/// The doc comment of macro `entity`, see [`attach_macro_docs`]. `sources`
+/// caches the lines of each header read so far.
+fn macro_doc(entity: &Entity, sources: &mut HashMap<PathBuf, Vec<String>>) -> Option<String> {
+    let range = entity.get_range()?;
+    let start = range.get_start().get_file_location();
+    let end = range.get_end().get_file_location();
+    let file = start.file?;
+    let lines = sources
+        .entry(file.get_path())
+        .or_insert_with(|| match file.get_contents() {
+            Some(contents) => contents.lines().map(str::to_string).collect(),
+            None => Vec::new(),
+        });
+
+    // Lines are 1-based; `above` is the index of the line before the macro.
+    let above = (start.line as usize).checked_sub(2);
+    let raw = above
+        .and_then(|above| comment_above(lines, above))
+        .or_else(|| {
+            let line = lines.get(end.line.max(start.line) as usize - 1)?;
+            trailing_comment(line).map(str::to_string)
+        })?;
+    clean_comment(&raw)
+}
+
+/// The doc comment (`///` lines or a `/** */` block) ending on line
+/// `last` (0-based), if any.
+fn comment_above(lines: &[String], last: usize) -> Option<String> {
+    let is_line_doc = |line: &str| line.starts_with("///") || line.starts_with("//!");
+    let trimmed = lines.get(last)?.trim();
+    let first = if is_line_doc(trimmed) {
+        let run = lines[..=last]
+            .iter()
+            .rev()
+            .take_while(|line| is_line_doc(line.trim()))
+            .count();
+        last + 1 - run
+    } else if trimmed.ends_with("*/") {
+        let first = lines[..=last]
+            .iter()
+            .rposition(|line| line.contains("/*"))?;
+        let opening = lines[first].trim_start();
+        if !(opening.starts_with("/**") || opening.starts_with("/*!"))
+            || opening.starts_with("/**/")
+        {
+            return None;
+        }
+        first
+    } else {
+        return None;
+    };
+    Some(lines[first..=last].join("\n"))
+}
+
+/// The comment at the end of a macro's last line, skipping `//` and `/*`
+/// inside string and character literals.
+fn trailing_comment(line: &str) -> Option<&str> {
+    let bytes = line.as_bytes();
+    let mut quote = None;
+    let mut i = 0;
+    while i < bytes.len() {
+        match (quote, bytes[i]) {
+            (Some(_), b'\\') => i += 1,
+            (Some(q), c) if c == q => quote = None,
+            (None, c @ (b'"' | b'\'')) => quote = Some(c),
+            (None, b'/') if matches!(bytes.get(i + 1), Some(b'/' | b'*')) => {
+                return Some(&line[i..]);
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Strip comment markers and leading `*` gutters from the comment text
+/// `raw`, and drop blank lines around it.
+fn clean_comment(raw: &str) -> Option<String> {
+    let lines: Vec<&str> = raw
+        .lines()
+        .map(|line| {
+            let line = line.trim();
+            let line = [
+                "///<", "//!<", "///", "//!", "/**<", "/*!<", "/**", "/*!", "//", "/*",
+            ]
+            .iter()
+            .find_map(|marker| line.strip_prefix(marker))
+            .unwrap_or(line);
+            let line = line.strip_suffix("*/").unwrap_or(line);
+            let line = line.trim_start();
+            let line = line.strip_prefix('*').unwrap_or(line);
+            line.trim()
+        })
+        .collect();
+
+    let start = lines.iter().position(|l| !l.is_empty())?;
+    let end = lines.iter().rposition(|l| !l.is_empty())?;
+    Some(lines[start..=end].join("\n"))
+}
+
+// ---------------------------------------------------------------------------
+// Complex numbers
+// ---------------------------------------------------------------------------
+
+/// Synthetic struct names for C99 `_Complex` types, by element type.
+const COMPLEX_TYPES: [(&str, CType); 2] = [("ComplexF32", CType::F32), ("ComplexF64", CType::F64)];
+
+/// Declare a `{ re, im }` struct for each `_Complex` type the partition
+/// uses. Partitions sharing one are deduplicated like any other struct, so
+/// it is emitted once and referenced everywhere else.
+fn add_complex_structs(partition: &mut Partition) {
+    let mut used = HashSet::new();
+    let mut visit = |name: &str| {
+        used.insert(name.to_string());
+    };
+    for s in &partition.structs {
+        for field in &s.fields {
+            field.ty.for_each_named(&mut visit);
+        }
+    }
+    for f in &partition.functions {
+        f.return_type.for_each_named(&mut visit);
+        for param in &f.params {
+            param.ty.for_each_named(&mut visit);
+        }
+    }
+    for td in &partition.typedefs {
+        td.underlying_type.for_each_named(&mut visit);
+    }
+    for g in &partition.variables {
+        g.ty.for_each_named(&mut visit);
+    }
+
+    for (name, element) in &COMPLEX_TYPES {
+        if !used.contains(*name) || partition.structs.iter().any(|s| s.name == *name) {
+            continue;
+        }
+        // Laid out like `T[2]`: same size and alignment as the C type.
+        let size = match element {
+            CType::F32 => 4,
+            _ => 8,
+        };
+        let field = |field_name: &str, offset| FieldDef {
+            name: field_name.to_string(),
+            ty: element.clone(),
+            bitfield_width: None,
+            bitfield_offset: None,
+            offset: Some(offset),
+            bitfields: Vec::new(),
+        };
+        debug!(name, namespace = %partition.namespace, "synthesized complex struct");
+        partition.structs.push(StructDef {
+            name: name.to_string(),
+            size: 2 * size,
+            align: size,
+            fields: vec![field("re", 0), field("im", size)],
+            is_union: false,
+            doc: None,
+            source: None,
+            deprecated: None,
+        });
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Source locations
+// ---------------------------------------------------------------------------
+
+/// The header and line `entity` is declared at, with the path as clang
+/// reports it. [`relativize_sources`] rewrites it once the partition is
+/// complete.
+fn source_location(entity: &Entity) -> Option<SourceLocation> {
+    let location = entity.get_location()?.get_file_location();
+    Some(SourceLocation {
+        file: location.file?.get_path().display().to_string(),
+        line: location.line,
     })
 }
 
+/// Make every declaration's source path relative to `base_dir`, with `/`
+/// separators, so the winmd does not depend on where the tree is checked
+/// out. Headers outside `base_dir` keep their absolute path.
+fn relativize_sources(partition: &mut Partition, base_dir: &Path) {
+    let Ok(base_dir) = base_dir.canonicalize() else {
+        return;
+    };
+    let sources = partition
+        .structs
+        .iter_mut()
+        .map(|s| &mut s.source)
+        .chain(partition.enums.iter_mut().map(|e| &mut e.source))
+        .chain(partition.functions.iter_mut().map(|f| &mut f.source))
+        .chain(partition.typedefs.iter_mut().map(|td| &mut td.source))
+        .chain(partition.variables.iter_mut().map(|g| &mut g.source));
+    for source in sources.flatten() {
+        let Ok(path) = Path::new(&source.file).canonicalize() else {
+            continue;
+        };
+        if let Ok(relative) = path.strip_prefix(&base_dir) {
+            source.file = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Typedef extraction
 // ---------------------------------------------------------------------------
 
-fn extract_typedef_from_entity(entity: &Entity, name: &str) -> Result<TypedefDef> {
+/// Map a parameter type, decaying C arrays to pointers (e.g. `const struct
+/// timespec t[2]` → `*timespec`), including arrays spelled through a typedef
+/// (`key_t k` with `typedef unsigned char key_t[32]` → `*u8`).
+///
+/// We must do this here because ELEMENT_TYPE_ARRAY blobs in method signatures
+/// can confuse windows-bindgen's reader which doesn't consume all ArrayShape
+/// fields, and an array typedef passed by value would copy the whole buffer.
+fn map_param_type(ty: &ClangType, dm: DataModel) -> Result<CType> {
+    reject_by_value(ty)?;
+    let canonical = ty.get_canonical_type();
+    let ty = match map_clang_type(ty, dm)? {
+        CType::Array { element, .. } => CType::Ptr {
+            pointee: element,
+            is_const: false,
+        },
+        CType::Named { .. } if canonical.get_kind() == TypeKind::ConstantArray => {
+            let element = canonical
+                .get_element_type()
+                .context("array has no element type")?;
+            CType::Ptr {
+                pointee: Box::new(map_clang_type(&element, dm)?),
+                is_const: false,
+            }
+        }
+        other => other,
+    };
+    Ok(ty)
+}
+
+/// Vectors are passed and returned in SIMD registers, `long double` in x87
+/// or floating-point registers and `__int128` in register pairs, which
+/// neither a pointer nor the fixed buffer they are stored as reproduces, so
+/// functions taking or returning one by value are dropped.
+fn reject_by_value(ty: &ClangType) -> Result<()> {
+    match ty.get_canonical_type().get_kind() {
+        TypeKind::Vector => {
+            anyhow::bail!("vector type `{}` passed by value", ty.get_display_name())
+        }
+        TypeKind::LongDouble | TypeKind::Int128 | TypeKind::UInt128 => {
+            anyhow::bail!("`{}` passed by value", ty.get_display_name())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// An underlying type that can't be mapped becomes `void`, unless `strict`
+/// (the partition's `strict_signatures`), where it is an error.
+fn extract_typedef_from_entity(
+    entity: &Entity,
+    name: &str,
+    dm: DataModel,
+    strict: bool,
+) -> Result<TypedefDef> {
     let underlying = entity
         .get_typedef_underlying_type()
         .context("typedef has no underlying type")?;
-    let ctype = map_clang_type(&underlying).unwrap_or(CType::Void);
+    let ctype = match size_typedef(name, &underlying, dm) {
+        Some(ctype) => ctype,
+        None if strict => map_clang_type(&underlying, dm).context("unsupported underlying type")?,
+        None => map_clang_type(&underlying, dm).unwrap_or(CType::Void),
+    };
     trace!(name = %name, ty = ?ctype, "typedef underlying type");
 
+    let canonical = underlying.get_canonical_type();
+    let array_layout = match (canonical.get_sizeof(), canonical.get_alignof()) {
+        (Ok(size), Ok(align))
+            if matches!(
+                canonical.get_kind(),
+                TypeKind::ConstantArray | TypeKind::Vector
+            ) =>
+        {
+            Some((size, align))
+        }
+        _ => None,
+    };
+
     Ok(TypedefDef {
         name: name.to_string(),
         underlying_type: ctype,
+        source: source_location(entity),
+        free_with: None,
+        array_layout,
     })
 }
 
@@ -594,7 +2914,22 @@ fn extract_typedef_from_entity(entity: &Entity, name: &str) -> Result<TypedefDef
 // Type mapping: clang TypeKind → CType
 // ---------------------------------------------------------------------------
 
-fn map_clang_type(ty: &ClangType) -> Result<CType> {
+fn map_clang_type(ty: &ClangType, dm: DataModel) -> Result<CType> {
+    map_clang_type_at(ty, dm, 0)
+}
+
+/// Typedef-of-typedef chains deeper than this fall back to clang's canonical
+/// type instead of being followed link by link.
+const MAX_TYPEDEF_DEPTH: usize = 32;
+
+/// [`map_clang_type`], tracking how many typedef links have been followed.
+fn map_clang_type_at(ty: &ClangType, dm: DataModel, depth: usize) -> Result<CType> {
+    // libclang reports qualifiers as flags on the type, not as a separate
+    // kind, so `volatile T` and `T *restrict` map exactly like the
+    // unqualified type below. winmd has no way to express either.
+    if ty.is_volatile_qualified() || ty.is_restrict_qualified() {
+        trace!(ty = %ty.get_display_name(), "dropping volatile/restrict qualifier");
+    }
     match ty.get_kind() {
         TypeKind::Void => Ok(CType::Void),
         TypeKind::Bool => Ok(CType::Bool),
@@ -604,30 +2939,102 @@ fn map_clang_type(ty: &ClangType) -> Result<CType> {
         TypeKind::UShort => Ok(CType::U16),
         TypeKind::Int => Ok(CType::I32),
         TypeKind::UInt => Ok(CType::U32),
-        // C `long` is 64-bit on Linux x86-64 (LP64 ABI)
-        TypeKind::Long => Ok(CType::I64),
-        TypeKind::ULong => Ok(CType::U64),
+        // C `long` is 64-bit on LP64 (Linux, macOS) and 32-bit on LLP64
+        // (Windows) and the 32-bit models
+        TypeKind::Long => Ok(match dm {
+            DataModel::Lp64 => CType::I64,
+            DataModel::Llp64 | DataModel::Ilp32 | DataModel::Win32 => CType::I32,
+        }),
+        TypeKind::ULong => Ok(match dm {
+            DataModel::Lp64 => CType::U64,
+            DataModel::Llp64 | DataModel::Ilp32 | DataModel::Win32 => CType::U32,
+        }),
         TypeKind::LongLong => Ok(CType::I64),
         TypeKind::ULongLong => Ok(CType::U64),
+        TypeKind::Int128 => Ok(CType::I128),
+        TypeKind::UInt128 => Ok(CType::U128),
+        // C++ builtin character types (in C these are typedefs, see below)
+        TypeKind::WChar => Ok(wchar_ctype(dm)),
+        TypeKind::Char16 => Ok(CType::Char16),
+        TypeKind::Char32 => Ok(CType::Char32),
         TypeKind::Float => Ok(CType::F32),
         TypeKind::Double => Ok(CType::F64),
+        TypeKind::LongDouble => {
+            let size = ty.get_sizeof().context("long double has no size")?;
+            Ok(CType::LongDouble { size })
+        }
+        TypeKind::Complex => {
+            let element = ty
+                .get_element_type()
+                .context("complex type has no element type")?;
+            let element = map_clang_type_at(&element, dm, depth)?;
+            let Some((name, _)) = COMPLEX_TYPES.iter().find(|(_, e)| *e == element) else {
+                anyhow::bail!("unsupported complex type `{}`", ty.get_display_name());
+            };
+            Ok(CType::Named {
+                name: name.to_string(),
+                resolved: None,
+            })
+        }
 
         TypeKind::Pointer => {
             let pointee = ty
                 .get_pointee_type()
                 .context("pointer has no pointee type")?;
             let is_const = pointee.is_const_qualified();
-            let inner = map_clang_type(&pointee)?;
+            let inner = match incomplete_record_tag(&pointee) {
+                // Keep the tag so `opaque_handles` can name a forward-declared
+                // struct; unless it does, the reference falls back to `void`.
+                Some(tag) => CType::Named {
+                    name: tag,
+                    resolved: Some(Box::new(CType::Void)),
+                },
+                None => map_clang_type_at(&pointee, dm, depth)?,
+            };
             Ok(CType::Ptr {
                 pointee: Box::new(inner),
                 is_const,
             })
         }
 
+        // Apple blocks (`void (^)(int)`, needs `-fblocks`) are pointers to a
+        // runtime block object with no C-level layout to describe, so keep
+        // the surrounding declaration with a pointer-sized placeholder.
+        TypeKind::BlockPointer => {
+            warn!(
+                ty = %ty.get_display_name(),
+                "block pointer emitted as an opaque pointer"
+            );
+            Ok(CType::Ptr {
+                pointee: Box::new(CType::Void),
+                is_const: false,
+            })
+        }
+
         TypeKind::ConstantArray => {
             let elem = ty.get_element_type().context("array has no element type")?;
             let len = ty.get_size().unwrap_or(0);
-            let inner = map_clang_type(&elem)?;
+            let inner = map_clang_type_at(&elem, dm, depth)?;
+            Ok(CType::Array {
+                element: Box::new(inner),
+                len,
+            })
+        }
+
+        // GCC/clang vector extensions (`vector_size`, `ext_vector_type`)
+        // become a fixed buffer of their element covering the vector's
+        // storage, which may exceed the element count (a 3-element
+        // `ext_vector_type` occupies 4 slots).
+        TypeKind::Vector => {
+            let elem = ty
+                .get_element_type()
+                .context("vector has no element type")?;
+            let elem_size = elem.get_sizeof().ok().filter(|&size| size > 0);
+            let len = match (ty.get_sizeof(), elem_size) {
+                (Ok(size), Some(elem_size)) => size / elem_size,
+                _ => anyhow::bail!("unsupported vector type `{}`", ty.get_display_name()),
+            };
+            let inner = map_clang_type_at(&elem, dm, depth)?;
             Ok(CType::Array {
                 element: Box::new(inner),
                 len,
@@ -639,7 +3046,7 @@ fn map_clang_type(ty: &ClangType) -> Result<CType> {
             let elem = ty
                 .get_element_type()
                 .context("incomplete array has no element type")?;
-            let inner = map_clang_type(&elem)?;
+            let inner = map_clang_type_at(&elem, dm, depth)?;
             Ok(CType::Ptr {
                 pointee: Box::new(inner),
                 is_const: false,
@@ -650,7 +3057,7 @@ fn map_clang_type(ty: &ClangType) -> Result<CType> {
             let inner = ty
                 .get_elaborated_type()
                 .context("elaborated type has no inner type")?;
-            map_clang_type(&inner)
+            map_clang_type_at(&inner, dm, depth)
         }
 
         TypeKind::Typedef => {
@@ -668,17 +3075,35 @@ fn map_clang_type(ty: &ClangType) -> Result<CType> {
                             is_const: false,
                         });
                     }
+                    // In C, wide character types are typedefs of plain
+                    // integers; keep them distinguishable as char types.
+                    if let Some(ctype) = wide_char_typedef(&name, dm) {
+                        return Ok(ctype);
+                    }
                     // Keep the name for cross-partition TypeRef resolution,
-                    // but also resolve the canonical type as fallback for
-                    // system typedefs that won't be in any partition.
-                    let canonical = ty.get_canonical_type();
-                    let resolved = map_clang_type(&canonical).ok().map(Box::new);
-                    return Ok(CType::Named { name, resolved });
+                    // and resolve one link down the chain as fallback for
+                    // typedefs that won't be in any partition. An
+                    // intermediate typedef stays `Named`, so the emitter
+                    // picks the nearest alias that is registered and
+                    // otherwise the terminal type.
+                    let resolved = if let Some(ctype) = size_typedef(&name, ty, dm) {
+                        Some(ctype)
+                    } else if depth < MAX_TYPEDEF_DEPTH {
+                        decl.get_typedef_underlying_type()
+                            .and_then(|next| map_clang_type_at(&next, dm, depth + 1).ok())
+                    } else {
+                        warn!(name = %name, "typedef chain too deep, using canonical type");
+                        map_clang_type(&ty.get_canonical_type(), dm).ok()
+                    };
+                    return Ok(CType::Named {
+                        name,
+                        resolved: resolved.map(Box::new),
+                    });
                 }
             }
             // Unnamed or unresolvable typedef — resolve to canonical primitive
             let canonical = ty.get_canonical_type();
-            map_clang_type(&canonical)
+            map_clang_type_at(&canonical, dm, depth)
         }
 
         TypeKind::Record => {
@@ -710,27 +3135,31 @@ fn map_clang_type(ty: &ClangType) -> Result<CType> {
         }
 
         TypeKind::Enum => {
-            let decl = ty.get_declaration();
-            if let Some(decl) = decl
-                && let Some(name) = decl.get_name()
-            {
-                return Ok(CType::Named {
+            let decl = ty
+                .get_declaration()
+                .context("enum type has no declaration")?;
+            // The integer type stands in for the enum wherever its
+            // definition isn't emitted (filtered out, or in a header no
+            // partition traverses), so references never dangle.
+            let underlying = map_clang_type_at(&enum_base_type(&decl)?, dm, depth)?;
+            match decl.get_name() {
+                Some(name) if !decl.is_anonymous() => Ok(CType::Named {
                     name,
-                    resolved: None,
-                });
+                    resolved: Some(Box::new(underlying)),
+                }),
+                _ => Ok(underlying),
             }
-            anyhow::bail!("anonymous enum type without name")
         }
 
         TypeKind::FunctionPrototype => {
             let ret = ty
                 .get_result_type()
                 .context("function prototype has no return type")?;
-            let ret_ctype = map_clang_type(&ret)?;
+            let ret_ctype = map_clang_type_at(&ret, dm, depth)?;
             let arg_types = ty.get_argument_types().unwrap_or_default();
             let mut params = Vec::new();
             for at in &arg_types {
-                params.push(map_clang_type(at)?);
+                params.push(map_clang_type_at(at, dm, depth)?);
             }
             let cc = ty
                 .get_calling_convention()
@@ -752,12 +3181,155 @@ fn map_clang_type(ty: &ClangType) -> Result<CType> {
             })
         }
 
+        // libclang 11+ reports `_Atomic(T)` as CXType_Atomic, which the
+        // clang crate (capped at the 10.0 API) surfaces as Unexposed and
+        // without `clang_Type_getValueType`, so recognise it by spelling.
+        TypeKind::Unexposed if atomic_value_spelling(&ty.get_display_name()).is_some() => {
+            map_atomic_type(ty, dm)
+        }
+
         other => {
             anyhow::bail!("unsupported clang TypeKind: {:?}", other)
         }
     }
 }
 
+/// The `T` in a type spelled `_Atomic(T)` (after any cv-qualifiers).
+fn atomic_value_spelling(spelling: &str) -> Option<&str> {
+    strip_qualifiers(spelling)
+        .strip_prefix("_Atomic(")?
+        .strip_suffix(')')
+}
+
+/// `spelling` without leading `const` / `volatile` / `restrict` qualifiers.
+fn strip_qualifiers(spelling: &str) -> &str {
+    let mut rest = spelling.trim();
+    while let Some(r) = rest
+        .strip_prefix("const ")
+        .or_else(|| rest.strip_prefix("volatile "))
+        .or_else(|| rest.strip_prefix("restrict "))
+    {
+        rest = r.trim_start();
+    }
+    rest
+}
+
+/// Map `_Atomic(T)` to plain `T` — winmd has no notion of atomicity, only
+/// layout matters.
+///
+/// Builtin and `<stdint.h>` value types map exactly and pointers become
+/// `void *`. Anything else (typedefs, small structs) falls back to the
+/// unsigned integer of the atomic's size, which has the same size and
+/// alignment as a lock-free atomic.
+fn map_atomic_type(ty: &ClangType, dm: DataModel) -> Result<CType> {
+    let spelling = ty.get_display_name();
+    let inner = atomic_value_spelling(&spelling).unwrap_or_default().trim();
+
+    if inner.ends_with('*') {
+        return Ok(CType::Ptr {
+            pointee: Box::new(CType::Void),
+            is_const: false,
+        });
+    }
+    if let Some(ctype) = builtin_by_spelling(inner, dm) {
+        return Ok(ctype);
+    }
+
+    let size = ty
+        .get_sizeof()
+        .map_err(|e| anyhow::anyhow!("cannot size `{spelling}`: {e:?}"))?;
+    let ctype = match size {
+        1 => CType::U8,
+        2 => CType::U16,
+        4 => CType::U32,
+        8 => CType::U64,
+        _ => anyhow::bail!("unsupported atomic type `{spelling}` ({size} bytes)"),
+    };
+    warn!(
+        ty = %spelling,
+        size,
+        "atomic value type not recognised, emitting as an unsigned integer of the same size"
+    );
+    Ok(ctype)
+}
+
+/// Builtin C types and `<stdint.h>` typedefs, by spelling.
+fn builtin_by_spelling(spelling: &str, dm: DataModel) -> Option<CType> {
+    let long = |signed| match (dm, signed) {
+        (DataModel::Lp64, true) => CType::I64,
+        (DataModel::Lp64, false) => CType::U64,
+        (DataModel::Llp64 | DataModel::Ilp32 | DataModel::Win32, true) => CType::I32,
+        (DataModel::Llp64 | DataModel::Ilp32 | DataModel::Win32, false) => CType::U32,
+    };
+    Some(match spelling {
+        "_Bool" | "bool" => CType::Bool,
+        "char" | "signed char" | "int8_t" => CType::I8,
+        "unsigned char" | "uint8_t" => CType::U8,
+        "short" | "int16_t" => CType::I16,
+        "unsigned short" | "uint16_t" => CType::U16,
+        "int" | "int32_t" => CType::I32,
+        "unsigned int" | "uint32_t" => CType::U32,
+        "long" => long(true),
+        "unsigned long" => long(false),
+        "long long" | "int64_t" => CType::I64,
+        "unsigned long long" | "uint64_t" => CType::U64,
+        "__int128" | "__int128_t" => CType::I128,
+        "unsigned __int128" | "__uint128_t" => CType::U128,
+        "intptr_t" | "ptrdiff_t" | "ssize_t" => CType::ISize,
+        "uintptr_t" | "size_t" => CType::USize,
+        "float" => CType::F32,
+        "double" => CType::F64,
+        _ => return None,
+    })
+}
+
+/// Typedefs that are pointer-sized unsigned integers by definition, whatever
+/// integer the platform spells them with (`__SIZE_TYPE__`, `unsigned long`,
+/// `unsigned long long`).
+const SIZE_TYPEDEFS: [&str; 3] = ["size_t", "__size_t", "uintptr_t"];
+
+/// [`CType::USize`] for the `size_t`-like typedef `name` of type `ty`, if
+/// its canonical type is an unsigned integer as wide as a pointer of the
+/// data model `dm`. Matching
+/// on the canonical type alone would also catch `uint64_t` (`unsigned
+/// long` on LP64), so the name decides and the width only guards against
+/// an unexpected definition. Aliases of these typedefs resolve through them.
+fn size_typedef(name: &str, ty: &ClangType, dm: DataModel) -> Option<CType> {
+    if !SIZE_TYPEDEFS.contains(&name) {
+        return None;
+    }
+    let canonical = ty.get_canonical_type();
+    let unsigned = matches!(
+        canonical.get_kind(),
+        TypeKind::UInt | TypeKind::ULong | TypeKind::ULongLong
+    );
+    if unsigned && canonical.get_sizeof().ok() == Some(dm.pointer_size()) {
+        Some(CType::USize)
+    } else {
+        warn!(name, ty = %canonical.get_display_name(), "size typedef is not pointer-sized");
+        None
+    }
+}
+
+/// `wchar_t` is 32-bit on LP64 and ILP32 platforms and 16-bit on Windows
+/// (LLP64 and Win32).
+fn wchar_ctype(dm: DataModel) -> CType {
+    match dm {
+        DataModel::Lp64 | DataModel::Ilp32 => CType::Char32,
+        DataModel::Llp64 | DataModel::Win32 => CType::Char16,
+    }
+}
+
+/// Map the C wide character typedef names to their char type.
+fn wide_char_typedef(name: &str, dm: DataModel) -> Option<CType> {
+    match name {
+        "wchar_t" => Some(wchar_ctype(dm)),
+        "char16_t" => Some(CType::Char16),
+        "char32_t" => Some(CType::Char32),
+        _ => None,
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Calling convention mapping
 // ---------------------------------------------------------------------------
@@ -767,8 +3339,22 @@ fn map_calling_convention(cc: CallingConvention) -> CallConv {
         CallingConvention::Cdecl => CallConv::Cdecl,
         CallingConvention::Stdcall => CallConv::Stdcall,
         CallingConvention::Fastcall => CallConv::Fastcall,
-        // Everything else → Cdecl (platform default)
-        _ => CallConv::Cdecl,
+        CallingConvention::Thiscall => CallConv::Thiscall,
+        CallingConvention::Vectorcall => CallConv::Vectorcall,
+        CallingConvention::RegCall => CallConv::Regcall,
+        CallingConvention::Pascal => CallConv::Pascal,
+        CallingConvention::Win64 => CallConv::Win64,
+        CallingConvention::SysV64 => CallConv::SysV64,
+        CallingConvention::Aapcs => CallConv::Aapcs,
+        CallingConvention::AapcsVfp => CallConv::AapcsVfp,
+        CallingConvention::Swift => CallConv::Swift,
+        CallingConvention::PreserveMost => CallConv::PreserveMost,
+        CallingConvention::PreserveAll => CallConv::PreserveAll,
+        CallingConvention::IntelOcl => CallConv::IntelOcl,
+        CallingConvention::Unexposed => {
+            warn!("unexposed calling convention, treating as cdecl");
+            CallConv::Cdecl
+        }
     }
 }
 
@@ -780,8 +3366,12 @@ fn map_calling_convention(cc: CallingConvention) -> CallConv {
 /// i.e. `typedef struct foo foo;` or `typedef enum bar bar;`.
 /// These are handled by sonar's find_structs/find_enums and should NOT also
 /// appear as typedefs.
+///
+/// Qualifiers are ignored (`typedef volatile struct foo foo;` is a
+/// pass-through too), since winmd cannot express them.
 fn is_struct_passthrough(underlying: &ClangType, typedef_name: &str) -> bool {
     let display = underlying.get_display_name();
+    let display = strip_qualifiers(&display);
     for prefix in &["struct ", "enum ", "union "] {
         if display.starts_with(prefix) && &display[prefix.len()..] == typedef_name {
             return true;
@@ -817,30 +3407,135 @@ fn should_emit_by_location(entity: &Entity, traverse_files: &[PathBuf], _base_di
         .any(|tf| file_path == *tf || file_path.ends_with(tf))
 }
 
+// ---------------------------------------------------------------------------
+// Symbol renaming
+// ---------------------------------------------------------------------------
+
+/// Rename every declaration in `partition` (types, functions, constants)
+/// named in `renames` (C name → new name), and remove the first matching
+/// prefix in `prefixes` from the rest.
+///
+/// Functions keep their original symbol as `import_name` so the P/Invoke
+/// import still binds. Returns the type renames (old → new) so callers can
+/// rewrite `CType::Named` references in every partition. Two symbols that
+/// end up with the same name are a hard error.
+pub fn rename_symbols(
+    partition: &mut Partition,
+    renames: &HashMap<String, String>,
+    prefixes: &[String],
+) -> Result<HashMap<String, String>> {
+    let mut type_renames = HashMap::new();
+    if renames.is_empty() && prefixes.is_empty() {
+        return Ok(type_renames);
+    }
+
+    let strip = |name: &str| -> Option<String> {
+        prefixes.iter().find_map(|prefix| {
+            let rest = name.strip_prefix(prefix.as_str())?;
+            // Never produce an empty name or one that isn't an identifier.
+            let first = rest.chars().next()?;
+            (first.is_ascii_alphabetic() || first == '_').then(|| rest.to_string())
+        })
+    };
+    let rename = |kind: &str, name: &str| -> Option<String> {
+        if let Some(new_name) = renames.get(name) {
+            debug!(kind, from = %name, to = %new_name, "renamed symbol");
+            return Some(new_name.clone());
+        }
+        let new_name = strip(name)?;
+        debug!(kind, from = %name, to = %new_name, "stripped symbol prefix");
+        Some(new_name)
+    };
+
+    // Types share one scope per namespace; functions and constants share the
+    // `Apis` class.
+    let namespace = partition.namespace.clone();
+    let mut type_names = HashMap::new();
+    let mut api_names = HashMap::new();
+
+    let mut rename_type = |name: &mut String| -> Result<()> {
+        let old_name = name.clone();
+        if let Some(new_name) = rename("type", &old_name) {
+            type_renames.insert(old_name.clone(), new_name.clone());
+            *name = new_name;
+        }
+        claim_new_name(&mut type_names, name, &old_name, &namespace)
+    };
+    for s in &mut partition.structs {
+        rename_type(&mut s.name)?;
+    }
+    for e in &mut partition.enums {
+        rename_type(&mut e.name)?;
+    }
+    for td in &mut partition.typedefs {
+        rename_type(&mut td.name)?;
+    }
+
+    for f in &mut partition.functions {
+        if let Some(new_name) = rename("function", &f.name) {
+            let old_name = std::mem::replace(&mut f.name, new_name);
+            f.import_name.get_or_insert(old_name);
+        }
+        let old_name = f.import_name.clone().unwrap_or_else(|| f.name.clone());
+        claim_new_name(&mut api_names, &f.name, &old_name, &namespace)?;
+    }
+    for c in &mut partition.constants {
+        let old_name = c.name.clone();
+        if let Some(new_name) = rename("constant", &c.name) {
+            c.name = new_name;
+        }
+        claim_new_name(&mut api_names, &c.name, &old_name, &namespace)?;
+    }
+    // Globals have their own class, so their own scope.
+    let mut global_names = HashMap::new();
+    for g in &mut partition.variables {
+        if let Some(new_name) = rename("global", &g.name) {
+            let old_name = std::mem::replace(&mut g.name, new_name);
+            g.import_name.get_or_insert(old_name);
+        }
+        let old_name = g.import_name.clone().unwrap_or_else(|| g.name.clone());
+        claim_new_name(&mut global_names, &g.name, &old_name, &namespace)?;
+    }
+
+    Ok(type_renames)
+}
+
+/// Record that `old_name` is emitted as `new_name` within one name scope,
+/// failing if another symbol already claimed `new_name`.
+fn claim_new_name(
+    scope: &mut HashMap<String, String>,
+    new_name: &str,
+    old_name: &str,
+    namespace: &str,
+) -> Result<()> {
+    if let Some(previous) = scope.insert(new_name.to_string(), old_name.to_string()) {
+        anyhow::bail!(
+            "name collision in partition `{namespace}`: `{previous}` and `{old_name}` \
+             both become `{new_name}` (check `renames` and `strip_prefix`)"
+        );
+    }
+    Ok(())
+}
+
 /// Build a type registry from all partitions' extracted data.
 ///
+/// `namespace_overrides` have already been applied by moving symbols
+/// between partitions, so each name is registered under the partition that
+/// actually emits it.
+///
 /// Typedefs use first-writer-wins: the first partition to register a typedef
 /// name owns it. This means a dedicated "types" partition should come first
 /// in the TOML so it claims shared types like `uid_t`, `pid_t`, etc. before
 /// other partitions can. Structs and enums still use last-writer-wins (they
 /// rarely overlap across partitions).
-pub fn build_type_registry(
-    partitions: &[Partition],
-    namespace_overrides: &std::collections::HashMap<String, String>,
-) -> TypeRegistry {
+pub fn build_type_registry(partitions: &[Partition]) -> TypeRegistry {
     let mut registry = TypeRegistry::default();
     for partition in partitions {
         for s in &partition.structs {
-            let ns = namespace_overrides
-                .get(&s.name)
-                .unwrap_or(&partition.namespace);
-            registry.register(&s.name, ns);
+            registry.register(&s.name, &partition.namespace);
         }
         for e in &partition.enums {
-            let ns = namespace_overrides
-                .get(&e.name)
-                .unwrap_or(&partition.namespace);
-            registry.register(&e.name, ns);
+            registry.register(&e.name, &partition.namespace);
         }
         for td in &partition.typedefs {
             // First-writer-wins for typedefs: if already registered by an
@@ -848,10 +3543,7 @@ pub fn build_type_registry(
             if registry.contains(&td.name) {
                 continue;
             }
-            let ns = namespace_overrides
-                .get(&td.name)
-                .unwrap_or(&partition.namespace);
-            registry.register(&td.name, ns);
+            registry.register(&td.name, &partition.namespace);
         }
     }
     registry
@@ -23,53 +23,175 @@
 //! ```
 
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
 use anyhow::{Context, Result};
-use tracing::{info, warn};
+use tracing::{debug, info, warn};
+use windows_metadata::Value;
+use windows_metadata::reader::{HasAttributes, TypeCategory, TypeDef};
 
+pub mod bindgen;
+pub mod cache;
 pub mod config;
 pub mod emit;
 pub mod extract;
+pub mod metadata;
 pub mod model;
+pub mod verify;
+
+pub use bindgen::{
+    BindgenLayout, BindgenOptions, GeneratedBindings, generate_bindings, run_with_bindings,
+};
+pub use cache::CachePolicy;
+pub use model::{Report, Stats};
+pub use verify::verify;
 
 /// Run the full pipeline: load config, parse C headers, emit WinMD, and write
-/// the output file.
+/// the output files.
 ///
 /// `config_path` is the path to a `bnd-winmd.toml` configuration file.  
-/// `output` optionally overrides the output file path from the config.
+/// `output` optionally overrides the output file path from the config; it
+/// is only accepted when the config has a single `[output]`.
 ///
 /// This is the top-level entry point intended for use in `build.rs` scripts
 /// or other programmatic callers that want the complete generate-and-write
 /// workflow in a single call.
 ///
-/// Returns the path the `.winmd` file was written to.
-pub fn run(config_path: &Path, output: Option<&Path>) -> Result<PathBuf> {
+/// Returns the paths the `.winmd` files were written to, one per
+/// `[[output]]` in config order.
+pub fn run(config_path: &Path, output: Option<&Path>) -> Result<Vec<PathBuf>> {
+    run_with_model_dump(config_path, output, None)
+}
+
+/// Like [`run`], but when `dump_path` is set also writes the extracted
+/// model there as [`dump_model`] JSON, from the same clang pass.
+///
+/// The dump is written before type references are validated, so it is
+/// available even when emitting fails. This is the CLI's `--dump-model`.
+pub fn run_with_model_dump(
+    config_path: &Path,
+    output: Option<&Path>,
+    dump_path: Option<&Path>,
+) -> Result<Vec<PathBuf>> {
     let cfg = config::load_config(config_path)
         .with_context(|| format!("loading config from {}", config_path.display()))?;
 
     let base_dir = config_path.parent().unwrap_or_else(|| Path::new("."));
+    warn_on_foreign_target(&cfg);
 
-    let winmd_bytes = generate_from_config(&cfg, base_dir)?;
+    let output_paths = output_paths(&cfg, base_dir, output)?;
+    let mut dumped = Ok(());
+    let emitted = emit_outputs(&cfg, base_dir, &[], &mut |partitions, registry| {
+        if let Some(path) = dump_path {
+            dumped = write_model_dump(path, partitions, registry);
+        }
+    });
+    dumped?;
+    let (winmds, _) = emitted?;
+    for (path, winmd_bytes) in output_paths.iter().zip(&winmds) {
+        write_winmd(path, winmd_bytes)?;
+    }
 
-    let output_path = match output {
-        Some(p) => p.to_path_buf(),
-        None => base_dir.join(&cfg.output.file),
-    };
-    std::fs::write(&output_path, &winmd_bytes)
-        .with_context(|| format!("writing output to {}", output_path.display()))?;
+    Ok(output_paths)
+}
+
+/// Like [`run`], but skips the clang pass when nothing that affects the
+/// output has changed since the last run.
+///
+/// A fingerprint of the tool version, the config file, all `headers` /
+/// `traverse` files and `type_import` winmds is stored next to each output
+/// as `<output>.stamp` (see [`cache`]). With [`CachePolicy::ReuseIfUnchanged`]
+/// matching stamps on every output return the existing paths without
+/// regenerating.
+pub fn run_cached(
+    config_path: &Path,
+    output: Option<&Path>,
+    cache_policy: CachePolicy,
+) -> Result<Vec<PathBuf>> {
+    let cfg = config::load_config(config_path)
+        .with_context(|| format!("loading config from {}", config_path.display()))?;
+
+    let base_dir = config_path.parent().unwrap_or_else(|| Path::new("."));
+    warn_on_foreign_target(&cfg);
+
+    let output_paths = output_paths(&cfg, base_dir, output)?;
+
+    let fingerprint = cache::fingerprint(&cfg, config_path, base_dir);
+    if cache_policy == CachePolicy::ReuseIfUnchanged
+        && output_paths
+            .iter()
+            .all(|path| cache::is_fresh(path, &fingerprint))
+    {
+        info!(
+            outputs = output_paths.len(),
+            "winmd up to date, skipping generation"
+        );
+        return Ok(output_paths);
+    }
+
+    let winmds = generate_all_from_config(&cfg, base_dir)?;
+    for (path, winmd_bytes) in output_paths.iter().zip(&winmds) {
+        write_winmd(path, winmd_bytes)?;
+
+        let stamp_path = cache::stamp_path(path);
+        std::fs::write(&stamp_path, &fingerprint)
+            .with_context(|| format!("writing stamp to {}", stamp_path.display()))?;
+    }
+
+    Ok(output_paths)
+}
+
+/// Warn when the configured `target` is not the host: the winmd's struct
+/// layouts are then only valid on that target.
+fn warn_on_foreign_target(cfg: &config::Config) {
+    if let Some(target) = &cfg.target
+        && !config::target_matches_host(target)
+    {
+        warn!(
+            target = %target,
+            host_arch = std::env::consts::ARCH,
+            host_os = std::env::consts::OS,
+            "generating for a target other than the host; the winmd is only valid there"
+        );
+    }
+}
+
+/// The path each `[[output]]` is written to, in config order.
+fn output_paths(
+    cfg: &config::Config,
+    base_dir: &Path,
+    output: Option<&Path>,
+) -> Result<Vec<PathBuf>> {
+    match output {
+        Some(p) if cfg.output.len() == 1 => Ok(vec![p.to_path_buf()]),
+        Some(p) => anyhow::bail!(
+            "cannot write {} outputs to {}: an output path override needs a \
+             config with a single `[output]`",
+            cfg.output.len(),
+            p.display()
+        ),
+        None => Ok(cfg.output.iter().map(|o| base_dir.join(&o.file)).collect()),
+    }
+}
+
+pub(crate) fn write_winmd(path: &Path, winmd_bytes: &[u8]) -> Result<()> {
+    std::fs::write(path, winmd_bytes)
+        .with_context(|| format!("writing output to {}", path.display()))?;
 
     info!(
-        path = %output_path.display(),
+        path = %path.display(),
         size = winmd_bytes.len(),
         "wrote winmd"
     );
-
-    Ok(output_path)
+    Ok(())
 }
 
 /// Parse a `bnd-winmd.toml` config file, extract declarations from the
 /// referenced C headers, and return the generated WinMD bytes without
 /// writing to disk.
+///
+/// The config must have a single `[output]`; use [`generate_all`] for
+/// configs with several.
 pub fn generate(config_path: &Path) -> Result<Vec<u8>> {
     let cfg = config::load_config(config_path)
         .with_context(|| format!("loading config from {}", config_path.display()))?;
@@ -79,37 +201,521 @@ pub fn generate(config_path: &Path) -> Result<Vec<u8>> {
     generate_from_config(&cfg, base_dir)
 }
 
+/// Like [`generate`], but returns the bytes of every `[[output]]`, in
+/// config order. Headers are parsed once for all outputs.
+pub fn generate_all(config_path: &Path) -> Result<Vec<Vec<u8>>> {
+    let cfg = config::load_config(config_path)
+        .with_context(|| format!("loading config from {}", config_path.display()))?;
+
+    let base_dir = config_path.parent().unwrap_or_else(|| Path::new("."));
+
+    generate_all_from_config(&cfg, base_dir)
+}
+
+/// Like [`generate`], but only emits the partitions whose namespace is one
+/// of `namespaces` or nested under one (`["posix.net"]` keeps `posix.net`
+/// and `posix.net.if`), like windows-bindgen's `--filter`.
+///
+/// Types of the dropped partitions are removed from the type registry.
+/// References to a dropped typedef fall back to its underlying type;
+/// references to a dropped struct or enum are an error naming the
+/// namespace to add.
+pub fn generate_filtered(config_path: &Path, namespaces: &[&str]) -> Result<Vec<u8>> {
+    let cfg = config::load_config(config_path)
+        .with_context(|| format!("loading config from {}", config_path.display()))?;
+
+    let base_dir = config_path.parent().unwrap_or_else(|| Path::new("."));
+
+    let (winmd_bytes, _) = emit_single_output(&cfg, base_dir, namespaces, &mut |_, _| {})?;
+    Ok(winmd_bytes)
+}
+
 /// Generate WinMD bytes from an already-loaded [`config::Config`].
 ///
 /// `base_dir` is the directory relative to which header paths in the config
 /// are resolved (typically the parent directory of the TOML file).
 pub fn generate_from_config(cfg: &config::Config, base_dir: &Path) -> Result<Vec<u8>> {
+    let (winmd_bytes, _) = generate_from_config_with_report(cfg, base_dir)?;
+    Ok(winmd_bytes)
+}
+
+/// Generate WinMD bytes from a single header, without a TOML file.
+///
+/// Builds a one-partition config with [`config::header_config`] (traversing
+/// only `header`) and generates it. The header is resolved relative to the
+/// current directory.
+pub fn generate_from_header(
+    header: &Path,
+    namespace: Option<&str>,
+    library: Option<&str>,
+) -> Result<Vec<u8>> {
+    let cfg = config::header_config(header, namespace, library)?;
+    generate_from_config(&cfg, Path::new("."))
+}
+
+/// [`generate_all`] for an already-loaded [`config::Config`].
+pub fn generate_all_from_config(cfg: &config::Config, base_dir: &Path) -> Result<Vec<Vec<u8>>> {
+    let (winmds, _) = emit_outputs(cfg, base_dir, &[], &mut |_, _| {})?;
+    Ok(winmds)
+}
+
+/// Like [`generate`], but also returns a [`Report`] of the declarations
+/// that were filtered out or could not be extracted.
+///
+/// Dropped declarations are otherwise only visible as `warn!` logs; a build
+/// script can use the report to fail when an expected symbol is missing:
+///
+/// ```no_run
+/// use std::path::Path;
+///
+/// let (winmd, report) = bnd_winmd::generate_with_report(Path::new("bnd-winmd.toml")).unwrap();
+/// assert!(report.is_clean(), "dropped declarations: {report:#?}");
+/// # let _ = winmd;
+/// ```
+pub fn generate_with_report(config_path: &Path) -> Result<(Vec<u8>, Report)> {
+    let cfg = config::load_config(config_path)
+        .with_context(|| format!("loading config from {}", config_path.display()))?;
+
+    let base_dir = config_path.parent().unwrap_or_else(|| Path::new("."));
+
+    generate_from_config_with_report(&cfg, base_dir)
+}
+
+/// [`generate_from_config`] returning the [`Report`] alongside the bytes.
+pub fn generate_from_config_with_report(
+    cfg: &config::Config,
+    base_dir: &Path,
+) -> Result<(Vec<u8>, Report)> {
+    emit_single_output(cfg, base_dir, &[], &mut |_, _| {})
+}
+
+/// [`generate_from_config`] with a `hook` that edits the model before it is
+/// emitted, for changes the config can't express.
+///
+/// The hook runs once, after extraction, cross-partition deduplication and
+/// registry construction, and mutates the partitions and the registry in
+/// place. Type references are validated after it returns, so a hook that
+/// renames or drops a type must update the registry and the references to
+/// it too (see [`model::Partition::rename_type_references`]).
+///
+/// ```no_run
+/// use std::path::Path;
+///
+/// let cfg = bnd_winmd::config::load_config(Path::new("bnd-winmd.toml")).unwrap();
+/// let winmd = bnd_winmd::generate_from_config_with(&cfg, Path::new("."), |partitions, _| {
+///     for partition in partitions.iter_mut() {
+///         partition.functions.retain(|f| !f.name.starts_with("internal_"));
+///     }
+/// })
+/// .unwrap();
+/// # let _ = winmd;
+/// ```
+pub fn generate_from_config_with(
+    cfg: &config::Config,
+    base_dir: &Path,
+    mut hook: impl FnMut(&mut Vec<model::Partition>, &mut model::TypeRegistry),
+) -> Result<Vec<u8>> {
+    let (winmd_bytes, _) = emit_single_output(cfg, base_dir, &[], &mut hook)?;
+    Ok(winmd_bytes)
+}
+
+/// Emit the only `[output]` of `cfg`, keeping the partitions selected by
+/// `namespaces` (all of them when empty).
+fn emit_single_output(
+    cfg: &config::Config,
+    base_dir: &Path,
+    namespaces: &[&str],
+    hook: ModelHook,
+) -> Result<(Vec<u8>, Report)> {
+    if cfg.output.len() != 1 {
+        anyhow::bail!(
+            "config has {} outputs, use `generate_all` to get all of them",
+            cfg.output.len()
+        );
+    }
+    let (mut winmds, report) = emit_outputs(cfg, base_dir, namespaces, hook)?;
+    Ok((winmds.remove(0), report))
+}
+
+/// Callback editing the extracted model, see [`generate_from_config_with`].
+type ModelHook<'a> = &'a mut dyn FnMut(&mut Vec<model::Partition>, &mut model::TypeRegistry);
+
+/// Extract the model once and emit one winmd per `[[output]]`, keeping the
+/// partitions selected by `namespaces` (all of them when empty). `hook`
+/// edits the model between extraction and validation.
+fn emit_outputs(
+    cfg: &config::Config,
+    base_dir: &Path,
+    namespaces: &[&str],
+    hook: ModelHook,
+) -> Result<(Vec<Vec<u8>>, Report)> {
+    let (mut partitions, mut registry) = extract_model(cfg, base_dir)?;
+    let excluded = filter_namespaces(&mut partitions, &mut registry, namespaces);
+    hook(&mut partitions, &mut registry);
+    let report = Report::from_partitions(&partitions);
+
+    // Validate that all referenced types are resolvable before emitting.
+    // This catches missing traverse headers early with actionable diagnostics
+    // instead of a cryptic windows-bindgen "type not found" panic later.
+    validate_type_references(&partitions, &registry, &excluded)?;
+    validate_by_value_cycles(&partitions)?;
+
+    let mut selected: Vec<Vec<model::Partition>> = cfg.output.iter().map(|_| Vec::new()).collect();
+    for partition in partitions {
+        match output_for(&cfg.output, &partition.namespace) {
+            Some(index) => selected[index].push(partition),
+            None => warn!(
+                namespace = %partition.namespace,
+                "partition is not selected by any output, skipping"
+            ),
+        }
+    }
+
+    let mut winmds = Vec::with_capacity(cfg.output.len());
+    for (index, (output, partitions)) in cfg.output.iter().zip(selected).enumerate() {
+        if partitions.is_empty() {
+            warn!(assembly = %output.name, "output selects no partitions");
+        }
+
+        // Types emitted into a sibling output are referenced across
+        // assemblies, exactly like `[[type_import]]`ed ones.
+        let mut output_registry = registry.clone();
+        for (name, namespace) in &registry.types {
+            if registry.imported.contains_key(name) {
+                continue;
+            }
+            if let Some(owner) = output_for(&cfg.output, namespace)
+                && owner != index
+            {
+                output_registry.register_imported(name, namespace, &cfg.output[owner].identity());
+            }
+        }
+
+        let mut winmd_bytes = emit::emit_winmd(
+            &output.name,
+            &partitions,
+            &output_registry,
+            cfg.emit_options(),
+        )?;
+        if let Some(version) = output.version {
+            metadata::set_assembly_version(&mut winmd_bytes, version)?;
+        }
+
+        info!(
+            assembly = %output.name,
+            partitions = partitions.len(),
+            size = winmd_bytes.len(),
+            "generated winmd"
+        );
+        winmds.push(winmd_bytes);
+    }
+
+    Ok((winmds, report))
+}
+
+/// Drop the partitions whose namespace matches none of `namespaces`, and
+/// their local types from the registry. Returns the dropped type names
+/// with their namespace. Does nothing when `namespaces` is empty.
+fn filter_namespaces(
+    partitions: &mut Vec<model::Partition>,
+    registry: &mut model::TypeRegistry,
+    namespaces: &[&str],
+) -> std::collections::HashMap<String, String> {
+    let mut excluded = std::collections::HashMap::new();
+    if namespaces.is_empty() {
+        return excluded;
+    }
+    let selected = |namespace: &str| {
+        namespaces
+            .iter()
+            .any(|prefix| config::namespace_matches(namespace, prefix))
+    };
+
+    partitions.retain(|partition| {
+        let keep = selected(&partition.namespace);
+        if !keep {
+            debug!(namespace = %partition.namespace, "partition excluded by namespace filter");
+        }
+        keep
+    });
+    if partitions.is_empty() {
+        warn!(?namespaces, "namespace filter selects no partitions");
+    }
+
+    let imported = &registry.imported;
+    registry.types.retain(|name, namespace| {
+        if imported.contains_key(name) || selected(namespace) {
+            return true;
+        }
+        excluded.insert(name.clone(), namespace.clone());
+        false
+    });
+    excluded
+}
+
+/// Index of the output a partition namespace is written to: the first one
+/// whose `partitions` select it, else the first without `partitions`.
+fn output_for(outputs: &[config::OutputConfig], namespace: &str) -> Option<usize> {
+    outputs
+        .iter()
+        .position(|o| o.selects(namespace))
+        .or_else(|| outputs.iter().position(|o| o.partitions.is_empty()))
+}
+
+/// Extract the intermediate model from an already-loaded [`config::Config`]
+/// and serialize it as pretty-printed JSON, without emitting a winmd.
+///
+/// The dump is a [`Model`](model::Model): every partition as it would be
+/// handed to the emitter — structs, enums, functions, typedefs and
+/// constants with their resolved [`CType`](model::CType)s, plus the
+/// symbols dropped by `include_symbols` / `exclude_symbols` — and the type
+/// registry. Type references are not validated, so a model that would fail
+/// generation can still be inspected. [`emit_from_model_json`] turns a dump
+/// back into a winmd without libclang.
+pub fn dump_model(cfg: &config::Config, base_dir: &Path) -> Result<String> {
+    let (partitions, registry) = extract_model(cfg, base_dir)?;
+    let model = model::Model {
+        partitions,
+        registry,
+    };
+    serde_json::to_string_pretty(&model).context("serializing model")
+}
+
+/// Write the model being generated to `path` as [`dump_model`] JSON.
+fn write_model_dump(
+    path: &Path,
+    partitions: &mut Vec<model::Partition>,
+    registry: &mut model::TypeRegistry,
+) -> Result<()> {
+    let model = model::Model {
+        partitions: std::mem::take(partitions),
+        registry: std::mem::take(registry),
+    };
+    let json = serde_json::to_string_pretty(&model).context("serializing model");
+    *partitions = model.partitions;
+    *registry = model.registry;
+    std::fs::write(path, json?).with_context(|| format!("writing model dump to {}", path.display()))
+}
+
+/// Extract the model from an already-loaded [`config::Config`] and count
+/// its declarations per partition, without emitting a winmd.
+///
+/// The counts are taken after cross-partition deduplication, so they match
+/// what would be written. Useful for checking config coverage.
+pub fn stats(cfg: &config::Config, base_dir: &Path) -> Result<Stats> {
+    let (partitions, registry) = extract_model(cfg, base_dir)?;
+    Ok(Stats {
+        partitions: partitions
+            .iter()
+            .map(model::PartitionStats::from_partition)
+            .collect(),
+        registry_types: registry.types.len(),
+        target: cfg
+            .emit_options()
+            .target
+            .map(|(triple, data_model)| format!("{triple} ({})", data_model.as_str())),
+    })
+}
+
+/// Emit a winmd named `assembly_name` from a [`dump_model`] JSON dump,
+/// without running clang.
+///
+/// Lets a build cache the model and skip the parse step. All partitions go
+/// into one assembly; per-config settings that are applied at emit time
+/// (`emit_docs`, `emit_source_locations`, `[output] version`) are not part
+/// of the dump and take their defaults.
+pub fn emit_from_model_json(json: &str, assembly_name: &str) -> Result<Vec<u8>> {
+    let model: model::Model = serde_json::from_str(json).context("parsing model JSON")?;
+    validate_type_references(&model.partitions, &model.registry, &Default::default())?;
+    validate_by_value_cycles(&model.partitions)?;
+    emit::emit_winmd(
+        assembly_name,
+        &model.partitions,
+        &model.registry,
+        emit::EmitOptions::default(),
+    )
+}
+
+/// Serializes use of libclang within the process.
+static LIBCLANG: Mutex<()> = Mutex::new(());
+
+/// Precompile the headers of the partition named `namespace` into `output`,
+/// for use as that partition's `pch`.
+///
+/// The PCH is built with the partition's own clang arguments and include
+/// paths, which clang requires to match when the PCH is loaded. Loading it
+/// skips re-parsing the headers; the extracted model is unchanged.
+pub fn build_pch(
+    cfg: &config::Config,
+    base_dir: &Path,
+    namespace: &str,
+    output: &Path,
+) -> Result<()> {
+    let partition = cfg
+        .partition
+        .iter()
+        .find(|p| p.namespace == namespace)
+        .with_context(|| format!("no partition with namespace `{namespace}`"))?;
+
+    let _guard = LIBCLANG.lock().unwrap_or_else(|e| e.into_inner());
+    let clang =
+        clang::Clang::new().map_err(|e| anyhow::anyhow!("failed to initialize libclang: {e}"))?;
+    let index = clang::Index::new(&clang, false, false);
+    extract::build_pch(
+        &index,
+        partition,
+        base_dir,
+        &partition.search_paths(base_dir, &cfg.include_paths),
+        output,
+    )
+}
+
+/// Extract the declarations of a single header into a [`model::Partition`],
+/// without a config file or emitting anything.
+///
+/// The header is parsed as the only partition of
+/// [`config::header_config`] (namespace and library named after its file
+/// stem), with `clang_args` passed to clang. Relative paths resolve against
+/// the current directory. Types are not yet resolved against a registry;
+/// this is the model a custom pipeline, or a test of the type mapping,
+/// starts from.
+pub fn extract_header(path: &Path, clang_args: &[String]) -> Result<model::Partition> {
+    let mut cfg = config::header_config(path, None, None)?;
+    let partition = &mut cfg.partition[0];
+    partition.clang_args.extend_from_slice(clang_args);
+    let base_dir = Path::new(".");
+
+    let _guard = LIBCLANG.lock().unwrap_or_else(|e| e.into_inner());
+    let clang =
+        clang::Clang::new().map_err(|e| anyhow::anyhow!("failed to initialize libclang: {e}"))?;
+    let index = clang::Index::new(&clang, false, false);
+    let mut extracted = extract::extract_partition(
+        &index,
+        partition,
+        base_dir,
+        &partition.search_paths(base_dir, &cfg.include_paths),
+        &cfg.namespace_overrides,
+        partition.data_model(cfg.data_model)?,
+    )?;
+    Ok(extracted.swap_remove(0))
+}
+
+/// Run extraction for every partition and resolve the result into the
+/// partitions and type registry the emitter consumes.
+fn extract_model(
+    cfg: &config::Config,
+    base_dir: &Path,
+) -> Result<(Vec<model::Partition>, model::TypeRegistry)> {
     info!(
-        assembly = %cfg.output.name,
+        outputs = cfg.output.len(),
         partitions = cfg.partition.len(),
         "loaded configuration"
     );
 
-    // Initialize clang
+    // Initialize clang. Only one `Clang` may exist per process, so
+    // concurrent generations (e.g. parallel tests) take turns.
+    let _guard = LIBCLANG.lock().unwrap_or_else(|e| e.into_inner());
     let clang =
         clang::Clang::new().map_err(|e| anyhow::anyhow!("failed to initialize libclang: {e}"))?;
     let index = clang::Index::new(&clang, false, false);
 
     // Extract all partitions
     let mut partitions = Vec::new();
+    let mut overridden = Vec::new();
+    let mut type_renames = std::collections::HashMap::new();
+    let mut extracted_types = std::collections::HashSet::new();
     for partition_cfg in &cfg.partition {
-        let partition = extract::extract_partition(
+        let extracted = extract::extract_partition(
             &index,
             partition_cfg,
             base_dir,
-            &cfg.include_paths,
+            &partition_cfg.search_paths(base_dir, &cfg.include_paths),
             &cfg.namespace_overrides,
+            partition_cfg.data_model(cfg.data_model)?,
         )?;
-        partitions.push(partition);
+        for (i, mut partition) in extracted.into_iter().enumerate() {
+            extracted_types.extend(partition.type_names().map(str::to_string));
+            type_renames.extend(extract::rename_symbols(
+                &mut partition,
+                &cfg.renames,
+                &partition_cfg.strip_prefix,
+            )?);
+            if i == 0 {
+                extract::push_partition(&mut partitions, partition)?;
+            } else {
+                overridden.push(partition);
+            }
+        }
+    }
+
+    // A type renamed onto the name of another extracted type would be
+    // merged with it by the name-keyed registry.
+    let mut renamed: Vec<_> = type_renames.iter().collect();
+    renamed.sort();
+    for (old_name, new_name) in renamed {
+        if extracted_types.contains(new_name) && !type_renames.contains_key(new_name) {
+            anyhow::bail!(
+                "renaming type `{old_name}` to `{new_name}` collides with type `{new_name}`"
+            );
+        }
+    }
+
+    // Symbols moved by `namespace_overrides` join the partition configured
+    // for their target namespace (which may come later in the TOML).
+    extract::merge_overridden(&mut partitions, overridden)?;
+    extract::check_enum_variants(&partitions)?;
+
+    // Renamed types must be followed by references in every partition,
+    // not just the one that declared the type.
+    if !type_renames.is_empty() {
+        for partition in &mut partitions {
+            partition.rename_type_references(&type_renames);
+        }
+    }
+
+    // Opaque handles replace pointers to them in every partition, since
+    // other partitions may pass the handle around too.
+    let handles: std::collections::HashSet<String> = cfg
+        .partition
+        .iter()
+        .flat_map(|p| &p.opaque_handles)
+        .map(|name| type_renames.get(name).unwrap_or(name).clone())
+        .collect();
+    if !handles.is_empty() {
+        let mut declared = std::collections::HashSet::new();
+        for partition in &mut partitions {
+            declared.extend(partition.apply_opaque_handles(&handles));
+        }
+        let mut undeclared: Vec<_> = handles.difference(&declared).collect();
+        undeclared.sort();
+        for name in undeclared {
+            warn!(name = %name, "opaque handle is not an extracted typedef, ignoring");
+        }
     }
 
     // Build global type registry
-    let mut registry = extract::build_type_registry(&partitions, &cfg.namespace_overrides);
+    let mut registry = extract::build_type_registry(&partitions);
+
+    // `canonical_types` pins the owner of a shared type, overriding the
+    // partition order the registry otherwise goes by.
+    let mut canonical: Vec<_> = cfg.canonical_types.iter().collect();
+    canonical.sort();
+    for (name, namespace) in canonical {
+        let name = type_renames.get(name).unwrap_or(name);
+        let defines = partitions
+            .iter()
+            .filter(|p| p.namespace == *namespace)
+            .any(|p| p.type_names().any(|t| t == name));
+        if defines {
+            registry.register(name, namespace);
+        } else {
+            warn!(
+                name = %name,
+                namespace = %namespace,
+                "canonical_types entry: type is not extracted in that namespace, ignoring"
+            );
+        }
+    }
 
     // Pre-seed the registry with types from external winmd files
     // (cross-winmd references). This must happen after build_type_registry
@@ -118,16 +724,38 @@ pub fn generate_from_config(cfg: &config::Config, base_dir: &Path) -> Result<Vec
     // by function signatures but not extracted locally.
     for ti in &cfg.type_import {
         let winmd_path = config::resolve_header(&ti.winmd, base_dir, &cfg.include_paths);
-        seed_registry_from_winmd(&mut registry, &winmd_path, &ti.namespace);
+        let imported = seed_registry_from_winmd(&mut registry, &winmd_path, ti)?;
+        if imported == 0 {
+            warn!(
+                path = %winmd_path.display(),
+                namespace = ti.namespace,
+                "type_import matched no types"
+            );
+        }
+    }
+
+    // `string_pointers` partitions refer to win32metadata's C string types,
+    // unless an import or a local typedef already provides them.
+    if cfg.partition.iter().any(|p| p.string_pointers) {
+        let win32 = metadata::AssemblyIdentity {
+            name: "Windows.Win32".to_string(),
+            version: metadata::AssemblyVersion::WRITER_DEFAULT,
+        };
+        for name in ["PSTR", "PCSTR"] {
+            if !registry.contains(name) {
+                registry.register_imported(name, "Windows.Win32.Foundation", &win32);
+            }
+        }
     }
 
-    // Deduplicate typedefs and structs: when the same type appears in
+    // Deduplicate typedefs, structs and enums: when the same type appears in
     // multiple partitions (e.g. `uid_t` or `__sigset_t` in signal, pthread,
-    // stat, etc.), keep it only in the partition the registry maps it to.
-    // The registry uses first-writer-wins, so the partition listed first in
-    // the TOML claims shared names. Other partitions drop their local copy;
-    // any function/struct that references the type will use a cross-partition
-    // TypeRef instead.
+    // stat, etc.), keep it only in the partition the registry maps it to. A
+    // shared typedef is claimed by the partition listed first in the TOML,
+    // and a shared struct or enum by the last partition that defines it;
+    // `canonical_types` overrides both rules for the names it lists. Other
+    // partitions drop their local copy, and any function or struct that
+    // references the type uses a cross-partition TypeRef instead.
     for partition in &mut partitions {
         partition.typedefs.retain(|td| {
             let canonical_ns = registry.namespace_for(&td.name, &partition.namespace);
@@ -155,38 +783,68 @@ pub fn generate_from_config(cfg: &config::Config, base_dir: &Path) -> Result<Vec
             }
             !dominated
         });
+        partition.enums.retain(|en| {
+            let canonical_ns = registry.namespace_for(&en.name, &partition.namespace);
+            let dominated = canonical_ns != partition.namespace;
+            if dominated {
+                warn!(
+                    name = en.name,
+                    canonical = canonical_ns,
+                    duplicate = partition.namespace,
+                    "dropping duplicate enum (canonical partition wins)"
+                );
+            }
+            !dominated
+        });
     }
 
-    // Validate that all referenced types are resolvable before emitting.
-    // This catches missing traverse headers early with actionable diagnostics
-    // instead of a cryptic windows-bindgen "type not found" panic later.
-    validate_type_references(&partitions, &registry)?;
-
-    // Emit winmd
-    let winmd_bytes = emit::emit_winmd(&cfg.output.name, &partitions, &registry)?;
-
-    info!(size = winmd_bytes.len(), "generated winmd");
+    extract::apply_resource_pairs(&mut partitions, &cfg.resource_pairs, &type_renames);
 
-    Ok(winmd_bytes)
+    Ok((partitions, registry))
 }
 
 /// Pre-seed the [`TypeRegistry`](model::TypeRegistry) with types from an
-/// external `.winmd` file.  Only types whose namespace starts with
-/// `ns_filter` are imported.
+/// external `.winmd` file.  Only types whose namespace matches the import's
+/// `namespace` filter are imported, tagged with the winmd's assembly
+/// identity. If the import pins an `assembly` or `version`, that identity
+/// must match it. Returns the number of newly registered type names.
 fn seed_registry_from_winmd(
     registry: &mut model::TypeRegistry,
     winmd_path: &Path,
-    ns_filter: &str,
-) {
-    let bytes = std::fs::read(winmd_path).unwrap_or_else(|e| {
-        panic!(
-            "failed to read external winmd {}: {e}\n\
+    import: &config::TypeImportConfig,
+) -> Result<usize> {
+    let bytes = std::fs::read(winmd_path).with_context(|| {
+        format!(
+            "failed to read external winmd {}\n\
              Hint: run the upstream gen crate first (e.g. `cargo run -p bnd-posix-gen`)",
             winmd_path.display()
         )
-    });
+    })?;
+
+    let identity = metadata::read_assembly(&bytes)
+        .with_context(|| format!("reading assembly of {}", winmd_path.display()))?;
+    if let Some(assembly) = &import.assembly
+        && *assembly != identity.name
+    {
+        anyhow::bail!(
+            "type_import {}: expected assembly `{assembly}`, found `{}`",
+            winmd_path.display(),
+            identity.name
+        );
+    }
+    if let Some(version) = import.version
+        && version != identity.version
+    {
+        anyhow::bail!(
+            "type_import {}: requested version {version}, but assembly `{}` is version {}",
+            winmd_path.display(),
+            identity.name,
+            identity.version
+        );
+    }
+
     let file = windows_metadata::reader::File::new(bytes)
-        .unwrap_or_else(|| panic!("failed to parse external winmd: {}", winmd_path.display()));
+        .with_context(|| format!("failed to parse external winmd: {}", winmd_path.display()))?;
     let index = windows_metadata::reader::TypeIndex::new(vec![file]);
     let mut count = 0usize;
     for td in index.types() {
@@ -196,28 +854,61 @@ fn seed_registry_from_winmd(
         if ns.is_empty() || name == "<Module>" || name == "Apis" {
             continue;
         }
-        if !ns.starts_with(ns_filter) {
+        if !import.matches_namespace(ns) {
             continue;
         }
         // Only insert if not already registered (local types win).
         // When two external namespaces define the same type name (e.g.
         // __sigset_t in posix.signal and posix.pthread), keep the
         // lexicographically smallest namespace for determinism.
+        if registry.contains(name) && registry.namespace_for(name, "").as_str() < ns {
+            // Already have a smaller namespace — keep it.
+            continue;
+        }
         if !registry.contains(name) {
-            registry.register(name, ns);
             count += 1;
-        } else if registry.namespace_for(name, "").as_str() < ns {
-            // Already have a smaller namespace — keep it.
+        }
+        if td.category() == TypeCategory::Interface {
+            let iid = guid_attribute(&td);
+            debug!(name, namespace = ns, iid = ?iid, "imported interface");
+            registry.register_imported_interface(name, ns, &identity, iid);
         } else {
-            registry.register(name, ns);
+            registry.register_imported(name, ns, &identity);
         }
     }
     info!(
         path = %winmd_path.display(),
-        namespace = ns_filter,
+        namespace = import.namespace,
         imported = count,
         "pre-seeded type registry from external winmd"
     );
+    Ok(count)
+}
+
+/// The `GuidAttribute` of `td` as a lowercase hyphenated GUID string.
+fn guid_attribute(td: &TypeDef) -> Option<String> {
+    let args = td.find_attribute("GuidAttribute")?.value();
+    let values: Vec<&Value> = args.iter().map(|(_, v)| v).collect();
+    let [
+        Value::U32(a),
+        Value::U16(b),
+        Value::U16(c),
+        Value::U8(d0),
+        Value::U8(d1),
+        Value::U8(d2),
+        Value::U8(d3),
+        Value::U8(d4),
+        Value::U8(d5),
+        Value::U8(d6),
+        Value::U8(d7),
+    ] = values.as_slice()
+    else {
+        warn!(name = td.name(), "malformed GuidAttribute, ignoring");
+        return None;
+    };
+    Some(format!(
+        "{a:08x}-{b:04x}-{c:04x}-{d0:02x}{d1:02x}-{d2:02x}{d3:02x}{d4:02x}{d5:02x}{d6:02x}{d7:02x}"
+    ))
 }
 
 // ---------------------------------------------------------------------------
@@ -234,12 +925,15 @@ struct UnresolvedRef {
 /// Walk all CType trees in every partition and verify that each
 /// `Named { resolved: None }` type is present in the registry.
 ///
-/// Types with `resolved: Some(_)` are fine — they fall back to the canonical
-/// primitive at emit time. Only `resolved: None` (records, enums, anonymous
-/// nested types) must be registered.
+/// Unregistered types with `resolved: Some(_)` fall back along their typedef
+/// chain at emit time, so the chain is checked instead. Only `resolved: None`
+/// (records, anonymous nested types) must be registered. `excluded` maps
+/// types dropped by a namespace filter to their namespace, so the error
+/// can point at the filter instead of `traverse`.
 fn validate_type_references(
     partitions: &[model::Partition],
     registry: &model::TypeRegistry,
+    excluded: &std::collections::HashMap<String, String>,
 ) -> Result<()> {
     let mut unresolved: Vec<UnresolvedRef> = Vec::new();
 
@@ -286,6 +980,16 @@ fn validate_type_references(
                 &mut unresolved,
             );
         }
+
+        for g in &partition.variables {
+            collect_unresolved(
+                &g.ty,
+                registry,
+                ns,
+                &format!("global `{}`", g.name),
+                &mut unresolved,
+            );
+        }
     }
 
     if unresolved.is_empty() {
@@ -314,13 +1018,19 @@ fn validate_type_references(
             "\n  • `{}` — referenced in {} (partition `{}`)",
             r.type_name, r.context, r.partition,
         ));
+        if let Some(namespace) = excluded.get(&r.type_name) {
+            msg.push_str(&format!(
+                ", defined in `{namespace}`, which the namespace filter excludes \
+                 — add it to the filter"
+            ));
+        }
     }
 
     anyhow::bail!("{msg}");
 }
 
 /// Recursively walk a CType and collect any `Named { resolved: None }` that
-/// is not in the registry.
+/// is not in the registry, following unregistered typedef chains.
 fn collect_unresolved(
     ctype: &model::CType,
     registry: &model::TypeRegistry,
@@ -330,12 +1040,20 @@ fn collect_unresolved(
 ) {
     match ctype {
         model::CType::Named { name, resolved } => {
-            if resolved.is_none() && !registry.contains(name) {
-                out.push(UnresolvedRef {
+            if registry.contains(name) {
+                return;
+            }
+            match resolved {
+                // Unregistered typedef — the emitter falls back to the next
+                // link of the chain, which must resolve in turn.
+                Some(resolved) => {
+                    collect_unresolved(resolved, registry, partition_ns, context, out)
+                }
+                None => out.push(UnresolvedRef {
                     type_name: name.clone(),
                     partition: partition_ns.to_string(),
                     context: context.to_string(),
-                });
+                }),
             }
         }
         model::CType::Ptr { pointee, .. } => {
@@ -358,3 +1076,71 @@ fn collect_unresolved(
         _ => {}
     }
 }
+
+/// Reject structs that contain themselves by value, directly or through
+/// other structs, typedefs and arrays.
+///
+/// C forbids this, so clang never produces one, but a hand-edited model
+/// JSON can. Such a struct has no finite size. Pointers break the chain, so
+/// `struct node { struct node *next; }` is fine.
+fn validate_by_value_cycles(partitions: &[model::Partition]) -> Result<()> {
+    let mut contains: std::collections::BTreeMap<&str, Vec<&str>> = Default::default();
+    for partition in partitions {
+        for s in &partition.structs {
+            let names = contains.entry(&s.name).or_default();
+            for field in &s.fields {
+                by_value_names(&field.ty, names);
+            }
+        }
+        // `typedef struct node node;` names the struct, not itself.
+        for td in &partition.typedefs {
+            let mut names = Vec::new();
+            by_value_names(&td.underlying_type, &mut names);
+            names.retain(|n| *n != td.name);
+            contains.entry(&td.name).or_default().extend(names);
+        }
+    }
+
+    // Depth-first search; `path` holds the types being expanded.
+    fn visit<'a>(
+        name: &'a str,
+        contains: &std::collections::BTreeMap<&'a str, Vec<&'a str>>,
+        path: &mut Vec<&'a str>,
+        done: &mut std::collections::HashSet<&'a str>,
+    ) -> Result<()> {
+        if let Some(start) = path.iter().position(|n| *n == name) {
+            let cycle = path[start..].join(" -> ");
+            anyhow::bail!("type `{name}` contains itself by value ({cycle} -> {name})");
+        }
+        if !done.insert(name) {
+            return Ok(());
+        }
+        path.push(name);
+        for inner in contains.get(name).into_iter().flatten() {
+            visit(inner, contains, path, done)?;
+        }
+        path.pop();
+        Ok(())
+    }
+
+    let mut done = std::collections::HashSet::new();
+    for name in contains.keys() {
+        visit(name, &contains, &mut Vec::new(), &mut done)?;
+    }
+    Ok(())
+}
+
+/// Names of the types `ctype` holds by value: named types, their typedef
+/// fallbacks and array elements, but nothing behind a pointer.
+fn by_value_names<'a>(ctype: &'a model::CType, out: &mut Vec<&'a str>) {
+    match ctype {
+        model::CType::Named { name, resolved } => {
+            out.push(name);
+            if let Some(resolved) = resolved {
+                by_value_names(resolved, out);
+            }
+        }
+        model::CType::Array { element, .. } => by_value_names(element, out),
+        _ => {}
+    }
+}
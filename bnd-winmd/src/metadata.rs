@@ -0,0 +1,576 @@
+//! Raw access to a winmd's assembly identity and ImplMap rows.
+//!
+//! The `windows-metadata` reader skips the `Assembly` and `AssemblyRef`
+//! tables, and the writer always stamps version `255.255.255.255` and names
+//! each `AssemblyRef` after a root namespace. The identities used for
+//! `[[type_import]]` version pinning, `[output] version` and cross-winmd
+//! `TypeRef` scopes are therefore read and patched here by walking the
+//! ECMA-335 (§II.24) table stream directly. The same goes for the ImplMap
+//! charset bits, which the writer's `PInvokeAttributes` can't express.
+
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+/// A four-part assembly version, e.g. `1.2.0.0`.
+///
+/// Parses from one to four dot-separated parts; missing parts are zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(try_from = "String")]
+pub struct AssemblyVersion(pub [u16; 4]);
+
+impl AssemblyVersion {
+    /// The version the writer stamps on every assembly it emits.
+    pub const WRITER_DEFAULT: Self = Self([255; 4]);
+}
+
+impl FromStr for AssemblyVersion {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let parts: Vec<&str> = s.split('.').collect();
+        if parts.len() > 4 {
+            bail!("invalid assembly version `{s}`: more than four parts");
+        }
+        let mut version = [0u16; 4];
+        for (slot, part) in version.iter_mut().zip(&parts) {
+            *slot = part
+                .parse()
+                .with_context(|| format!("invalid assembly version `{s}`"))?;
+        }
+        Ok(Self(version))
+    }
+}
+
+impl TryFrom<String> for AssemblyVersion {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl Serialize for AssemblyVersion {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl fmt::Display for AssemblyVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [major, minor, build, revision] = self.0;
+        write!(f, "{major}.{minor}.{build}.{revision}")
+    }
+}
+
+/// Name and version from a winmd's `Assembly` row.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct AssemblyIdentity {
+    pub name: String,
+    pub version: AssemblyVersion,
+}
+
+/// Resolution scope of a `TypeRef` row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolutionScope {
+    Module,
+    ModuleRef,
+    AssemblyRef(AssemblyIdentity),
+    TypeRef,
+}
+
+/// A `TypeRef` row: the referenced type and where it is resolved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeRefInfo {
+    pub namespace: String,
+    pub name: String,
+    pub scope: ResolutionScope,
+}
+
+/// Read the identity of the assembly defined by the winmd in `bytes`.
+pub fn read_assembly(bytes: &[u8]) -> Result<AssemblyIdentity> {
+    let tables = Tables::parse(bytes)?;
+    if tables.rows[ASSEMBLY] == 0 {
+        bail!("winmd has no Assembly row");
+    }
+    tables.identity(bytes, ASSEMBLY, 0)
+}
+
+/// Overwrite the version in the winmd's `Assembly` row.
+pub fn set_assembly_version(bytes: &mut [u8], version: AssemblyVersion) -> Result<()> {
+    let tables = Tables::parse(bytes)?;
+    if tables.rows[ASSEMBLY] == 0 {
+        bail!("winmd has no Assembly row");
+    }
+    let row = tables.row(ASSEMBLY, 0);
+    write_version(bytes, row + tables.column(ASSEMBLY, 1), version);
+    Ok(())
+}
+
+/// Every `AssemblyRef` row, in table order.
+pub fn assembly_refs(bytes: &[u8]) -> Result<Vec<AssemblyIdentity>> {
+    let tables = Tables::parse(bytes)?;
+    (0..tables.rows[ASSEMBLY_REF] as usize)
+        .map(|i| tables.identity(bytes, ASSEMBLY_REF, i))
+        .collect()
+}
+
+/// Every `TypeRef` row, in table order.
+pub fn type_refs(bytes: &[u8]) -> Result<Vec<TypeRefInfo>> {
+    let tables = Tables::parse(bytes)?;
+    (0..tables.rows[TYPE_REF] as usize)
+        .map(|i| {
+            let row = tables.row(TYPE_REF, i);
+            let scope_width = tables.width(Col::Coded(RESOLUTION_SCOPE));
+            let scope = read_index(bytes, row, scope_width)?;
+            let target = (scope >> 2).checked_sub(1);
+            let scope = match (scope & 0b11, target) {
+                (0, _) => ResolutionScope::Module,
+                (1, _) => ResolutionScope::ModuleRef,
+                (2, Some(target)) => {
+                    ResolutionScope::AssemblyRef(tables.identity(bytes, ASSEMBLY_REF, target)?)
+                }
+                (3, _) => ResolutionScope::TypeRef,
+                _ => bail!("TypeRef row {i} has a null AssemblyRef scope"),
+            };
+            Ok(TypeRefInfo {
+                name: tables.string(bytes, row + tables.column(TYPE_REF, 1))?,
+                namespace: tables.string(bytes, row + tables.column(TYPE_REF, 2))?,
+                scope,
+            })
+        })
+        .collect()
+}
+
+/// Point the `AssemblyRef` named `current` at the assembly `target`,
+/// rewriting its version and, when they differ, its name.
+///
+/// Returns false if no `AssemblyRef` is named `current`. The name can only
+/// be rewritten to a string already in the `#Strings` heap (the heap can't
+/// grow without relaying out the file); otherwise the old name is kept and
+/// a warning logged.
+pub fn retarget_assembly_ref(
+    bytes: &mut [u8],
+    current: &str,
+    target: &AssemblyIdentity,
+) -> Result<bool> {
+    let tables = Tables::parse(bytes)?;
+    let name_column = tables.column(ASSEMBLY_REF, 6);
+    let Some(row) = (0..tables.rows[ASSEMBLY_REF] as usize)
+        .map(|i| tables.row(ASSEMBLY_REF, i))
+        .find(|&row| tables.string(bytes, row + name_column).ok().as_deref() == Some(current))
+    else {
+        return Ok(false);
+    };
+
+    write_version(bytes, row + tables.column(ASSEMBLY_REF, 0), target.version);
+    if target.name != current {
+        match tables.find_string(bytes, &target.name) {
+            Some(index) => write_index(bytes, row + name_column, tables.string_index, index),
+            None => warn!(
+                assembly_ref = current,
+                assembly = target.name,
+                "assembly name not in the string heap, keeping the namespace-derived name"
+            ),
+        }
+    }
+    Ok(true)
+}
+
+/// `CharSetAnsi` in an ImplMap's `MappingFlags` (§II.23.1.8).
+pub const CHARSET_ANSI: u16 = 0x2;
+/// `CharSetUnicode` in an ImplMap's `MappingFlags`.
+pub const CHARSET_UNICODE: u16 = 0x4;
+
+/// Import name and `MappingFlags` of every `ImplMap` row, in table order.
+pub fn impl_map_flags(bytes: &[u8]) -> Result<Vec<(String, u16)>> {
+    let tables = Tables::parse(bytes)?;
+    (0..tables.rows[IMPL_MAP] as usize)
+        .map(|i| {
+            let row = tables.row(IMPL_MAP, i);
+            Ok((
+                tables.string(bytes, row + tables.column(IMPL_MAP, 2))?,
+                read_u16(bytes, row)?,
+            ))
+        })
+        .collect()
+}
+
+/// Set `flags[i]` in the `MappingFlags` of the `i`th `ImplMap` row, on top
+/// of the flags already there. `flags` must cover every row.
+pub fn add_impl_map_flags(bytes: &mut [u8], flags: &[u16]) -> Result<()> {
+    let tables = Tables::parse(bytes)?;
+    if tables.rows[IMPL_MAP] as usize != flags.len() {
+        bail!(
+            "winmd has {} ImplMap rows, expected {}",
+            tables.rows[IMPL_MAP],
+            flags.len()
+        );
+    }
+    for (i, &extra) in flags.iter().enumerate() {
+        let row = tables.row(IMPL_MAP, i);
+        let current = read_u16(bytes, row)?;
+        bytes[row..row + 2].copy_from_slice(&(current | extra).to_le_bytes());
+    }
+    Ok(())
+}
+
+// Table ids referenced by the row layouts below.
+const MODULE: usize = 0x00;
+const TYPE_REF: usize = 0x01;
+const TYPE_DEF: usize = 0x02;
+const FIELD: usize = 0x04;
+const METHOD_DEF: usize = 0x06;
+const PARAM: usize = 0x08;
+const INTERFACE_IMPL: usize = 0x09;
+const MEMBER_REF: usize = 0x0A;
+const DECL_SECURITY: usize = 0x0E;
+const STAND_ALONE_SIG: usize = 0x11;
+const EVENT: usize = 0x14;
+const PROPERTY: usize = 0x17;
+const MODULE_REF: usize = 0x1A;
+const TYPE_SPEC: usize = 0x1B;
+const IMPL_MAP: usize = 0x1C;
+const ASSEMBLY: usize = 0x20;
+const ASSEMBLY_REF: usize = 0x23;
+const FILE: usize = 0x26;
+const EXPORTED_TYPE: usize = 0x27;
+const MANIFEST_RESOURCE: usize = 0x28;
+const GENERIC_PARAM: usize = 0x2A;
+const METHOD_SPEC: usize = 0x2B;
+const GENERIC_PARAM_CONSTRAINT: usize = 0x2C;
+
+/// Column of a metadata table row (§II.22).
+#[derive(Clone, Copy)]
+enum Col {
+    U8,
+    U16,
+    U32,
+    Str,
+    Guid,
+    Blob,
+    /// Simple index into another table.
+    Table(usize),
+    /// Coded index over several tables.
+    Coded(&'static [usize]),
+}
+
+const TYPE_DEF_OR_REF: &[usize] = &[TYPE_DEF, TYPE_REF, TYPE_SPEC];
+const HAS_CONSTANT: &[usize] = &[FIELD, PARAM, PROPERTY];
+const HAS_CUSTOM_ATTRIBUTE: &[usize] = &[
+    METHOD_DEF,
+    FIELD,
+    TYPE_REF,
+    TYPE_DEF,
+    PARAM,
+    INTERFACE_IMPL,
+    MEMBER_REF,
+    MODULE,
+    DECL_SECURITY,
+    PROPERTY,
+    EVENT,
+    STAND_ALONE_SIG,
+    MODULE_REF,
+    TYPE_SPEC,
+    ASSEMBLY,
+    ASSEMBLY_REF,
+    FILE,
+    EXPORTED_TYPE,
+    MANIFEST_RESOURCE,
+    GENERIC_PARAM,
+    GENERIC_PARAM_CONSTRAINT,
+    METHOD_SPEC,
+];
+const HAS_FIELD_MARSHAL: &[usize] = &[FIELD, PARAM];
+const HAS_DECL_SECURITY: &[usize] = &[TYPE_DEF, METHOD_DEF, ASSEMBLY];
+const MEMBER_REF_PARENT: &[usize] = &[TYPE_DEF, TYPE_REF, MODULE_REF, METHOD_DEF, TYPE_SPEC];
+const HAS_SEMANTICS: &[usize] = &[EVENT, PROPERTY];
+const METHOD_DEF_OR_REF: &[usize] = &[METHOD_DEF, MEMBER_REF];
+const MEMBER_FORWARDED: &[usize] = &[FIELD, METHOD_DEF];
+// Tags 0, 1 and 4 are unused; only their count matters for the width.
+const CUSTOM_ATTRIBUTE_TYPE: &[usize] =
+    &[METHOD_DEF, METHOD_DEF, METHOD_DEF, MEMBER_REF, MEMBER_REF];
+const RESOLUTION_SCOPE: &[usize] = &[MODULE, MODULE_REF, ASSEMBLY_REF, TYPE_REF];
+
+/// Row layouts of the tables up to `AssemblyRef` (ids `0x00..=0x23`).
+fn columns(table: usize) -> &'static [Col] {
+    use Col::*;
+    match table {
+        0x00 => &[U16, Str, Guid, Guid, Guid],
+        0x01 => &[Coded(RESOLUTION_SCOPE), Str, Str],
+        0x02 => &[
+            U32,
+            Str,
+            Str,
+            Coded(TYPE_DEF_OR_REF),
+            Table(FIELD),
+            Table(METHOD_DEF),
+        ],
+        0x03 => &[Table(FIELD)],
+        0x04 => &[U16, Str, Blob],
+        0x05 => &[Table(METHOD_DEF)],
+        0x06 => &[U32, U16, U16, Str, Blob, Table(PARAM)],
+        0x07 => &[Table(PARAM)],
+        0x08 => &[U16, U16, Str],
+        0x09 => &[Table(TYPE_DEF), Coded(TYPE_DEF_OR_REF)],
+        0x0A => &[Coded(MEMBER_REF_PARENT), Str, Blob],
+        0x0B => &[U8, U8, Coded(HAS_CONSTANT), Blob],
+        0x0C => &[
+            Coded(HAS_CUSTOM_ATTRIBUTE),
+            Coded(CUSTOM_ATTRIBUTE_TYPE),
+            Blob,
+        ],
+        0x0D => &[Coded(HAS_FIELD_MARSHAL), Blob],
+        0x0E => &[U16, Coded(HAS_DECL_SECURITY), Blob],
+        0x0F => &[U16, U32, Table(TYPE_DEF)],
+        0x10 => &[U32, Table(FIELD)],
+        0x11 => &[Blob],
+        0x12 => &[Table(TYPE_DEF), Table(EVENT)],
+        0x13 => &[Table(EVENT)],
+        0x14 => &[U16, Str, Coded(TYPE_DEF_OR_REF)],
+        0x15 => &[Table(TYPE_DEF), Table(PROPERTY)],
+        0x16 => &[Table(PROPERTY)],
+        0x17 => &[U16, Str, Blob],
+        0x18 => &[U16, Table(METHOD_DEF), Coded(HAS_SEMANTICS)],
+        0x19 => &[
+            Table(TYPE_DEF),
+            Coded(METHOD_DEF_OR_REF),
+            Coded(METHOD_DEF_OR_REF),
+        ],
+        0x1A => &[Str],
+        0x1B => &[Blob],
+        0x1C => &[U16, Coded(MEMBER_FORWARDED), Str, Table(MODULE_REF)],
+        0x1D => &[U32, Table(FIELD)],
+        0x1E => &[U32, U32],
+        0x1F => &[U32],
+        0x20 => &[U32, U16, U16, U16, U16, U32, Blob, Str, Str],
+        0x21 => &[U32],
+        0x22 => &[U32, U32, U32],
+        0x23 => &[U16, U16, U16, U16, U32, Blob, Str, Str, Blob],
+        _ => unreachable!("no layout for table {table:#x}"),
+    }
+}
+
+/// Number of tables with a layout in [`columns`].
+const TABLE_COUNT: usize = ASSEMBLY_REF + 1;
+
+/// Decoded `#~` stream header: where each table's rows live and how wide
+/// its columns are.
+struct Tables {
+    rows: [u32; 64],
+    /// File offset of each table's first row.
+    start: [usize; TABLE_COUNT],
+    string_index: usize,
+    guid_index: usize,
+    blob_index: usize,
+    /// File offset and size of the `#Strings` heap.
+    strings: usize,
+    strings_size: usize,
+}
+
+impl Tables {
+    /// Walk PE headers → CLI header → metadata root → `#~` table stream.
+    fn parse(bytes: &[u8]) -> Result<Self> {
+        let pe = read_u32(bytes, 0x3C)? as usize;
+        if bytes.get(pe..pe + 4) != Some(b"PE\0\0") {
+            bail!("not a PE file");
+        }
+        let section_count = read_u16(bytes, pe + 6)? as usize;
+        let optional_size = read_u16(bytes, pe + 20)? as usize;
+        let optional = pe + 24;
+        let data_directories = match read_u16(bytes, optional)? {
+            0x10B => optional + 96,
+            0x20B => optional + 112,
+            magic => bail!("unknown PE optional header magic {magic:#x}"),
+        };
+        let sections = optional + optional_size;
+        let rva_to_offset = |rva: u32| -> Result<usize> {
+            for i in 0..section_count {
+                let header = sections + 40 * i;
+                let size = read_u32(bytes, header + 8)?;
+                let address = read_u32(bytes, header + 12)?;
+                let raw = read_u32(bytes, header + 20)?;
+                if (address..address.saturating_add(size)).contains(&rva) {
+                    return Ok((rva - address + raw) as usize);
+                }
+            }
+            bail!("RVA {rva:#x} is outside every section")
+        };
+
+        // Data directory 14 is the CLI header, which points at the metadata.
+        let cli = rva_to_offset(read_u32(bytes, data_directories + 14 * 8)?)?;
+        let root = rva_to_offset(read_u32(bytes, cli + 8)?)?;
+        if read_u32(bytes, root)? != 0x424A_5342 {
+            bail!("missing metadata signature");
+        }
+        let version_len = read_u32(bytes, root + 12)? as usize;
+        let stream_count = read_u16(bytes, root + 16 + version_len + 2)? as usize;
+
+        let mut tables = None;
+        let mut strings = None;
+        let mut header = root + 16 + version_len + 4;
+        for _ in 0..stream_count {
+            let offset = root + read_u32(bytes, header)? as usize;
+            let size = read_u32(bytes, header + 4)? as usize;
+            let name_start = header + 8;
+            let name_len = bytes[name_start.min(bytes.len())..]
+                .iter()
+                .position(|&b| b == 0)
+                .context("unterminated stream name")?;
+            match &bytes[name_start..name_start + name_len] {
+                b"#~" => tables = Some(offset),
+                b"#Strings" => strings = Some((offset, size)),
+                _ => {}
+            }
+            // Names are null-terminated and padded to a multiple of four.
+            header = name_start + (name_len + 4) / 4 * 4;
+        }
+        let tables = tables.context("missing #~ stream")?;
+        let (strings, strings_size) = strings.context("missing #Strings stream")?;
+
+        let heap_sizes = *bytes.get(tables + 6).context("truncated #~ stream")?;
+        let string_index = if heap_sizes & 0x01 != 0 { 4 } else { 2 };
+        let guid_index = if heap_sizes & 0x02 != 0 { 4 } else { 2 };
+        let blob_index = if heap_sizes & 0x04 != 0 { 4 } else { 2 };
+
+        let valid = read_u64(bytes, tables + 8)?;
+        let mut rows = [0u32; 64];
+        let mut cursor = tables + 24;
+        for (table, count) in rows.iter_mut().enumerate() {
+            if valid & (1 << table) != 0 {
+                *count = read_u32(bytes, cursor)?;
+                cursor += 4;
+            }
+        }
+
+        let mut result = Self {
+            rows,
+            start: [0; TABLE_COUNT],
+            string_index,
+            guid_index,
+            blob_index,
+            strings,
+            strings_size,
+        };
+        let mut offset = cursor;
+        for (table, &count) in rows.iter().enumerate().take(TABLE_COUNT) {
+            result.start[table] = offset;
+            offset += result.row_size(table) * count as usize;
+        }
+        Ok(result)
+    }
+
+    /// Width in bytes of a column.
+    fn width(&self, col: Col) -> usize {
+        let index_width = |tables: &[usize], tag_bits: u32| {
+            let max = tables.iter().map(|&t| self.rows[t]).max().unwrap_or(0);
+            if max < 1 << (16 - tag_bits) { 2 } else { 4 }
+        };
+        match col {
+            Col::U8 => 1,
+            Col::U16 => 2,
+            Col::U32 => 4,
+            Col::Str => self.string_index,
+            Col::Guid => self.guid_index,
+            Col::Blob => self.blob_index,
+            Col::Table(t) => index_width(&[t], 0),
+            Col::Coded(ts) => index_width(ts, usize::BITS - (ts.len() - 1).leading_zeros()),
+        }
+    }
+
+    fn row_size(&self, table: usize) -> usize {
+        columns(table).iter().map(|&c| self.width(c)).sum()
+    }
+
+    /// File offset of row `index` (zero-based) of `table`.
+    fn row(&self, table: usize, index: usize) -> usize {
+        self.start[table] + index * self.row_size(table)
+    }
+
+    /// Offset of column `col` within a row of `table`.
+    fn column(&self, table: usize, col: usize) -> usize {
+        columns(table)[..col].iter().map(|&c| self.width(c)).sum()
+    }
+
+    /// Read the string whose heap index is stored at `at`.
+    fn string(&self, bytes: &[u8], at: usize) -> Result<String> {
+        let index = read_index(bytes, at, self.string_index)?;
+        read_string(bytes, self.strings + index)
+    }
+
+    /// Heap index of `s` (possibly as the suffix of a longer string).
+    fn find_string(&self, bytes: &[u8], s: &str) -> Option<usize> {
+        let heap = bytes.get(self.strings..self.strings + self.strings_size)?;
+        let needle = [s.as_bytes(), b"\0"].concat();
+        // Index 0 is the empty string; skip it so `s` can't be "".
+        heap.windows(needle.len())
+            .skip(1)
+            .position(|w| w == needle)
+            .map(|i| i + 1)
+    }
+
+    /// Name and version from row `index` of the `Assembly` or
+    /// `AssemblyRef` table.
+    fn identity(&self, bytes: &[u8], table: usize, index: usize) -> Result<AssemblyIdentity> {
+        // Assembly starts with HashAlgId; AssemblyRef with the version.
+        let (version_col, name_col) = if table == ASSEMBLY { (1, 7) } else { (0, 6) };
+        let row = self.row(table, index);
+        let at = row + self.column(table, version_col);
+        let mut version = [0u16; 4];
+        for (i, part) in version.iter_mut().enumerate() {
+            *part = read_u16(bytes, at + 2 * i)?;
+        }
+        Ok(AssemblyIdentity {
+            name: self.string(bytes, row + self.column(table, name_col))?,
+            version: AssemblyVersion(version),
+        })
+    }
+}
+
+fn write_version(bytes: &mut [u8], at: usize, version: AssemblyVersion) {
+    for (i, part) in version.0.iter().enumerate() {
+        bytes[at + 2 * i..at + 2 * i + 2].copy_from_slice(&part.to_le_bytes());
+    }
+}
+
+fn write_index(bytes: &mut [u8], at: usize, width: usize, index: usize) {
+    if width == 4 {
+        bytes[at..at + 4].copy_from_slice(&(index as u32).to_le_bytes());
+    } else {
+        bytes[at..at + 2].copy_from_slice(&(index as u16).to_le_bytes());
+    }
+}
+
+fn read_u16(bytes: &[u8], at: usize) -> Result<u16> {
+    let b = bytes.get(at..at + 2).context("truncated winmd")?;
+    Ok(u16::from_le_bytes([b[0], b[1]]))
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> Result<u32> {
+    let b = bytes.get(at..at + 4).context("truncated winmd")?;
+    Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn read_u64(bytes: &[u8], at: usize) -> Result<u64> {
+    Ok(read_u32(bytes, at)? as u64 | (read_u32(bytes, at + 4)? as u64) << 32)
+}
+
+fn read_index(bytes: &[u8], at: usize, width: usize) -> Result<usize> {
+    Ok(if width == 4 {
+        read_u32(bytes, at)? as usize
+    } else {
+        read_u16(bytes, at)? as usize
+    })
+}
+
+fn read_string(bytes: &[u8], at: usize) -> Result<String> {
+    let tail = bytes.get(at..).context("truncated #Strings heap")?;
+    let len = tail
+        .iter()
+        .position(|&b| b == 0)
+        .context("unterminated string")?;
+    Ok(String::from_utf8_lossy(&tail[..len]).into_owned())
+}
@@ -3,22 +3,186 @@
 //! These types are clang-independent and winmd-independent, making both the extractor
 //! and emitter easier to test in isolation.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::DataModel;
+use crate::metadata::AssemblyIdentity;
+
+/// Everything the emitter needs: the partitions plus the registry that
+/// resolves type names across them. This is what
+/// [`dump_model`](crate::dump_model) writes and
+/// [`emit_from_model_json`](crate::emit_from_model_json) reads.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Model {
+    pub partitions: Vec<Partition>,
+    pub registry: TypeRegistry,
+}
 
 /// A fully extracted partition ready for winmd emission.
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Partition {
     pub namespace: String,
     pub library: String,
+    /// Data model the partition was parsed for, which sizes its
+    /// pointer-sized enums and constants. Dumps without one take the host's.
+    #[serde(default = "DataModel::host")]
+    pub data_model: DataModel,
     pub structs: Vec<StructDef>,
     pub enums: Vec<EnumDef>,
     pub functions: Vec<FunctionDef>,
     pub typedefs: Vec<TypedefDef>,
     pub constants: Vec<ConstantDef>,
+    /// `extern` global variables, emitted on the `Globals` class.
+    #[serde(default)]
+    pub variables: Vec<VariableDef>,
+    /// In-scope symbols dropped by `include_symbols` / `exclude_symbols`,
+    /// sorted by name.
+    pub filtered: Vec<String>,
+    /// In-scope declarations that could not be extracted.
+    pub dropped: Vec<DroppedDecl>,
+}
+
+/// Kind of a [`DroppedDecl`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DeclKind {
+    Struct,
+    Union,
+    Enum,
+    Function,
+    Typedef,
+    Variable,
+}
+
+/// A declaration that was in scope but could not be extracted, e.g. a
+/// function taking an unsupported type.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DroppedDecl {
+    pub name: String,
+    pub kind: DeclKind,
+    /// The extraction error, including its context chain.
+    pub reason: String,
+}
+
+impl DroppedDecl {
+    pub fn new(name: &str, kind: DeclKind, err: &anyhow::Error) -> Self {
+        Self {
+            name: name.to_string(),
+            kind,
+            reason: format!("{err:#}"),
+        }
+    }
+}
+
+/// What generation left out, per partition. Returned by
+/// [`generate_with_report`](crate::generate_with_report).
+#[derive(Debug, Default, Serialize)]
+pub struct Report {
+    pub partitions: Vec<PartitionReport>,
+}
+
+/// Dropped and filtered symbols of a single partition.
+#[derive(Debug, Serialize)]
+pub struct PartitionReport {
+    pub namespace: String,
+    pub dropped: Vec<DroppedDecl>,
+    pub filtered: Vec<String>,
+}
+
+impl Report {
+    /// Build the report from extracted partitions.
+    pub fn from_partitions(partitions: &[Partition]) -> Self {
+        let partitions = partitions
+            .iter()
+            .map(|p| PartitionReport {
+                namespace: p.namespace.clone(),
+                dropped: p.dropped.clone(),
+                filtered: p.filtered.clone(),
+            })
+            .collect();
+        Self { partitions }
+    }
+
+    /// Declarations dropped from the partition for `namespace`.
+    pub fn dropped(&self, namespace: &str) -> impl Iterator<Item = &DroppedDecl> {
+        self.partitions
+            .iter()
+            .filter(move |p| p.namespace == namespace)
+            .flat_map(|p| &p.dropped)
+    }
+
+    /// Returns true if no declaration was dropped from any partition.
+    pub fn is_clean(&self) -> bool {
+        self.partitions.iter().all(|p| p.dropped.is_empty())
+    }
+}
+
+/// Declaration counts of the extracted model. Returned by
+/// [`stats`](crate::stats).
+#[derive(Debug, Default, Serialize)]
+pub struct Stats {
+    pub partitions: Vec<PartitionStats>,
+    /// Number of names in the type registry, including imported types.
+    pub registry_types: usize,
+    /// Configured target triple and its data model, e.g.
+    /// `x86_64-unknown-linux-gnu (lp64)`.
+    pub target: Option<String>,
+}
+
+/// Declaration counts of a single partition, as handed to the emitter.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PartitionStats {
+    pub namespace: String,
+    pub structs: usize,
+    pub enums: usize,
+    pub functions: usize,
+    pub typedefs: usize,
+    pub constants: usize,
+    pub variables: usize,
+    pub dropped: usize,
+}
+
+impl PartitionStats {
+    pub fn from_partition(partition: &Partition) -> Self {
+        Self {
+            namespace: partition.namespace.clone(),
+            structs: partition.structs.len(),
+            enums: partition.enums.len(),
+            functions: partition.functions.len(),
+            typedefs: partition.typedefs.len(),
+            constants: partition.constants.len(),
+            variables: partition.variables.len(),
+            dropped: partition.dropped.len(),
+        }
+    }
+}
+
+impl std::fmt::Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(target) = &self.target {
+            writeln!(f, "target: {target}")?;
+        }
+        for p in &self.partitions {
+            writeln!(
+                f,
+                "{}: {} structs, {} enums, {} functions, {} typedefs, {} constants, {} variables, {} dropped",
+                p.namespace,
+                p.structs,
+                p.enums,
+                p.functions,
+                p.typedefs,
+                p.constants,
+                p.variables,
+                p.dropped
+            )?;
+        }
+        write!(f, "type registry: {} types", self.registry_types)
+    }
 }
 
 /// A C struct or union definition.
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct StructDef {
     pub name: String,
     pub size: usize,
@@ -26,10 +190,17 @@ pub struct StructDef {
     pub fields: Vec<FieldDef>,
     /// True if this is a C `union` (all fields at offset 0).
     pub is_union: bool,
+    /// Doc comment attached to the declaration, with comment markers removed.
+    pub doc: Option<String>,
+    /// Where the declaration is.
+    pub source: Option<SourceLocation>,
+    /// `Some` if the declaration is marked deprecated, holding the attribute
+    /// message (empty when the attribute has none).
+    pub deprecated: Option<String>,
 }
 
 /// A single struct field.
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct FieldDef {
     pub name: String,
     pub ty: CType,
@@ -37,19 +208,40 @@ pub struct FieldDef {
     pub bitfield_width: Option<usize>,
     /// Bit offset of a bitfield within the struct (from clang).
     pub bitfield_offset: Option<usize>,
+    /// Byte offset of the field within the struct (from clang), if known.
+    pub offset: Option<usize>,
+    /// Bitfields packed into this field when it is a synthetic `_bitfield`
+    /// storage unit. Empty for ordinary fields.
+    pub bitfields: Vec<Bitfield>,
+}
+
+/// A C bitfield member stored inside a `_bitfield` storage field.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Bitfield {
+    pub name: String,
+    /// Bit offset within the storage field.
+    pub offset: usize,
+    /// Width in bits.
+    pub width: usize,
 }
 
 /// A C enum definition.
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct EnumDef {
     pub name: String,
     /// The underlying integer type (e.g. `CType::U32`).
     pub underlying_type: CType,
     pub variants: Vec<EnumVariant>,
+    /// True if this is a bitmask enum, emitted with `System.FlagsAttribute`.
+    pub is_flags: bool,
+    /// Doc comment attached to the declaration, with comment markers removed.
+    pub doc: Option<String>,
+    /// Where the declaration is.
+    pub source: Option<SourceLocation>,
 }
 
 /// A single enum variant.
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct EnumVariant {
     pub name: String,
     /// Value as (signed, unsigned) pair — from clang.
@@ -58,45 +250,153 @@ pub struct EnumVariant {
 }
 
 /// A C function declaration.
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct FunctionDef {
     pub name: String,
+    /// Symbol to import from the library when it differs from `name`
+    /// (e.g. after `strip_prefix`). `None` means `name` is the symbol.
+    pub import_name: Option<String>,
     pub return_type: CType,
     pub params: Vec<ParamDef>,
     pub calling_convention: CallConv,
+    /// True if the function takes trailing `...` arguments.
+    pub is_variadic: bool,
+    /// Doc comment attached to the declaration, with comment markers removed.
+    pub doc: Option<String>,
+    /// Where the declaration is. `None` for `globals` accessors.
+    pub source: Option<SourceLocation>,
+    /// `Some` if the declaration is marked deprecated, holding the attribute
+    /// message (empty when the attribute has none).
+    pub deprecated: Option<String>,
+    /// True for `static` / `inline` functions, which have no exported
+    /// symbol. Only kept with `inline_functions = "mark"`.
+    pub inline: bool,
+    /// Function releasing what this function returns, from
+    /// `resource_pairs`.
+    pub free_with: Option<String>,
+    /// Library to import from instead of the partition's, from
+    /// `function_libraries`.
+    pub library: Option<String>,
+    /// True if the function is listed in `last_error_functions`.
+    pub sets_last_error: bool,
+    /// Width of the C strings the function takes and returns, when they are
+    /// all `char` or all `wchar_t`. Recorded as the ImplMap charset.
+    #[serde(default)]
+    pub charset: Option<CharSet>,
+}
+
+/// Character width of a function's string parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CharSet {
+    /// `char *` strings (`CharSetAnsi`).
+    Ansi,
+    /// `wchar_t *` strings (`CharSetUnicode`).
+    Unicode,
 }
 
 /// A function parameter.
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ParamDef {
     pub name: String,
     pub ty: CType,
+    /// Direction set by `param_directions` in the config. `None` lets the
+    /// emitter infer it from the type and name.
+    pub direction: Option<ParamDirection>,
+    /// C spelling of the type (e.g. `const char *const *`), kept when `ty`
+    /// has constness below the outer pointer that the signature loses.
+    pub native_type: Option<String>,
+    /// Index (0-based) of the parameter holding this array parameter's
+    /// element count, set by `array_params` in the config.
+    pub array_len: Option<usize>,
+}
+
+/// Marshalling direction of a function parameter.
+///
+/// ```toml
+/// param_directions = { "create_widget:out" = "out" }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParamDirection {
+    /// `[In]`: the callee only reads it.
+    In,
+    /// `[Out]`: the callee only writes through it.
+    Out,
+    /// `[In, Out]`: the callee reads and writes through it.
+    InOut,
+    /// `[Out]` plus `RetValAttribute`: the function's logical return value.
+    RetVal,
 }
 
 /// A C typedef.
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct TypedefDef {
     pub name: String,
     pub underlying_type: CType,
+    /// Where the declaration is.
+    pub source: Option<SourceLocation>,
+    /// Function releasing a handle of this type, from `resource_pairs`.
+    pub free_with: Option<String>,
+    /// Size and alignment in bytes of an array or vector typedef (`typedef
+    /// char path_t[256]`), which is emitted as a fixed buffer of that size.
+    pub array_layout: Option<(usize, usize)>,
 }
 
-/// A `#define` integer constant.
-#[derive(Debug)]
+/// An `extern` global variable (`extern int optind;`).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VariableDef {
+    pub name: String,
+    /// Symbol to import from the library when it differs from `name`.
+    pub import_name: Option<String>,
+    pub ty: CType,
+    /// True if the variable is declared `const`.
+    pub is_const: bool,
+    /// Doc comment attached to the declaration, with comment markers removed.
+    pub doc: Option<String>,
+    /// Where the declaration is.
+    pub source: Option<SourceLocation>,
+}
+
+/// The header and 1-based line of a declaration. `file` is relative to the
+/// config file's directory (with `/` separators) when the header is under
+/// it, and absolute otherwise (e.g. system headers).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceLocation {
+    pub file: String,
+    pub line: u32,
+}
+
+/// A `#define` constant, or a variant of an anonymous enum.
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ConstantDef {
     pub name: String,
     pub value: ConstantValue,
+    /// Comment documenting the `#define`, with comment markers removed.
+    pub doc: Option<String>,
 }
 
-/// Value of a `#define` constant.
-#[derive(Debug, Clone)]
+/// Value of a [`ConstantDef`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ConstantValue {
     Signed(i64),
     Unsigned(u64),
     Float(f64),
+    /// A variant of an anonymous enum, emitted with the enum's underlying
+    /// integer type. Holds the value as a (signed, unsigned) pair like
+    /// [`EnumVariant`].
+    Typed {
+        ty: CType,
+        signed: i64,
+        unsigned: u64,
+    },
+    /// An integer literal too wide for 64 bits (e.g. a 128-bit hex mask),
+    /// kept as its spelling without the suffix, with a leading `-` if
+    /// negated. Emitted as a string constant.
+    Wide(String),
 }
 
 /// Calling convention.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum CallConv {
     /// Platform default (cdecl on most platforms).
     Cdecl,
@@ -104,12 +404,36 @@ pub enum CallConv {
     Stdcall,
     /// Fastcall.
     Fastcall,
+    /// thiscall (MSVC x86 member functions).
+    Thiscall,
+    /// vectorcall (MSVC x86/x64).
+    Vectorcall,
+    /// regcall (Intel).
+    Regcall,
+    /// Pascal (legacy x86).
+    Pascal,
+    /// Microsoft x64 (`ms_abi`).
+    Win64,
+    /// System V AMD64 (`sysv_abi`).
+    SysV64,
+    /// ARM AAPCS.
+    Aapcs,
+    /// ARM AAPCS with VFP registers.
+    AapcsVfp,
+    /// Swift.
+    Swift,
+    /// `preserve_most`.
+    PreserveMost,
+    /// `preserve_all`.
+    PreserveAll,
+    /// Intel OpenCL built-ins.
+    IntelOcl,
 }
 
 /// A C type — our intermediate representation.
 ///
 /// Maps closely to both clang's `TypeKind` and ECMA-335's `Type` enum.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum CType {
     Void,
     Bool,
@@ -125,6 +449,21 @@ pub enum CType {
     F64,
     ISize,
     USize,
+    /// `char16_t` (and `wchar_t` under LLP64). Emitted as `u16`.
+    Char16,
+    /// `char32_t` (and `wchar_t` under LP64). Emitted as `u32`.
+    Char32,
+    /// C `long double`. Its width is target-dependent (80-bit x87 padded to
+    /// 16 bytes on x86-64, 64-bit on MSVC, 128-bit IEEE quad on aarch64), so
+    /// `size` carries `sizeof(long double)` as reported by clang.
+    LongDouble {
+        size: usize,
+    },
+    /// `__int128`. Emitted as an opaque 16-byte array: ECMA-335 has no
+    /// 128-bit primitive.
+    I128,
+    /// `unsigned __int128`. Emitted like [`CType::I128`].
+    U128,
     /// Pointer to a type. `is_const` indicates `const T*`.
     Ptr {
         pointee: Box<CType>,
@@ -136,13 +475,15 @@ pub enum CType {
         len: usize,
     },
     /// A named type reference (struct, enum, typedef in another namespace).
-    /// For typedefs, `resolved` holds the canonical primitive type from clang,
-    /// used as fallback when the name isn't in the TypeRegistry.
+    /// For typedefs and enums, `resolved` holds the aliased type, used as
+    /// fallback when the name isn't in the TypeRegistry.
     Named {
         name: String,
-        /// Canonical type resolved by clang. `None` for records/enums
-        /// (they must be in the registry). `Some` for typedefs so we can
-        /// fall back to the primitive when the typedef isn't extracted.
+        /// The typedef's underlying type, one link down the chain (itself
+        /// `Named` for a typedef of a typedef), or an enum's integer type.
+        /// `None` for records (they must be in the registry). `Some`
+        /// otherwise so we can fall back to the aliased type when the
+        /// typedef or enum isn't extracted.
         resolved: Option<Box<CType>>,
     },
     /// A function pointer type.
@@ -153,7 +494,227 @@ pub enum CType {
     },
 }
 
+impl Partition {
+    /// Names of the structs, enums and typedefs this partition declares.
+    pub fn type_names(&self) -> impl Iterator<Item = &str> {
+        self.structs
+            .iter()
+            .map(|s| s.name.as_str())
+            .chain(self.enums.iter().map(|e| e.name.as_str()))
+            .chain(self.typedefs.iter().map(|td| td.name.as_str()))
+    }
+
+    /// Rewrite every `CType::Named` reference in this partition according to
+    /// `renames` (old name → new name).
+    pub fn rename_type_references(&mut self, renames: &HashMap<String, String>) {
+        for s in &mut self.structs {
+            for field in &mut s.fields {
+                field.ty.rename_named(renames);
+            }
+        }
+        for f in &mut self.functions {
+            f.return_type.rename_named(renames);
+            for param in &mut f.params {
+                param.ty.rename_named(renames);
+            }
+        }
+        for td in &mut self.typedefs {
+            td.underlying_type.rename_named(renames);
+        }
+        for g in &mut self.variables {
+            g.ty.rename_named(renames);
+        }
+    }
+
+    /// Turn the typedefs named in `handles` into pointer-sized opaque handles
+    /// and drop one pointer level from every reference to them, so
+    /// `EVP_MD_CTX *` is passed around as `EVP_MD_CTX` by value.
+    ///
+    /// Returns the handle names declared as typedefs in this partition.
+    pub fn apply_opaque_handles(&mut self, handles: &HashSet<String>) -> Vec<String> {
+        let mut declared = Vec::new();
+        for td in &mut self.typedefs {
+            if handles.contains(&td.name) {
+                td.underlying_type = CType::opaque_pointer();
+                declared.push(td.name.clone());
+            } else {
+                td.underlying_type.collapse_handle_pointers(handles);
+            }
+        }
+        for s in &mut self.structs {
+            for field in &mut s.fields {
+                field.ty.collapse_handle_pointers(handles);
+            }
+        }
+        for f in &mut self.functions {
+            f.return_type.collapse_handle_pointers(handles);
+            for param in &mut f.params {
+                param.ty.collapse_handle_pointers(handles);
+            }
+        }
+        for g in &mut self.variables {
+            g.ty.collapse_handle_pointers(handles);
+        }
+        declared
+    }
+}
+
 impl CType {
+    /// `void *` — the representation of an opaque handle.
+    pub fn opaque_pointer() -> CType {
+        CType::Ptr {
+            pointee: Box::new(CType::Void),
+            is_const: false,
+        }
+    }
+
+    /// Recursively replace `Ptr { Named(h) }` with `Named(h)` for every
+    /// opaque handle `h` in `handles`.
+    pub fn collapse_handle_pointers(&mut self, handles: &HashSet<String>) {
+        match self {
+            CType::Ptr { pointee, .. } => {
+                if let CType::Named { name, .. } = pointee.as_ref()
+                    && handles.contains(name)
+                {
+                    *self = CType::Named {
+                        name: name.clone(),
+                        resolved: Some(Box::new(CType::opaque_pointer())),
+                    };
+                } else {
+                    pointee.collapse_handle_pointers(handles);
+                }
+            }
+            CType::Array { element, .. } => element.collapse_handle_pointers(handles),
+            CType::FnPtr {
+                return_type,
+                params,
+                ..
+            } => {
+                return_type.collapse_handle_pointers(handles);
+                for p in params {
+                    p.collapse_handle_pointers(handles);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Recursively rename `Named` types found in `renames` (old → new).
+    pub fn rename_named(&mut self, renames: &HashMap<String, String>) {
+        match self {
+            CType::Named { name, resolved } => {
+                if let Some(new_name) = renames.get(name) {
+                    *name = new_name.clone();
+                }
+                if let Some(resolved) = resolved {
+                    resolved.rename_named(renames);
+                }
+            }
+            CType::Ptr { pointee, .. } => pointee.rename_named(renames),
+            CType::Array { element, .. } => element.rename_named(renames),
+            CType::FnPtr {
+                return_type,
+                params,
+                ..
+            } => {
+                return_type.rename_named(renames);
+                for p in params {
+                    p.rename_named(renames);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Call `f` with the name of every `Named` type this type refers to,
+    /// through pointers, arrays, function pointers and typedef fallbacks.
+    pub fn for_each_named(&self, f: &mut impl FnMut(&str)) {
+        match self {
+            CType::Named { name, resolved } => {
+                f(name);
+                if let Some(resolved) = resolved {
+                    resolved.for_each_named(f);
+                }
+            }
+            CType::Ptr { pointee, .. } => pointee.for_each_named(f),
+            CType::Array { element, .. } => element.for_each_named(f),
+            CType::FnPtr {
+                return_type,
+                params,
+                ..
+            } => {
+                return_type.for_each_named(f);
+                for p in params {
+                    p.for_each_named(f);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// If this type is (or points to / is an array of) a wide character
+    /// type, returns its C name for the `NativeTypeNameAttribute` hint.
+    pub fn wide_char_name(&self) -> Option<&'static str> {
+        match self {
+            CType::Char16 => Some("char16_t"),
+            CType::Char32 => Some("char32_t"),
+            CType::Ptr { pointee, .. } => pointee.wide_char_name(),
+            CType::Array { element, .. } => element.wide_char_name(),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the type is emitted as an opaque byte array
+    /// (`long double`, `__int128`, or arrays of them), which has alignment 1.
+    pub fn is_opaque_bytes(&self) -> bool {
+        match self {
+            CType::LongDouble { .. } | CType::I128 | CType::U128 => true,
+            CType::Array { element, .. } => element.is_opaque_bytes(),
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the type is an integer, directly or through a
+    /// typedef chain.
+    pub fn is_integer(&self) -> bool {
+        match self {
+            CType::I8
+            | CType::U8
+            | CType::I16
+            | CType::U16
+            | CType::I32
+            | CType::U32
+            | CType::I64
+            | CType::U64
+            | CType::ISize
+            | CType::USize => true,
+            CType::Named {
+                resolved: Some(resolved),
+                ..
+            } => resolved.is_integer(),
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the outermost type is a pointer to const data
+    /// (`const T *`, including `T *const *`).
+    pub fn is_outer_ptr_const(&self) -> bool {
+        matches!(self, CType::Ptr { is_const: true, .. })
+    }
+
+    /// Returns `true` if a pointer below the outermost one points to const
+    /// data (`const char **`, `const char *const *`). Only the outer level
+    /// maps to `ConstAttribute`.
+    pub fn has_inner_ptr_const(&self) -> bool {
+        match self {
+            CType::Ptr { pointee, .. } => {
+                matches!(pointee.as_ref(), CType::Ptr { is_const: true, .. })
+                    || pointee.has_inner_ptr_const()
+            }
+            _ => false,
+        }
+    }
+
     /// Returns `true` if the outermost type is a mutable pointer (`T *`,
     /// i.e. `Ptr { is_const: false }`).
     pub fn is_outer_ptr_mut(&self) -> bool {
@@ -171,15 +732,68 @@ impl CType {
 ///
 /// Built during extraction by scanning all partitions, then used during
 /// emission to resolve `CType::Named` references to the correct namespace.
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TypeRegistry {
     /// Maps type name → namespace.
+    #[serde(serialize_with = "serialize_sorted")]
     pub types: HashMap<String, String>,
+    /// Maps type name → the external assembly it was imported from (see
+    /// `[[type_import]]`). Types defined in this winmd have no entry.
+    #[serde(serialize_with = "serialize_sorted")]
+    pub imported: HashMap<String, AssemblyIdentity>,
+    /// Imported types that are COM-style interfaces, mapped to their IID
+    /// (from the winmd's `GuidAttribute`, formatted like
+    /// `00000000-0000-0000-c000-000000000046`) when it has one.
+    #[serde(default, serialize_with = "serialize_sorted")]
+    pub interfaces: HashMap<String, Option<String>>,
+}
+
+/// Serialize a map with sorted keys, so model dumps are reproducible.
+fn serialize_sorted<V: Serialize, S: serde::Serializer>(
+    map: &HashMap<String, V>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    map.iter()
+        .collect::<std::collections::BTreeMap<_, _>>()
+        .serialize(serializer)
 }
 
 impl TypeRegistry {
     pub fn register(&mut self, name: &str, namespace: &str) {
         self.types.insert(name.to_string(), namespace.to_string());
+        self.imported.remove(name);
+        self.interfaces.remove(name);
+    }
+
+    /// Register a type defined by the external assembly `assembly`.
+    pub fn register_imported(&mut self, name: &str, namespace: &str, assembly: &AssemblyIdentity) {
+        self.types.insert(name.to_string(), namespace.to_string());
+        self.imported.insert(name.to_string(), assembly.clone());
+        self.interfaces.remove(name);
+    }
+
+    /// Register an interface defined by the external assembly `assembly`,
+    /// with its IID if known.
+    pub fn register_imported_interface(
+        &mut self,
+        name: &str,
+        namespace: &str,
+        assembly: &AssemblyIdentity,
+        iid: Option<String>,
+    ) {
+        self.register_imported(name, namespace, assembly);
+        self.interfaces.insert(name.to_string(), iid);
+    }
+
+    /// Returns true if the type name is an imported interface. Interfaces
+    /// are reference types, so `IFoo *` in C is just `IFoo` in the winmd.
+    pub fn is_interface(&self, name: &str) -> bool {
+        self.interfaces.contains_key(name)
+    }
+
+    /// The IID of an imported interface, if its winmd records one.
+    pub fn interface_iid(&self, name: &str) -> Option<&str> {
+        self.interfaces.get(name)?.as_deref()
     }
 
     /// Returns true if the type name is registered (i.e. was extracted from
@@ -0,0 +1,115 @@
+//! Verifier — re-reads an emitted winmd and checks structural invariants.
+//!
+//! The writer accepts anything it is given, so emitter bugs otherwise only
+//! surface later as windows-bindgen panics. [`verify`] checks that:
+//!
+//! - every named type referenced by a field or method signature is defined
+//!   in the file itself or registered (e.g. via `[[type_import]]`) under the
+//!   same namespace,
+//! - no namespace defines the same type name twice,
+//! - every method of an `Apis` class carries an `ImplMap` (P/Invoke) row.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use windows_metadata::Type;
+use windows_metadata::reader::{File, TypeIndex};
+
+use crate::config::TypeImportConfig;
+use crate::model::TypeRegistry;
+
+/// Check the invariants listed in the [module docs](self) on the winmd in
+/// `bytes`. `registry` supplies the types the file may reference without
+/// defining them; pass an empty registry for a self-contained winmd.
+///
+/// All violations are collected and reported in a single error.
+pub fn verify(bytes: &[u8], registry: &TypeRegistry) -> Result<()> {
+    let file = File::new(bytes.to_vec()).context("not a valid winmd file")?;
+    let index = TypeIndex::new(vec![file]);
+
+    // BTreeSet keeps the report sorted and reports each problem once.
+    let mut problems = BTreeSet::new();
+
+    for (namespace, name, td) in index.iter() {
+        if index.get(namespace, name).nth(1).is_some() {
+            problems.insert(format!("duplicate type `{namespace}.{name}`"));
+        }
+
+        for field in td.fields() {
+            let context = format!("field `{namespace}.{name}.{}`", field.name());
+            check_type(&field.ty(), &index, registry, &context, &mut problems);
+        }
+
+        for method in td.methods() {
+            let context = format!("method `{namespace}.{name}.{}`", method.name());
+            let sig = method.signature(&[]);
+            check_type(&sig.return_type, &index, registry, &context, &mut problems);
+            for ty in &sig.types {
+                check_type(ty, &index, registry, &context, &mut problems);
+            }
+            if name == "Apis" && method.impl_map().is_none() {
+                problems.insert(format!("{context} has no ImplMap"));
+            }
+        }
+    }
+
+    if problems.is_empty() {
+        return Ok(());
+    }
+
+    let mut msg = format!("winmd verification found {} problem(s):\n", problems.len());
+    for p in &problems {
+        msg.push_str(&format!("\n  • {p}"));
+    }
+    anyhow::bail!("{msg}");
+}
+
+/// Read the winmd at `path` and [`verify`] it.
+pub fn verify_file(path: &Path, registry: &TypeRegistry) -> Result<()> {
+    let bytes = std::fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+    verify(&bytes, registry).with_context(|| format!("verifying {}", path.display()))
+}
+
+/// Build a registry of every type defined in the winmds at `paths`, for
+/// verifying a winmd generated with `[[type_import]]`s.
+pub fn imported_types(paths: &[impl AsRef<Path>]) -> Result<TypeRegistry> {
+    let mut registry = TypeRegistry::default();
+    let import_all = TypeImportConfig::default();
+    for path in paths {
+        crate::seed_registry_from_winmd(&mut registry, path.as_ref(), &import_all)?;
+    }
+    Ok(registry)
+}
+
+/// Recursively check that every named type inside `ty` resolves.
+fn check_type(
+    ty: &Type,
+    index: &TypeIndex,
+    registry: &TypeRegistry,
+    context: &str,
+    problems: &mut BTreeSet<String>,
+) {
+    match ty {
+        Type::Name(tn) => {
+            let imported = registry
+                .types
+                .get(&tn.name)
+                .is_some_and(|ns| *ns == tn.namespace);
+            if !index.contains(&tn.namespace, &tn.name) && !imported {
+                problems.insert(format!(
+                    "dangling type reference `{}.{}` in {context}",
+                    tn.namespace, tn.name
+                ));
+            }
+        }
+        Type::Array(inner)
+        | Type::ArrayRef(inner)
+        | Type::RefMut(inner)
+        | Type::RefConst(inner)
+        | Type::PtrMut(inner, _)
+        | Type::PtrConst(inner, _)
+        | Type::ArrayFixed(inner, _) => check_type(inner, index, registry, context, problems),
+        _ => {}
+    }
+}
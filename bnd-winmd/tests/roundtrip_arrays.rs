@@ -0,0 +1,56 @@
+//! Round-trip integration test: `array_params` marks a buffer parameter
+//! with the index of its length parameter.
+
+use std::path::Path;
+use std::sync::LazyLock;
+
+use windows_metadata::{HasAttributes, Value};
+
+static ARRAYS_WINMD: LazyLock<Vec<u8>> = LazyLock::new(|| {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/arrays/arrays.toml");
+    bnd_winmd::generate(&path).expect("generate arrays winmd")
+});
+
+/// The `NativeArrayInfoAttribute` count index of each parameter of `name`.
+fn array_infos(name: &str) -> Vec<(String, Option<i16>)> {
+    let file = windows_metadata::reader::File::new(ARRAYS_WINMD.clone()).expect("parse winmd");
+    let index = windows_metadata::reader::TypeIndex::new(vec![file]);
+    let method = index
+        .expect("ArrayTest", "Apis")
+        .methods()
+        .find(|m| m.name() == name)
+        .unwrap_or_else(|| panic!("{name} not found"));
+    method
+        .params()
+        .filter(|p| p.sequence() > 0)
+        .map(|p| {
+            let info =
+                p.find_attribute("NativeArrayInfoAttribute")
+                    .map(|a| match a.value().as_slice() {
+                        [(field, Value::I16(index))] if field == "CountParamIndex" => *index,
+                        other => panic!("unexpected NativeArrayInfoAttribute value: {other:?}"),
+                    });
+            (p.name().to_string(), info)
+        })
+        .collect()
+}
+
+#[test]
+fn buffer_references_its_length_param() {
+    assert_eq!(
+        array_infos("RAND_bytes"),
+        [("buf".to_string(), Some(1)), ("num".to_string(), None)]
+    );
+}
+
+#[test]
+fn length_index_counts_from_the_first_param() {
+    assert_eq!(
+        array_infos("RAND_seed"),
+        [
+            ("flags".to_string(), None),
+            ("seed".to_string(), Some(2)),
+            ("len".to_string(), None),
+        ]
+    );
+}
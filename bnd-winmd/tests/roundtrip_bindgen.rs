@@ -0,0 +1,58 @@
+//! Integration test: `generate_bindings` writes the winmd and runs
+//! windows-bindgen on it.
+
+use std::path::Path;
+
+use bnd_winmd::{BindgenLayout, BindgenOptions};
+
+#[test]
+fn zlib_fixture_produces_flat_bindings() {
+    let config = Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/zlib/zlib.toml");
+    let dir = std::env::temp_dir().join(format!("bnd_winmd_bindgen_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let generated = bnd_winmd::generate_bindings(
+        &config,
+        &dir.join("src"),
+        &BindgenOptions {
+            filter: vec!["Zlib".to_string()],
+            layout: BindgenLayout::Flat,
+            sys: true,
+            winmd_dir: Some(dir.join("winmd")),
+            ..Default::default()
+        },
+    )
+    .expect("generate bindings");
+
+    assert_eq!(generated.winmds, vec![dir.join("winmd/zlib.winmd")]);
+    assert!(generated.winmds[0].exists());
+    assert_eq!(generated.bindings, dir.join("src/bindings.rs"));
+
+    let bindings = std::fs::read_to_string(&generated.bindings).expect("read bindings.rs");
+    assert!(bindings.contains("fn deflate("), "{bindings}");
+    assert!(bindings.contains("pub struct z_stream_s"), "{bindings}");
+}
+
+#[test]
+fn cli_emit_rust_writes_winmd_and_bindings() {
+    let config = Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/zlib/zlib.toml");
+    let dir = std::env::temp_dir().join(format!("bnd_winmd_emit_rust_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let winmd = dir.join("zlib.winmd");
+
+    let status = std::process::Command::new(env!("CARGO_BIN_EXE_bnd-winmd"))
+        .arg(&config)
+        .arg("-o")
+        .arg(&winmd)
+        .arg("--emit-rust")
+        .arg(dir.join("src"))
+        .args(["--filter", "Zlib", "--flat", "--sys"])
+        .status()
+        .expect("run bnd-winmd");
+    assert!(status.success());
+
+    assert!(winmd.exists());
+    let bindings = std::fs::read_to_string(dir.join("src/bindings.rs")).expect("read bindings.rs");
+    assert!(bindings.contains("fn deflate("), "{bindings}");
+}
@@ -0,0 +1,37 @@
+//! Round-trip integration test: functions taking Apple block pointers are
+//! kept, with the block as an opaque pointer.
+
+use std::path::Path;
+
+use windows_metadata::Type;
+
+#[test]
+fn block_params_become_opaque_pointers() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/blocks/blocks.toml");
+    let (winmd, report) = bnd_winmd::generate_with_report(&path).expect("generate blocks winmd");
+    assert!(report.is_clean(), "dropped: {report:#?}");
+
+    let file = windows_metadata::reader::File::new(winmd).expect("parse winmd");
+    let index = windows_metadata::reader::TypeIndex::new(vec![file]);
+    let apis = index.expect("BlocksTest", "Apis");
+    let param_types = |name: &str| {
+        apis.methods()
+            .find(|m| m.name() == name)
+            .unwrap_or_else(|| panic!("{name} was dropped"))
+            .signature(&[])
+            .types
+    };
+
+    assert_eq!(
+        param_types("run_async"),
+        vec![Type::I32, Type::PtrMut(Box::new(Type::Void), 1)]
+    );
+    assert_eq!(
+        param_types("run_with"),
+        vec![Type::named("BlocksTest", "completion_t")]
+    );
+
+    let completion = index.expect("BlocksTest", "completion_t");
+    let field = completion.fields().next().expect("typedef value field");
+    assert_eq!(field.ty(), Type::PtrMut(Box::new(Type::Void), 1));
+}
@@ -0,0 +1,72 @@
+//! Integration test: `run_cached` skips regeneration while inputs are unchanged.
+
+use std::path::{Path, PathBuf};
+
+use bnd_winmd::CachePolicy;
+
+/// Copy the `simple` fixture into a fresh temp directory so the test can
+/// edit the header without touching the shared fixture.
+fn simple_fixture_copy(name: &str) -> PathBuf {
+    let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/simple");
+    let dir = std::env::temp_dir().join(format!("bnd_winmd_cache_{name}_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    for file in ["simple.h", "simple.toml"] {
+        std::fs::copy(src.join(file), dir.join(file)).unwrap();
+    }
+    dir
+}
+
+#[test]
+fn unchanged_inputs_reuse_output() {
+    let dir = simple_fixture_copy("reuse");
+    let config = dir.join("simple.toml");
+
+    let output =
+        bnd_winmd::run_cached(&config, None, CachePolicy::ReuseIfUnchanged).unwrap()[0].clone();
+    assert!(bnd_winmd::cache::stamp_path(&output).exists());
+
+    // Replace the output with a sentinel: a cache hit must leave it alone.
+    std::fs::write(&output, b"sentinel").unwrap();
+    bnd_winmd::run_cached(&config, None, CachePolicy::ReuseIfUnchanged).unwrap();
+    assert_eq!(std::fs::read(&output).unwrap(), b"sentinel");
+
+    // Regenerate ignores the stamp.
+    bnd_winmd::run_cached(&config, None, CachePolicy::Regenerate).unwrap();
+    assert_ne!(std::fs::read(&output).unwrap(), b"sentinel");
+}
+
+#[test]
+fn header_change_regenerates() {
+    let dir = simple_fixture_copy("header");
+    let config = dir.join("simple.toml");
+
+    let output =
+        bnd_winmd::run_cached(&config, None, CachePolicy::ReuseIfUnchanged).unwrap()[0].clone();
+    std::fs::write(&output, b"sentinel").unwrap();
+
+    let header = dir.join("simple.h");
+    let mut text = std::fs::read_to_string(&header).unwrap();
+    text.push_str("\n#define CACHE_BUST 1\n");
+    std::fs::write(&header, text).unwrap();
+
+    bnd_winmd::run_cached(&config, None, CachePolicy::ReuseIfUnchanged).unwrap();
+    assert_ne!(std::fs::read(&output).unwrap(), b"sentinel");
+}
+
+#[test]
+fn config_change_regenerates() {
+    let dir = simple_fixture_copy("config");
+    let config = dir.join("simple.toml");
+
+    let output =
+        bnd_winmd::run_cached(&config, None, CachePolicy::ReuseIfUnchanged).unwrap()[0].clone();
+    std::fs::write(&output, b"sentinel").unwrap();
+
+    let mut toml = std::fs::read_to_string(&config).unwrap();
+    toml.push_str("clang_args = [\"-DCACHE_BUST\"]\n");
+    std::fs::write(&config, toml).unwrap();
+
+    bnd_winmd::run_cached(&config, None, CachePolicy::ReuseIfUnchanged).unwrap();
+    assert_ne!(std::fs::read(&output).unwrap(), b"sentinel");
+}
@@ -0,0 +1,56 @@
+//! Round-trip integration test: calling conventions map onto the P/Invoke
+//! flags windows-bindgen understands.
+
+use std::path::Path;
+use std::sync::LazyLock;
+
+use windows_metadata::PInvokeAttributes;
+
+static CALLCONV_WINMD: LazyLock<Vec<u8>> = LazyLock::new(|| {
+    let path =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/callconv/callconv.toml");
+    bnd_winmd::generate(&path).expect("generate callconv winmd")
+});
+
+fn pinvoke_flags(name: &str) -> PInvokeAttributes {
+    let file = windows_metadata::reader::File::new(CALLCONV_WINMD.clone()).expect("parse winmd");
+    let index = windows_metadata::reader::TypeIndex::new(vec![file]);
+    let apis = index.expect("CallConvTest", "Apis");
+    let method = apis
+        .methods()
+        .find(|m| m.name() == name)
+        .unwrap_or_else(|| panic!("{name} not found"));
+    method
+        .impl_map()
+        .unwrap_or_else(|| panic!("{name} has no ImplMap"))
+        .flags()
+}
+
+#[test]
+fn cdecl_is_emitted_as_cdecl() {
+    assert_eq!(pinvoke_flags("cc_cdecl"), PInvokeAttributes::CallConvCdecl);
+}
+
+#[test]
+fn ms_abi_on_windows_is_the_default_convention() {
+    // clang folds `ms_abi` into the target default (C) on Windows targets.
+    assert_eq!(pinvoke_flags("cc_ms_abi"), PInvokeAttributes::CallConvCdecl);
+}
+
+#[test]
+fn vectorcall_falls_back_to_platformapi() {
+    // winmd has no vectorcall encoding; the function is still emitted, as
+    // `extern "system"`, with a warning.
+    assert_eq!(
+        pinvoke_flags("cc_vectorcall"),
+        PInvokeAttributes::CallConvPlatformapi
+    );
+}
+
+#[test]
+fn sysv_abi_falls_back_to_cdecl() {
+    assert_eq!(
+        pinvoke_flags("cc_sysv_abi"),
+        PInvokeAttributes::CallConvCdecl
+    );
+}
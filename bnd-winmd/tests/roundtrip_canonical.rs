@@ -0,0 +1,48 @@
+//! Round-trip integration test: `canonical_types` decides which namespace
+//! keeps a type several partitions extract, regardless of their order.
+
+use std::path::Path;
+use std::sync::LazyLock;
+
+use windows_metadata::Type;
+
+static CANONICAL_WINMD: LazyLock<Vec<u8>> = LazyLock::new(|| {
+    let path =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/canonical/canonical.toml");
+    bnd_winmd::generate(&path).expect("generate canonical winmd")
+});
+
+fn open_index() -> windows_metadata::reader::TypeIndex {
+    let file = windows_metadata::reader::File::new(CANONICAL_WINMD.clone()).expect("parse winmd");
+    windows_metadata::reader::TypeIndex::new(vec![file])
+}
+
+#[test]
+fn shared_types_land_in_the_mapped_namespace() {
+    let index = open_index();
+
+    for name in ["handle_t", "Shared"] {
+        assert!(
+            index.contains("CanonicalTest.Types", name),
+            "{name} should be in the types namespace"
+        );
+        assert!(
+            !index.contains("CanonicalTest.Api", name),
+            "{name} should not be duplicated into the api namespace"
+        );
+    }
+
+    let open = index
+        .expect("CanonicalTest.Api", "Apis")
+        .methods()
+        .find(|m| m.name() == "shared_open")
+        .expect("shared_open")
+        .signature(&[]);
+    assert_eq!(
+        open.types,
+        [
+            Type::named("CanonicalTest.Types", "handle_t"),
+            Type::PtrMut(Box::new(Type::named("CanonicalTest.Types", "Shared")), 1),
+        ]
+    );
+}
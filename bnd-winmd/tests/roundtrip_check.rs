@@ -0,0 +1,73 @@
+//! Integration test: `validate_config` reports config problems without
+//! running clang.
+
+use std::path::{Path, PathBuf};
+
+fn fixture(path: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../tests/fixtures")
+        .join(path)
+}
+
+fn warnings(config: &Path) -> Vec<String> {
+    let cfg = bnd_winmd::config::load_config(config).expect("load config");
+    bnd_winmd::config::validate_config(&cfg, config.parent().unwrap())
+        .expect("validate config")
+        .iter()
+        .map(|w| w.to_string())
+        .collect()
+}
+
+#[test]
+fn broken_config_is_reported() {
+    let warnings = warnings(&fixture("check/broken.toml"));
+    let has = |needle: &str| warnings.iter().any(|w| w.contains(needle));
+
+    assert!(
+        has("headers entry `not_there.h` not found"),
+        "{warnings:#?}"
+    );
+    assert!(
+        has("namespace `CheckTest` is shared by partitions linking `check` and `other`"),
+        "{warnings:#?}"
+    );
+    assert!(
+        has("type_import winmd `missing.winmd` not found"),
+        "{warnings:#?}"
+    );
+    assert!(has("`NoSuchType` does not occur"), "{warnings:#?}");
+    assert!(
+        !has("`Point`"),
+        "Point is declared in check.h: {warnings:#?}"
+    );
+    assert_eq!(warnings.len(), 4, "{warnings:#?}");
+}
+
+#[test]
+fn valid_config_has_no_warnings() {
+    assert_eq!(warnings(&fixture("multi/multi.toml")), Vec::<String>::new());
+    assert_eq!(
+        warnings(&fixture("overrides/overrides.toml")),
+        Vec::<String>::new()
+    );
+}
+
+fn load_error(config: &Path) -> String {
+    let err = bnd_winmd::config::load_config(config).expect_err("config has a misspelled key");
+    format!("{err:#}")
+}
+
+#[test]
+fn misspelled_keys_are_rejected() {
+    for (file, key) in [
+        ("check/typo.toml", "namesapce"),
+        ("check/typo_table.toml", "partitions"),
+    ] {
+        let err = load_error(&fixture(file));
+        assert!(err.contains(file), "error should name the file: {err}");
+        assert!(
+            err.contains(&format!("unknown field `{key}`")),
+            "error should name the key: {err}"
+        );
+    }
+}
@@ -0,0 +1,84 @@
+//! Round-trip integration test: C99 `_Complex` types map to synthetic
+//! `ComplexF32` / `ComplexF64` structs with `re` / `im` fields.
+
+use std::path::Path;
+use std::sync::LazyLock;
+
+use windows_metadata::Type;
+
+static COMPLEX_WINMD: LazyLock<Vec<u8>> = LazyLock::new(|| {
+    let config =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/complex/complex.toml");
+    bnd_winmd::generate(&config).expect("generate complex winmd")
+});
+
+fn open_index() -> windows_metadata::reader::TypeIndex {
+    let file = windows_metadata::reader::File::new(COMPLEX_WINMD.clone()).expect("parse winmd");
+    windows_metadata::reader::TypeIndex::new(vec![file])
+}
+
+#[test]
+fn complex_f64_is_re_im_struct() {
+    let index = open_index();
+    let complex = index.expect("ComplexTest.Dsp", "ComplexF64");
+
+    let fields: Vec<(String, Type)> = complex
+        .fields()
+        .map(|f| (f.name().to_string(), f.ty()))
+        .collect();
+    assert_eq!(
+        fields,
+        vec![("re".to_string(), Type::F64), ("im".to_string(), Type::F64)]
+    );
+
+    let layout = complex.class_layout().expect("ComplexF64 layout");
+    assert_eq!(layout.class_size(), 16);
+    assert_eq!(layout.packing_size(), 8);
+}
+
+#[test]
+fn complex_f32_has_half_the_size() {
+    let index = open_index();
+    let layout = index
+        .expect("ComplexTest", "ComplexF32")
+        .class_layout()
+        .expect("ComplexF32 layout");
+    assert_eq!(layout.class_size(), 8);
+    assert_eq!(layout.packing_size(), 4);
+}
+
+#[test]
+fn struct_with_complex_field_keeps_layout() {
+    let index = open_index();
+    let sample = index.expect("ComplexTest", "Sample");
+
+    let value = sample.fields().find(|f| f.name() == "value").unwrap();
+    assert_eq!(value.ty(), Type::named("ComplexTest.Dsp", "ComplexF64"));
+    assert_eq!(
+        sample.class_layout().expect("Sample layout").class_size(),
+        24
+    );
+}
+
+/// Like any struct defined by several partitions, the last one claims it.
+#[test]
+fn complex_struct_is_emitted_once() {
+    let index = open_index();
+    let count = index.types().filter(|td| td.name() == "ComplexF64").count();
+    assert_eq!(count, 1, "ComplexF64 should be shared across partitions");
+}
+
+#[test]
+fn complex_pointer_param_in_second_partition() {
+    let index = open_index();
+    let fft = index
+        .expect("ComplexTest.Dsp", "Apis")
+        .methods()
+        .find(|m| m.name() == "fft")
+        .expect("fft");
+    let sig = fft.signature(&[]);
+    assert_eq!(
+        sig.types[0],
+        Type::PtrMut(Box::new(Type::named("ComplexTest.Dsp", "ComplexF64")), 1)
+    );
+}
@@ -0,0 +1,46 @@
+//! Concurrent generations of configs that share a namespace and need a
+//! multi-header wrapper must not interfere with each other.
+
+use std::path::Path;
+use std::thread;
+
+fn type_names(winmd: Vec<u8>) -> Vec<String> {
+    let file = windows_metadata::reader::File::new(winmd).expect("parse winmd");
+    let index = windows_metadata::reader::TypeIndex::new(vec![file]);
+    index.types().map(|td| td.name().to_string()).collect()
+}
+
+#[test]
+fn concurrent_generations_sharing_a_namespace() {
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/concurrent");
+
+    let handles: Vec<_> = ["alpha", "beta"]
+        .into_iter()
+        .cycle()
+        .take(6)
+        .map(|name| {
+            let path = fixtures.join(format!("{name}.toml"));
+            thread::spawn(move || (name, bnd_winmd::generate(&path)))
+        })
+        .collect();
+
+    for handle in handles {
+        let (name, result) = handle.join().expect("generation thread panicked");
+        let winmd = result.unwrap_or_else(|e| panic!("{name} failed: {e:#}"));
+        let types = type_names(winmd);
+        let (own, other) = match name {
+            "alpha" => (["AlphaOne", "AlphaTwo"], "BetaOne"),
+            _ => (["BetaOne", "BetaTwo"], "AlphaOne"),
+        };
+        for ty in own {
+            assert!(
+                types.iter().any(|t| t == ty),
+                "{name}: missing {ty} in {types:?}"
+            );
+        }
+        assert!(
+            !types.iter().any(|t| t == other),
+            "{name}: picked up {other} from the other config: {types:?}"
+        );
+    }
+}
@@ -0,0 +1,173 @@
+//! Integration test: a partition with `language = "c++"` parses headers that
+//! only compile as C++.
+
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use bnd_winmd::config::{Language, PartitionConfig};
+use windows_metadata::Type;
+
+fn fixture_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/cpp")
+}
+
+static CPP_WINMD: LazyLock<Vec<u8>> = LazyLock::new(|| {
+    bnd_winmd::generate(&fixture_dir().join("cpp.toml"))
+        .unwrap_or_else(|e| panic!("generate cpp winmd: {e:#}"))
+});
+
+fn open_index() -> windows_metadata::reader::TypeIndex {
+    let file = windows_metadata::reader::File::new(CPP_WINMD.clone()).expect("parse winmd");
+    windows_metadata::reader::TypeIndex::new(vec![file])
+}
+
+#[test]
+fn cpp_declarations_are_extracted() {
+    let index = open_index();
+
+    let point = index.expect("CppTest", "CppPoint");
+    let fields: Vec<&str> = point.fields().map(|f| f.name()).collect();
+    assert_eq!(fields, vec!["x", "y"]);
+
+    // Both live inside `extern "C"` blocks.
+    let apis = index.expect("CppTest", "Apis");
+    let sig = |name: &str| {
+        apis.methods()
+            .find(|m| m.name() == name)
+            .unwrap_or_else(|| panic!("{name} not found"))
+            .signature(&[])
+    };
+    let is_origin = sig("cpp_point_is_origin");
+    assert_eq!(is_origin.return_type, Type::Bool);
+    assert_eq!(
+        is_origin.types,
+        vec![Type::PtrMut(
+            Box::new(Type::named("CppTest", "CppPoint")),
+            1
+        )]
+    );
+    assert_eq!(
+        sig("cpp_point_sum").types,
+        vec![Type::named("CppTest", "CppPoint")]
+    );
+}
+
+#[test]
+fn scoped_enum_is_stored_as_its_base() {
+    let index = open_index();
+
+    let storage: Vec<Type> = index
+        .expect("CppTest", "CppLevel")
+        .fields()
+        .filter(|f| f.constant().is_none())
+        .map(|f| f.ty())
+        .collect();
+    assert_eq!(storage, vec![Type::U8]);
+}
+
+#[test]
+fn listed_namespaces_are_flattened() {
+    let index = open_index();
+
+    let point = index.expect("CppTest.Ns", "NsPoint");
+    let fields: Vec<&str> = point.fields().map(|f| f.name()).collect();
+    assert_eq!(fields, vec!["x", "y"]);
+
+    let apis = index.expect("CppTest.Ns", "Apis");
+    let symbols: Vec<(&str, String)> = apis
+        .methods()
+        .map(|m| {
+            let symbol = m.impl_map().expect("ImplMap").import_name().to_string();
+            (m.name(), symbol)
+        })
+        .collect();
+    let names: Vec<&str> = symbols.iter().map(|(name, _)| *name).collect();
+    assert_eq!(names, vec!["ns_point_sum", "ns_point_dot"]);
+
+    // `extern "C"` keeps the plain symbol; C++ linkage imports the mangled one.
+    assert_eq!(symbols[0].1, "ns_point_sum");
+    assert!(
+        symbols[1].1.starts_with("_Z") && symbols[1].1.contains("ns_point_dot"),
+        "ns_point_dot should import its mangled symbol, got {}",
+        symbols[1].1
+    );
+    assert_eq!(
+        apis.methods()
+            .find(|m| m.name() == "ns_point_dot")
+            .unwrap()
+            .signature(&[])
+            .types,
+        vec![
+            Type::PtrConst(Box::new(Type::named("CppTest.Ns", "NsPoint")), 1),
+            Type::PtrConst(Box::new(Type::named("CppTest.Ns", "NsPoint")), 1),
+        ]
+    );
+}
+
+#[test]
+fn same_headers_fail_as_c() {
+    let dir = std::env::temp_dir().join(format!("bnd_winmd_cpp_as_c_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let config = dir.join("as_c.toml");
+    let header = |name: &str| fixture_dir().join(name).display().to_string();
+    std::fs::write(
+        &config,
+        format!(
+            r#"
+[output]
+name = "CppAsC"
+
+[[partition]]
+namespace = "CppAsC"
+library = "cpptest"
+headers = ['{types}', '{api}']
+"#,
+            types = header("cpp_types.h"),
+            api = header("cpp_api.h"),
+        ),
+    )
+    .unwrap();
+
+    assert!(
+        bnd_winmd::generate(&config).is_err(),
+        "cpp_api.h must not parse as C"
+    );
+}
+
+#[test]
+fn language_selects_clang_flags_and_wrapper_extension() {
+    let partition: PartitionConfig = toml::from_str(
+        r#"
+namespace = "CppTest"
+library = "cpptest"
+language = "c++"
+std = "c++17"
+headers = ["a.h", "b.h"]
+clang_args = ["-DFOO"]
+"#,
+    )
+    .unwrap();
+    assert_eq!(partition.language, Language::Cpp);
+    let host_target = format!("--target={}", bnd_winmd::config::host_triple());
+    assert_eq!(
+        partition.clang_args(),
+        vec!["-x", "c++-header", "-std=c++17", "-DFOO", &host_target]
+    );
+    let (wrapper, _) = partition.translation_unit(Path::new("/tmp"), &[]);
+    assert_eq!(wrapper.extension().unwrap(), "cpp");
+
+    // C is the default, and an explicit `-x` in `clang_args` wins.
+    let partition: PartitionConfig = toml::from_str(
+        r#"
+namespace = "CTest"
+library = "ctest"
+headers = ["a.h", "b.h"]
+clang_args = ["-x", "c"]
+"#,
+    )
+    .unwrap();
+    assert_eq!(partition.language, Language::C);
+    assert_eq!(partition.clang_args(), vec!["-x", "c", &host_target]);
+    let (wrapper, _) = partition.translation_unit(Path::new("/tmp"), &[]);
+    assert_eq!(wrapper.extension().unwrap(), "c");
+}
@@ -0,0 +1,81 @@
+//! Round-trip integration test: a partition's `defines` reach the parse as
+//! `-D` flags, ahead of `clang_args`.
+
+use std::path::{Path, PathBuf};
+
+use windows_metadata::Type;
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../tests/fixtures/defines")
+        .join(name)
+}
+
+/// Fields and size of `Record` as generated from `config`.
+fn record(config: &str) -> (Vec<(String, Type)>, u32) {
+    let winmd = bnd_winmd::generate(&fixture(config)).expect("generate defines winmd");
+    let file = windows_metadata::reader::File::new(winmd).expect("parse winmd");
+    let index = windows_metadata::reader::TypeIndex::new(vec![file]);
+    let record = index.expect("DefinesTest", "Record");
+    let fields = record
+        .fields()
+        .map(|f| (f.name().to_string(), f.ty()))
+        .collect();
+    let size = record.class_layout().expect("Record layout").class_size();
+    (fields, size)
+}
+
+#[test]
+fn defines_change_the_struct_layout() {
+    let (fields, size) = record("narrow.toml");
+    assert_eq!(
+        fields,
+        [
+            ("offset".to_string(), Type::I32),
+            ("flags".to_string(), Type::I32)
+        ]
+    );
+    assert_eq!(size, 8);
+
+    let (fields, size) = record("wide.toml");
+    assert_eq!(
+        fields,
+        [
+            ("offset".to_string(), Type::I64),
+            ("flags".to_string(), Type::I32),
+            ("tag".to_string(), Type::I32)
+        ]
+    );
+    assert_eq!(size, 16);
+}
+
+#[test]
+fn defines_precede_clang_args_in_name_order() {
+    let mut cfg = bnd_winmd::config::load_config(&fixture("wide.toml")).expect("load config");
+    let partition = &mut cfg.partition[0];
+    partition.clang_args.push("-URECORD_TAGGED".to_string());
+    partition.defines.insert(
+        "RECORD_NAME".to_string(),
+        bnd_winmd::config::Define::Text("rec".to_string()),
+    );
+    partition
+        .defines
+        .insert("NDEBUG".to_string(), bnd_winmd::config::Define::Flag(false));
+
+    let mut args = partition.clang_args();
+    assert_eq!(
+        args.pop(),
+        Some(format!("--target={}", bnd_winmd::config::host_triple()))
+    );
+    let start = args.iter().position(|a| a.starts_with("-U")).unwrap();
+    assert_eq!(
+        args[start..],
+        [
+            "-UNDEBUG",
+            "-DRECORD_NAME=rec",
+            "-DRECORD_OFFSET_BITS=64",
+            "-DRECORD_TAGGED",
+            "-URECORD_TAGGED"
+        ]
+    );
+}
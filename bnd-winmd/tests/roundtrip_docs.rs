@@ -0,0 +1,88 @@
+//! Round-trip integration test: C doc comments survive as
+//! `DocumentationAttribute`s when `emit_docs = true`.
+
+use std::path::Path;
+use std::sync::LazyLock;
+
+use windows_metadata::{HasAttributes, Value};
+
+static DOCS_WINMD: LazyLock<Vec<u8>> = LazyLock::new(|| {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/docs/docs.toml");
+    bnd_winmd::generate(&path).expect("generate docs winmd")
+});
+
+fn open_index() -> windows_metadata::reader::TypeIndex {
+    let file = windows_metadata::reader::File::new(DOCS_WINMD.clone()).expect("parse winmd");
+    windows_metadata::reader::TypeIndex::new(vec![file])
+}
+
+fn doc<'a>(row: &impl HasAttributes<'a>) -> Option<String> {
+    let attr = row.find_attribute("DocumentationAttribute")?;
+    match attr.value().first() {
+        Some((_, Value::Utf8(text))) => Some(text.clone()),
+        other => panic!("unexpected DocumentationAttribute value: {other:?}"),
+    }
+}
+
+#[test]
+fn function_doc_comment_round_trips() {
+    let index = open_index();
+    let apis = index.expect("DocsTest", "Apis");
+
+    let connect = apis.methods().find(|m| m.name() == "doc_connect").unwrap();
+    assert_eq!(
+        doc(&connect).as_deref(),
+        Some("Open a connection using `settings`.\nReturns DOC_OK on success.")
+    );
+
+    let close = apis.methods().find(|m| m.name() == "doc_close").unwrap();
+    assert_eq!(doc(&close), None, "plain `//` comments are not docs");
+}
+
+#[test]
+fn type_doc_comments_round_trip() {
+    let index = open_index();
+
+    let settings = index.expect("DocsTest", "DocSettings");
+    assert_eq!(
+        doc(&settings).as_deref(),
+        Some("Connection settings.\n\nPassed by pointer to doc_connect.")
+    );
+
+    let status = index.expect("DocsTest", "DocStatus");
+    assert_eq!(
+        doc(&status).as_deref(),
+        Some("Result of a connection attempt.")
+    );
+}
+
+#[test]
+fn define_comments_round_trip() {
+    let index = open_index();
+    let apis = index.expect("DocsTest", "Apis");
+    let constant_doc = |name: &str| {
+        let field = apis
+            .fields()
+            .find(|f| f.name() == name)
+            .unwrap_or_else(|| panic!("{name} not found"));
+        doc(&field)
+    };
+
+    assert_eq!(
+        constant_doc("DOC_DEFAULT_PORT").as_deref(),
+        Some("Default port for doc_connect.\n\nOverride it in DocSettings.")
+    );
+    assert_eq!(
+        constant_doc("DOC_MAX_RETRIES").as_deref(),
+        Some("Upper bound for DocSettings.retries.")
+    );
+    assert_eq!(
+        constant_doc("DOC_FLAG_FAST").as_deref(),
+        Some("skip the handshake")
+    );
+    assert_eq!(
+        constant_doc("DOC_UNDOCUMENTED"),
+        None,
+        "plain `//` comments above a macro are not docs"
+    );
+}
@@ -0,0 +1,81 @@
+//! Integration test: `dump_model` serializes the extracted model as JSON.
+
+use std::path::Path;
+
+use serde_json::{Value, json};
+
+fn dump_json(fixture: &str) -> String {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../tests/fixtures")
+        .join(fixture);
+    let cfg = bnd_winmd::config::load_config(&path).expect("load config");
+    bnd_winmd::dump_model(&cfg, path.parent().unwrap()).expect("dump model")
+}
+
+fn dump(fixture: &str) -> Value {
+    serde_json::from_str(&dump_json(fixture)).expect("dump is valid JSON")
+}
+
+#[test]
+fn dump_lists_structs_functions_and_filtered_symbols() {
+    let model = dump("filter/filter.toml");
+    let partition = &model["partitions"][0];
+    assert_eq!(model["registry"]["types"]["LibContext"], "FilterTest");
+    assert_eq!(partition["namespace"], "FilterTest");
+
+    let ctx = partition["structs"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|s| s["name"] == "LibContext")
+        .expect("LibContext in dump");
+    assert_eq!(ctx["size"], 4);
+    assert_eq!(ctx["fields"][0]["name"], "handle");
+    assert_eq!(ctx["fields"][0]["ty"], "I32");
+
+    let lib_open = partition["functions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|f| f["name"] == "lib_open")
+        .expect("lib_open in dump");
+    assert_eq!(
+        lib_open["params"][0]["ty"],
+        json!({ "Ptr": { "pointee": { "Named": { "name": "LibContext", "resolved": null } }, "is_const": false } })
+    );
+
+    let filtered = partition["filtered"].as_array().unwrap();
+    for name in [
+        "OtherContext",
+        "other_open",
+        "lib_reset_internal",
+        "OTHER_VERSION",
+    ] {
+        assert!(
+            filtered.contains(&json!(name)),
+            "{name} not in {filtered:?}"
+        );
+    }
+}
+
+#[test]
+fn dump_re_emits_the_same_winmd() {
+    for (fixture, assembly) in [
+        ("simple/simple.toml", "SimpleTest"),
+        ("multi/multi.toml", "MultiTest"),
+    ] {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../tests/fixtures")
+            .join(fixture);
+        let direct = bnd_winmd::generate(&path).expect("generate winmd");
+        let from_json = bnd_winmd::emit_from_model_json(&dump_json(fixture), assembly)
+            .expect("emit from model JSON");
+        assert!(direct == from_json, "{fixture}: winmd differs via JSON");
+    }
+}
+
+#[test]
+fn malformed_model_json_is_an_error() {
+    let err = bnd_winmd::emit_from_model_json("[]", "Broken").expect_err("not a model");
+    assert!(format!("{err:#}").contains("parsing model JSON"), "{err:#}");
+}
@@ -0,0 +1,87 @@
+//! Round-trip integration test: enums referenced from another partition
+//! resolve to a TypeRef into the namespace that defines them, and fall back
+//! to their integer type when no partition emits them.
+
+use std::path::Path;
+use std::sync::LazyLock;
+
+use windows_metadata::Type;
+use windows_metadata::reader::TypeCategory;
+
+static ENUMREFS_WINMD: LazyLock<Vec<u8>> = LazyLock::new(|| {
+    let path =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/enumrefs/enumrefs.toml");
+    bnd_winmd::generate(&path).expect("generate enumrefs winmd")
+});
+
+fn open_index() -> windows_metadata::reader::TypeIndex {
+    let file = windows_metadata::reader::File::new(ENUMREFS_WINMD.clone()).expect("parse winmd");
+    windows_metadata::reader::TypeIndex::new(vec![file])
+}
+
+/// Namespaces that define a type called `name`.
+fn defining_namespaces(index: &windows_metadata::reader::TypeIndex, name: &str) -> Vec<String> {
+    index
+        .types()
+        .filter(|td| td.name() == name)
+        .map(|td| td.namespace().to_string())
+        .collect()
+}
+
+fn param_types(index: &windows_metadata::reader::TypeIndex, function: &str) -> Vec<Type> {
+    index
+        .expect("EnumRefs.Api", "Apis")
+        .methods()
+        .find(|m| m.name() == function)
+        .unwrap_or_else(|| panic!("{function} missing"))
+        .signature(&[])
+        .types
+}
+
+/// The TypeRef in `ty` must name an enum the reader can find.
+fn assert_resolves_to_enum(index: &windows_metadata::reader::TypeIndex, ty: &Type) {
+    let Type::Name(name) = ty else {
+        panic!("expected a named type, got {ty:?}");
+    };
+    let td = index
+        .get(&name.namespace, &name.name)
+        .next()
+        .unwrap_or_else(|| panic!("{}.{} does not resolve", name.namespace, name.name));
+    assert_eq!(td.category(), TypeCategory::Enum);
+}
+
+#[test]
+fn cross_partition_enum_params_resolve() {
+    let index = open_index();
+    let level = param_types(&index, "set_level");
+    assert_eq!(level, vec![Type::named("EnumRefs.Types", "Level")]);
+    assert_resolves_to_enum(&index, &level[0]);
+
+    let mode = param_types(&index, "set_mode");
+    assert_resolves_to_enum(&index, &mode[0]);
+}
+
+#[test]
+fn cross_partition_enum_fields_resolve() {
+    let index = open_index();
+    let settings = index.expect("EnumRefs.Api", "Settings");
+    for field in settings.fields() {
+        assert_resolves_to_enum(&index, &field.ty());
+    }
+}
+
+#[test]
+fn enum_shared_by_two_partitions_is_emitted_once() {
+    let index = open_index();
+    let namespaces = defining_namespaces(&index, "Mode");
+    assert_eq!(namespaces.len(), 1, "Mode defined in {namespaces:?}");
+    let mode = &param_types(&index, "set_mode")[0];
+    assert_eq!(mode, &Type::named(&namespaces[0], "Mode"));
+}
+
+#[test]
+fn filtered_enum_falls_back_to_its_integer_type() {
+    let index = open_index();
+    assert!(defining_namespaces(&index, "Hidden").is_empty());
+    assert_eq!(param_types(&index, "set_hidden"), vec![Type::U32]);
+}
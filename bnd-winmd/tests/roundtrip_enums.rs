@@ -0,0 +1,253 @@
+//! Round-trip integration test: enum extraction and emission from enums.h.
+
+use std::path::Path;
+use std::sync::LazyLock;
+
+use windows_metadata::{HasAttributes, Type, Value};
+
+static ENUMS_WINMD: LazyLock<Vec<u8>> = LazyLock::new(|| {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/enums/enums.toml");
+    bnd_winmd::generate(&path).expect("generate enums winmd")
+});
+
+fn open_index() -> windows_metadata::reader::TypeIndex {
+    let file = windows_metadata::reader::File::new(ENUMS_WINMD.clone()).expect("parse winmd");
+    windows_metadata::reader::TypeIndex::new(vec![file])
+}
+
+fn is_flags(index: &windows_metadata::reader::TypeIndex, name: &str) -> bool {
+    index
+        .expect("EnumsTest", name)
+        .has_attribute("FlagsAttribute")
+}
+
+#[test]
+fn sequential_enum_is_not_flags() {
+    let index = open_index();
+    assert!(!is_flags(&index, "Color"), "Color must not be a flags enum");
+}
+
+#[test]
+fn power_of_two_enum_is_flags() {
+    let index = open_index();
+    assert!(is_flags(&index, "Perms"), "Perms should be a flags enum");
+}
+
+#[test]
+fn config_overrides_flags_heuristic() {
+    let index = open_index();
+    assert!(
+        !is_flags(&index, "Channel"),
+        "Channel is listed in plain_enums"
+    );
+    assert!(
+        is_flags(&index, "Single"),
+        "Single is listed in flags_enums"
+    );
+}
+
+/// The `value__` type and the variant values of enum `name`.
+fn enum_fields(
+    index: &windows_metadata::reader::TypeIndex,
+    name: &str,
+) -> (Type, Vec<(String, Value)>) {
+    let mut storage = None;
+    let mut variants = Vec::new();
+    for field in index.expect("EnumsTest", name).fields() {
+        match field.constant() {
+            Some(constant) => variants.push((field.name().to_string(), constant.value())),
+            None => storage = Some(field.ty()),
+        }
+    }
+    (storage.expect("value__ field"), variants)
+}
+
+#[test]
+fn unsigned_enum_keeps_unsigned_values() {
+    let index = open_index();
+    let (storage, variants) = enum_fields(&index, "HighBit");
+    assert_eq!(storage, Type::U32);
+    assert_eq!(
+        variants,
+        vec![
+            ("HIGH_BIT_CLEAR".to_string(), Value::U32(0)),
+            ("HIGH_BIT_SET".to_string(), Value::U32(0x8000_0000)),
+        ]
+    );
+}
+
+#[test]
+fn signed_enum_keeps_signed_values() {
+    let index = open_index();
+    let (storage, variants) = enum_fields(&index, "Wide");
+    assert_eq!(storage, Type::I64);
+    assert_eq!(
+        variants,
+        vec![
+            ("WIDE_NEG".to_string(), Value::I64(-1)),
+            ("WIDE_BIG".to_string(), Value::I64(0x1_0000_0000)),
+        ]
+    );
+}
+
+#[test]
+fn fixed_base_through_typedef_sets_storage() {
+    let index = open_index();
+    let (storage, variants) = enum_fields(&index, "Big");
+    assert_eq!(storage, Type::U64);
+    assert_eq!(
+        variants,
+        vec![
+            ("BIG_ONE".to_string(), Value::U64(1)),
+            ("BIG_TWO".to_string(), Value::U64(2)),
+        ]
+    );
+}
+
+/// Size of `TinyPacket` and the signature of `tiny_next`, with the
+/// `NativeTypeNameAttribute` of its parameter.
+fn tiny_usage(
+    index: &windows_metadata::reader::TypeIndex,
+) -> (u32, Type, Vec<Type>, Option<String>) {
+    let packet = index.expect("EnumsTest", "TinyPacket");
+    let size = packet
+        .class_layout()
+        .expect("TinyPacket layout")
+        .class_size();
+    let method = index
+        .expect("EnumsTest", "Apis")
+        .methods()
+        .find(|m| m.name() == "tiny_next")
+        .expect("tiny_next");
+    let sig = method.signature(&[]);
+    let native = method
+        .params()
+        .find(|p| p.sequence() == 1)
+        .and_then(|p| p.find_attribute("NativeTypeNameAttribute"))
+        .map(|attr| match attr.value().as_slice() {
+            [(_, Value::Utf8(name))] => name.clone(),
+            other => panic!("unexpected NativeTypeNameAttribute value: {other:?}"),
+        });
+    (size, sig.return_type, sig.types, native)
+}
+
+#[test]
+fn enum_repr_int_passes_char_sized_enums_as_int() {
+    let index = open_index();
+    let tiny = Type::named("EnumsTest", "Tiny");
+    assert_eq!(enum_fields(&index, "Tiny").0, Type::U8);
+    assert_eq!(tiny_usage(&index), (2, tiny.clone(), vec![tiny], None));
+
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/enums/enums.toml");
+    let mut cfg = bnd_winmd::config::load_config(&path).expect("load config");
+    cfg.partition[0].enum_repr = bnd_winmd::config::EnumRepr::Int;
+    let winmd = bnd_winmd::generate_from_config(&cfg, path.parent().unwrap())
+        .expect("generate enums winmd");
+    let file = windows_metadata::reader::File::new(winmd).expect("parse winmd");
+    let index = windows_metadata::reader::TypeIndex::new(vec![file]);
+
+    // The enum and the struct holding it keep their C layout.
+    let (storage, variants) = enum_fields(&index, "Tiny");
+    assert_eq!(storage, Type::U8);
+    assert_eq!(
+        variants,
+        vec![
+            ("TINY_LOW".to_string(), Value::U8(1)),
+            ("TINY_HIGH".to_string(), Value::U8(200)),
+        ]
+    );
+    assert_eq!(
+        tiny_usage(&index),
+        (2, Type::I32, vec![Type::I32], Some("Tiny".to_string()))
+    );
+    let size_attr = index
+        .expect("EnumsTest", "TinyPacket")
+        .find_attribute("NativeStructSizeAttribute")
+        .expect("NativeStructSizeAttribute");
+    assert!(
+        matches!(
+            size_attr.value().as_slice(),
+            [(_, Value::I64(2)), (_, Value::I64(1))]
+        ),
+        "{:?}",
+        size_attr.value()
+    );
+}
+
+#[test]
+fn anonymous_enum_variants_are_constants() {
+    let index = open_index();
+    let constants: Vec<(String, Value)> = index
+        .expect("EnumsTest", "Apis")
+        .fields()
+        .map(|f| {
+            let value = f.constant().expect("constant value").value();
+            (f.name().to_string(), value)
+        })
+        .collect();
+    let value = |name: &str| {
+        constants
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v)
+            .unwrap_or_else(|| panic!("{name} missing from {constants:?}"))
+    };
+
+    // Each keeps the type clang chose for its enum.
+    assert_eq!(value("BUF_SMALL"), &Value::U32(64));
+    assert_eq!(value("BUF_LARGE"), &Value::U32(4096));
+    assert_eq!(value("STATUS_ERROR"), &Value::I32(-1));
+    assert_eq!(value("STATUS_OK"), &Value::I32(0));
+    assert_eq!(value("MASK_HIGH"), &Value::U64(0x1_0000_0000));
+}
+
+#[test]
+fn value_aliases_are_distinct_fields() {
+    let index = open_index();
+    let (_, variants) = enum_fields(&index, "Whence");
+    assert_eq!(
+        variants,
+        vec![
+            ("WHENCE_SET".to_string(), Value::U32(0)),
+            ("WHENCE_CUR".to_string(), Value::U32(1)),
+            ("WHENCE_END".to_string(), Value::U32(2)),
+            ("WHENCE_DEFAULT".to_string(), Value::U32(0)),
+        ]
+    );
+}
+
+#[test]
+fn computed_variants_keep_their_folded_values() {
+    let index = open_index();
+    let (storage, variants) = enum_fields(&index, "Step");
+    assert_eq!(storage, Type::I32);
+    assert_eq!(
+        variants,
+        vec![
+            ("STEP_BASE".to_string(), Value::I32(-8)),
+            ("STEP_NEXT".to_string(), Value::I32(-7)),
+            ("STEP_FAR".to_string(), Value::I32(2)),
+            ("STEP_AFTER".to_string(), Value::I32(3)),
+            ("STEP_SHIFTED".to_string(), Value::I32(16)),
+            ("STEP_MASK".to_string(), Value::I32(19)),
+            ("STEP_NEG".to_string(), Value::I32(-19)),
+        ]
+    );
+    assert!(!is_flags(&index, "Step"));
+}
+
+#[test]
+fn same_name_variants_in_one_namespace_are_an_error() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/enums/clash.toml");
+    let err = format!(
+        "{:#}",
+        bnd_winmd::generate(&path).expect_err("MODE_DEFAULT is declared twice")
+    );
+    assert!(
+        err.contains("MODE_DEFAULT")
+            && err.contains("`Level`")
+            && err.contains("`Mode`")
+            && err.contains("ClashTest.A"),
+        "error should name the variant, both enums and the namespace, got:\n{err}"
+    );
+}
@@ -0,0 +1,145 @@
+//! Round-trip integration test: `extern` global variables become static
+//! fields of the `Globals` class, each naming its library and symbol in a
+//! `DataImportAttribute`.
+
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use bnd_winmd::emit::GLOBALS_TYPE;
+use windows_metadata::{HasAttributes, Type, Value};
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../tests/fixtures/globals")
+        .join(name)
+}
+
+static VARS_WINMD: LazyLock<Vec<u8>> =
+    LazyLock::new(|| bnd_winmd::generate(&fixture("vars.toml")).expect("generate vars winmd"));
+
+fn open_index(winmd: Vec<u8>) -> windows_metadata::reader::TypeIndex {
+    let file = windows_metadata::reader::File::new(winmd).expect("parse winmd");
+    windows_metadata::reader::TypeIndex::new(vec![file])
+}
+
+/// Type and `DataImportAttribute` `(library, symbol)` of each global in
+/// `namespace`, in declaration order, plus whether it is marked const.
+fn globals(winmd: Vec<u8>, namespace: &str) -> Vec<(String, Type, (String, String), bool)> {
+    let index = open_index(winmd);
+    index
+        .expect(namespace, GLOBALS_TYPE)
+        .fields()
+        .map(|field| {
+            let import = match field
+                .find_attribute("DataImportAttribute")
+                .expect("DataImportAttribute")
+                .value()
+                .as_slice()
+            {
+                [(_, Value::Utf8(library)), (_, Value::Utf8(symbol))] => {
+                    (library.clone(), symbol.clone())
+                }
+                other => panic!("unexpected DataImportAttribute value: {other:?}"),
+            };
+            (
+                field.name().to_string(),
+                field.ty(),
+                import,
+                field.has_attribute("ConstAttribute"),
+            )
+        })
+        .collect()
+}
+
+fn import(symbol: &str) -> (String, String) {
+    ("vars".to_string(), symbol.to_string())
+}
+
+#[test]
+fn extern_variables_are_static_fields() {
+    assert_eq!(
+        globals(VARS_WINMD.clone(), "VarsTest"),
+        vec![
+            ("optind".to_string(), Type::I32, import("optind"), false),
+            (
+                "optarg".to_string(),
+                Type::PtrMut(Box::new(Type::I8), 1),
+                import("optarg"),
+                false
+            ),
+            (
+                "version_major".to_string(),
+                Type::I32,
+                import("version_major"),
+                true
+            ),
+            (
+                "io_default".to_string(),
+                Type::named("VarsTest", "io_state"),
+                import("io_default"),
+                false
+            ),
+        ]
+    );
+}
+
+#[test]
+fn globals_stay_off_apis() {
+    let index = open_index(VARS_WINMD.clone());
+    assert!(!index.contains("VarsTest", "Apis"));
+}
+
+#[test]
+fn variables_are_in_the_model() {
+    let cfg = bnd_winmd::config::load_config(&fixture("vars.toml")).expect("load config");
+    let json = bnd_winmd::dump_model(&cfg, &fixture("")).expect("dump model");
+    let model: bnd_winmd::model::Model = serde_json::from_str(&json).expect("parse model");
+    let partition = &model.partitions[0];
+    let names: Vec<&str> = partition
+        .variables
+        .iter()
+        .map(|g| g.name.as_str())
+        .collect();
+    assert_eq!(names, ["optind", "optarg", "version_major", "io_default"]);
+    assert_eq!(
+        partition.variables[0].doc.as_deref(),
+        Some("Index of the next argument to process.")
+    );
+}
+
+/// getopt's `optind` from the real glibc headers, resolved the way a
+/// consumer of the winmd would: look up the symbol its
+/// `DataImportAttribute` names, in the library it names. That is libc's
+/// `optind`, holding its initial value of 1.
+#[cfg(all(target_os = "linux", target_env = "gnu", target_arch = "x86_64"))]
+#[test]
+fn system_optind_is_read_from_libc() {
+    use std::ffi::{CString, c_char, c_int, c_void};
+
+    unsafe extern "C" {
+        fn dlopen(filename: *const c_char, flags: c_int) -> *mut c_void;
+        fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+        static optind: c_int;
+    }
+    const RTLD_NOW: c_int = 2;
+
+    let winmd = bnd_winmd::generate(&fixture("getopt.toml")).expect("generate getopt winmd");
+    let globals = globals(winmd, "posix.getopt");
+    let (_, ty, (library, symbol), _) = globals
+        .iter()
+        .find(|(name, ..)| name == "optind")
+        .expect("optind");
+    assert_eq!(*ty, Type::I32);
+    assert_eq!((library.as_str(), symbol.as_str()), ("c", "optind"));
+
+    let library = CString::new(format!("lib{library}.so.6")).unwrap();
+    let symbol = CString::new(symbol.as_str()).unwrap();
+    unsafe {
+        let handle = dlopen(library.as_ptr(), RTLD_NOW);
+        assert!(!handle.is_null(), "dlopen {library:?}");
+        let address = dlsym(handle, symbol.as_ptr()).cast::<c_int>();
+        assert!(!address.is_null(), "dlsym {symbol:?}");
+        assert_eq!(address.cast_const(), &raw const optind);
+        assert_eq!(*address, 1);
+    }
+}
@@ -0,0 +1,57 @@
+//! Integration test: `extract_header` turns one header into a model
+//! partition without a config file.
+
+use std::path::{Path, PathBuf};
+
+use bnd_winmd::model::Partition;
+
+fn simple_header() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/simple/simple.h")
+}
+
+fn simple() -> Partition {
+    bnd_winmd::extract_header(&simple_header(), &[]).expect("extract simple.h")
+}
+
+#[test]
+fn header_declarations_are_extracted() {
+    let partition = simple();
+    assert_eq!(partition.namespace, "simple");
+    assert_eq!(partition.library, "simple");
+
+    let mut structs: Vec<&str> = partition.structs.iter().map(|s| s.name.as_str()).collect();
+    structs.sort();
+    assert_eq!(
+        structs,
+        [
+            "NetAddr",
+            "NetAddr_addr",
+            "PackedHeader",
+            "Rect",
+            "Value",
+            "Widget"
+        ]
+    );
+    let functions: Vec<&str> = partition
+        .functions
+        .iter()
+        .map(|f| f.name.as_str())
+        .collect();
+    assert_eq!(
+        functions,
+        ["create_widget", "destroy_widget", "widget_count"]
+    );
+    assert_eq!(partition.enums.len(), 1);
+    assert_eq!(partition.constants.len(), 3);
+}
+
+#[test]
+fn clang_args_reach_the_parse() {
+    let args = ["-include".to_string(), "no_such_prelude.h".to_string()];
+    let err =
+        bnd_winmd::extract_header(&simple_header(), &args).expect_err("prelude does not exist");
+    assert!(
+        format!("{err:#}").contains("no_such_prelude.h"),
+        "error should name the missing include, got:\n{err:#}"
+    );
+}
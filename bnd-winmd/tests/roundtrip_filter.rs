@@ -0,0 +1,56 @@
+//! Round-trip integration test: per-partition symbol allowlist/blocklist.
+
+use std::path::Path;
+use std::sync::LazyLock;
+
+static FILTER_WINMD: LazyLock<Vec<u8>> = LazyLock::new(|| {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/filter/filter.toml");
+    bnd_winmd::generate(&path).expect("generate filter winmd")
+});
+
+fn open_index() -> windows_metadata::reader::TypeIndex {
+    let file = windows_metadata::reader::File::new(FILTER_WINMD.clone()).expect("parse winmd");
+    windows_metadata::reader::TypeIndex::new(vec![file])
+}
+
+#[test]
+fn include_symbols_keeps_only_matching_types() {
+    let index = open_index();
+
+    let types: Vec<String> = index.types().map(|td| td.name().to_string()).collect();
+    assert!(
+        types.contains(&"LibContext".to_string()),
+        "types: {types:?}"
+    );
+    assert!(types.contains(&"LibMode".to_string()), "types: {types:?}");
+    assert!(
+        !types.contains(&"OtherContext".to_string()),
+        "OtherContext is not in include_symbols. Types: {types:?}"
+    );
+}
+
+#[test]
+fn exclude_symbols_wins_over_include_symbols() {
+    let index = open_index();
+
+    let apis = index.expect("FilterTest", "Apis");
+    let mut methods: Vec<String> = apis.methods().map(|m| m.name().to_string()).collect();
+    methods.sort();
+    assert_eq!(methods, vec!["lib_close", "lib_open"]);
+}
+
+#[test]
+fn constants_are_filtered_by_name() {
+    let index = open_index();
+
+    let apis = index.expect("FilterTest", "Apis");
+    let fields: Vec<String> = apis.fields().map(|f| f.name().to_string()).collect();
+    assert_eq!(fields, vec!["LIB_VERSION"]);
+}
+
+#[test]
+fn invalid_pattern_is_reported() {
+    let err = bnd_winmd::config::SymbolFilter::new(&["lib_(".to_string()], &[])
+        .expect_err("unbalanced group should fail to compile");
+    assert!(format!("{err:#}").contains("lib_("), "got: {err:#}");
+}
@@ -0,0 +1,65 @@
+//! Integration test: `generate_filtered` keeps only the partitions under the
+//! given namespace prefixes of the multi fixture.
+
+use std::path::{Path, PathBuf};
+
+fn multi_config() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/multi/multi.toml")
+}
+
+/// `(namespace, name)` of every type in the winmd, `<Module>` excluded.
+fn types(winmd: Vec<u8>) -> Vec<(String, String)> {
+    let file = windows_metadata::reader::File::new(winmd).expect("parse winmd");
+    let index = windows_metadata::reader::TypeIndex::new(vec![file]);
+    index
+        .types()
+        .filter(|td| !td.namespace().is_empty())
+        .map(|td| (td.namespace().to_string(), td.name().to_string()))
+        .collect()
+}
+
+#[test]
+fn filter_keeps_one_namespace() {
+    let winmd = bnd_winmd::generate_filtered(&multi_config(), &["MultiTest.Types"])
+        .expect("generate filtered winmd");
+    let types = types(winmd);
+
+    for name in ["Color", "Rect", "CompareFunc", "Apis"] {
+        assert!(
+            types.contains(&("MultiTest.Types".to_string(), name.to_string())),
+            "MultiTest.Types.{name} missing from {types:?}"
+        );
+    }
+    assert!(
+        types.iter().all(|(ns, _)| ns == "MultiTest.Types"),
+        "only MultiTest.Types should be emitted, got {types:?}"
+    );
+}
+
+#[test]
+fn filter_prefix_keeps_nested_namespaces() {
+    let winmd = bnd_winmd::generate_filtered(&multi_config(), &["MultiTest"])
+        .expect("generate filtered winmd");
+    let types = types(winmd);
+
+    assert!(types.contains(&("MultiTest.Types".to_string(), "Rect".to_string())));
+    assert!(types.contains(&("MultiTest.Widgets".to_string(), "Widget".to_string())));
+}
+
+#[test]
+fn reference_into_excluded_namespace_is_reported() {
+    let err = format!(
+        "{:#}",
+        bnd_winmd::generate_filtered(&multi_config(), &["MultiTest.Widgets"])
+            .expect_err("Widgets references Rect from the excluded Types partition")
+    );
+
+    assert!(
+        err.contains("`Rect`") && err.contains("create_widget"),
+        "error should name the type and its use, got:\n{err}"
+    );
+    assert!(
+        err.contains("MultiTest.Types") && err.contains("namespace filter"),
+        "error should point at the excluded namespace, got:\n{err}"
+    );
+}
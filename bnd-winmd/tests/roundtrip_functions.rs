@@ -0,0 +1,267 @@
+//! Round-trip integration test: function signatures from functions.h.
+
+use std::path::Path;
+use std::sync::LazyLock;
+
+use windows_metadata::reader::TypeCategory;
+use windows_metadata::{
+    HasAttributes, MethodCallAttributes, PInvokeAttributes, ParamAttributes, Type, TypeAttributes,
+    Value,
+};
+
+static FUNCTIONS_WINMD: LazyLock<Vec<u8>> = LazyLock::new(|| {
+    let path =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/functions/functions.toml");
+    bnd_winmd::generate(&path).expect("generate functions winmd")
+});
+
+fn open_index() -> windows_metadata::reader::TypeIndex {
+    let file = windows_metadata::reader::File::new(FUNCTIONS_WINMD.clone()).expect("parse winmd");
+    windows_metadata::reader::TypeIndex::new(vec![file])
+}
+
+fn method<'a>(
+    index: &'a windows_metadata::reader::TypeIndex,
+    name: &str,
+) -> windows_metadata::reader::MethodDef<'a> {
+    index
+        .expect("FunctionsTest", "Apis")
+        .methods()
+        .find(|m| m.name() == name)
+        .unwrap_or_else(|| panic!("{name} not found"))
+}
+
+#[test]
+fn variadic_function_keeps_vararg_flag() {
+    let index = open_index();
+
+    let log_message = method(&index, "log_message");
+    let sig = log_message.signature(&[]);
+    assert!(
+        sig.flags.contains(MethodCallAttributes::VARARG),
+        "log_message should carry the VARARG calling convention"
+    );
+    assert_eq!(sig.types.len(), 2, "fixed params should be preserved");
+
+    let params: Vec<String> = log_message.params().map(|p| p.name().to_string()).collect();
+    assert_eq!(params, vec!["level", "fmt"]);
+}
+
+#[test]
+fn non_variadic_function_has_default_flags() {
+    let index = open_index();
+
+    let sig = method(&index, "log_flush").signature(&[]);
+    assert!(!sig.flags.contains(MethodCallAttributes::VARARG));
+}
+
+#[test]
+fn deprecated_function_is_obsolete() {
+    let index = open_index();
+
+    let log_open = method(&index, "log_open");
+    let obsolete = log_open
+        .find_attribute("ObsoleteAttribute")
+        .expect("log_open should carry ObsoleteAttribute");
+    assert_eq!(
+        obsolete.value(),
+        vec![(String::new(), Value::Utf8("use log_open_ex".to_string()))]
+    );
+
+    let log_reset = method(&index, "log_reset");
+    let obsolete = log_reset
+        .find_attribute("ObsoleteAttribute")
+        .expect("log_reset should carry ObsoleteAttribute");
+    assert!(obsolete.value().is_empty());
+
+    assert!(!method(&index, "log_flush").has_attribute("ObsoleteAttribute"));
+}
+
+#[test]
+fn deprecated_struct_is_obsolete() {
+    let index = open_index();
+    let config = index.expect("FunctionsTest", "LogConfig");
+    assert!(config.has_attribute("ObsoleteAttribute"));
+}
+
+/// Returns `(name, flags, has ConstAttribute)` for each param of `name`.
+fn param_attrs(
+    index: &windows_metadata::reader::TypeIndex,
+    name: &str,
+) -> Vec<(String, ParamAttributes, bool)> {
+    method(index, name)
+        .params()
+        .map(|p| {
+            (
+                p.name().to_string(),
+                p.flags(),
+                p.has_attribute("ConstAttribute"),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn pointer_params_follow_pointee_constness() {
+    let index = open_index();
+
+    let in_out = ParamAttributes::In | ParamAttributes::Out;
+    assert_eq!(
+        param_attrs(&index, "fill_buffer"),
+        vec![
+            ("dst".to_string(), in_out, false),
+            ("src".to_string(), ParamAttributes::In, true),
+            // `char *const`: the pointer is const, the data is not
+            ("fixed".to_string(), in_out, false),
+        ]
+    );
+}
+
+#[test]
+fn pointer_to_pointer_params() {
+    let index = open_index();
+
+    let in_out = ParamAttributes::In | ParamAttributes::Out;
+    assert_eq!(
+        param_attrs(&index, "copy_strings"),
+        vec![
+            ("out".to_string(), in_out, false),
+            // `const char **`: the pointee is a mutable `const char *`
+            ("names".to_string(), in_out, false),
+            // `char *const *`: the pointee pointer is const
+            ("argv".to_string(), ParamAttributes::In, true),
+        ]
+    );
+}
+
+#[test]
+fn trailing_output_param_is_out_only() {
+    let index = open_index();
+
+    assert_eq!(
+        param_attrs(&index, "read_count"),
+        vec![
+            ("path".to_string(), ParamAttributes::In, true),
+            ("out_count".to_string(), ParamAttributes::Out, false),
+        ]
+    );
+    // No error code to return, so the pointer may be read too.
+    assert_eq!(
+        param_attrs(&index, "read_into"),
+        vec![(
+            "out".to_string(),
+            ParamAttributes::In | ParamAttributes::Out,
+            false
+        )]
+    );
+}
+
+#[test]
+fn param_directions_override_inference() {
+    let index = open_index();
+
+    let header = method(&index, "parse_header")
+        .params()
+        .find(|p| p.name() == "header")
+        .expect("header param");
+    assert_eq!(header.flags(), ParamAttributes::Out);
+    assert!(header.has_attribute("RetValAttribute"));
+
+    assert_eq!(
+        param_attrs(&index, "query_size"),
+        vec![("size".to_string(), ParamAttributes::Out, false)]
+    );
+    assert_eq!(
+        param_attrs(&index, "checksum")[1],
+        (
+            "out".to_string(),
+            ParamAttributes::In | ParamAttributes::Out,
+            false
+        )
+    );
+}
+
+/// The `ClassLayout` size and layout flag of record `name`, which must be a
+/// value type.
+fn record_layout(index: &windows_metadata::reader::TypeIndex, name: &str) -> (u32, bool) {
+    let td = index.expect("FunctionsTest", name);
+    assert_eq!(td.category(), TypeCategory::Struct, "{name}");
+    let size = td.class_layout().expect("ClassLayout").class_size();
+    (size, td.flags().contains(TypeAttributes::ExplicitLayout))
+}
+
+#[test]
+fn struct_returned_by_value() {
+    let index = open_index();
+    let sig = method(&index, "int_div").signature(&[]);
+
+    // A named type, not a pointer: the writer encodes it as a VALUETYPE.
+    assert_eq!(sig.return_type, Type::named("FunctionsTest", "div_result"));
+    assert_eq!(sig.types, vec![Type::I32, Type::I32]);
+    assert_eq!(record_layout(&index, "div_result"), (8, false));
+}
+
+#[test]
+fn union_passed_and_returned_by_value() {
+    let index = open_index();
+    let float_bits = method(&index, "float_bits");
+    let sig = float_bits.signature(&[]);
+
+    let union = Type::named("FunctionsTest", "FloatBits");
+    assert_eq!(sig.return_type, union);
+    assert_eq!(sig.types, vec![union]);
+    assert_eq!(record_layout(&index, "FloatBits"), (4, true));
+
+    // By-value arguments are neither `[In]` nor `[Out]`.
+    let param = float_bits.params().find(|p| p.sequence() == 1).unwrap();
+    assert_eq!(param.flags(), ParamAttributes::default());
+}
+
+#[test]
+fn pointer_to_pointer_to_record_keeps_both_levels() {
+    let index = open_index();
+    let sig = method(&index, "list_results").signature(&[]);
+
+    // `div_result **`: two pointer levels ending in the record's TypeRef.
+    assert_eq!(
+        sig.types,
+        vec![
+            Type::PtrMut(Box::new(Type::named("FunctionsTest", "div_result")), 2),
+            Type::PtrMut(Box::new(Type::I32), 1),
+        ]
+    );
+}
+
+#[test]
+fn function_libraries_override_import_scope() {
+    let index = open_index();
+    let scope = |name: &str| {
+        method(&index, name)
+            .impl_map()
+            .expect("ImplMap")
+            .import_scope()
+            .name()
+            .to_string()
+    };
+
+    assert_eq!(scope("log_flush"), "logsink");
+    assert_eq!(scope("int_div"), "mathlib");
+    assert_eq!(scope("log_message"), "functions");
+}
+
+#[test]
+fn last_error_functions_support_last_error() {
+    let index = open_index();
+    let supports_last_error = |name: &str| {
+        method(&index, name)
+            .impl_map()
+            .expect("ImplMap")
+            .flags()
+            .contains(PInvokeAttributes::SupportsLastError)
+    };
+
+    assert!(supports_last_error("log_open"));
+    assert!(supports_last_error("read_count"));
+    assert!(!supports_last_error("log_flush"));
+    assert!(!supports_last_error("int_div"));
+}
@@ -0,0 +1,128 @@
+//! Round-trip integration test: `[partition.globals]` emits accessor-backed
+//! globals such as `errno` as functions importing the accessor symbol.
+
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use windows_metadata::Type;
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../tests/fixtures/globals")
+        .join(name)
+}
+
+static GLOBALS_WINMD: LazyLock<Vec<u8>> = LazyLock::new(|| {
+    bnd_winmd::generate(&fixture("globals.toml")).expect("generate globals winmd")
+});
+
+fn open_index() -> windows_metadata::reader::TypeIndex {
+    let file = windows_metadata::reader::File::new(GLOBALS_WINMD.clone()).expect("parse winmd");
+    windows_metadata::reader::TypeIndex::new(vec![file])
+}
+
+/// Return type and import name of the accessor function for `global`.
+fn global(name: &str) -> (Type, String) {
+    let index = open_index();
+    let method = index
+        .expect("GlobalsTest", "Apis")
+        .methods()
+        .find(|m| m.name() == name)
+        .unwrap_or_else(|| panic!("{name} not found"));
+    let sig = method.signature(&[]);
+    assert!(sig.types.is_empty(), "{name} should take no arguments");
+    let import_name = method
+        .impl_map()
+        .expect("ImplMap")
+        .import_name()
+        .to_string();
+    (sig.return_type, import_name)
+}
+
+#[test]
+fn errno_imports_its_accessor() {
+    let (return_type, import_name) = global("errno");
+    assert_eq!(return_type, Type::PtrMut(Box::new(Type::I32), 1));
+    assert_eq!(import_name, "__errno_location");
+}
+
+#[test]
+fn global_of_extracted_struct_type() {
+    let (return_type, import_name) = global("io_state_current");
+    assert_eq!(
+        return_type,
+        Type::PtrMut(Box::new(Type::named("GlobalsTest", "io_state")), 1)
+    );
+    assert_eq!(import_name, "__io_state_location");
+}
+
+#[test]
+fn global_of_const_pointer_type() {
+    let (return_type, import_name) = global("progname");
+    assert_eq!(
+        return_type,
+        Type::PtrMut(Box::new(Type::PtrMut(Box::new(Type::I8), 1)), 1)
+    );
+    assert_eq!(import_name, "__progname_location");
+}
+
+#[test]
+fn accessor_is_still_extracted() {
+    let index = open_index();
+    let apis = index.expect("GlobalsTest", "Apis");
+    assert!(apis.methods().any(|m| m.name() == "__errno_location"));
+}
+
+#[test]
+fn unparseable_global_type_is_reported() {
+    let err = format!(
+        "{:#}",
+        bnd_winmd::generate(&fixture("bad_type.toml")).expect_err("should fail")
+    );
+    assert!(
+        err.contains("errno") && err.contains("int (*)(void)"),
+        "error should name the global and its type, got:\n{err}"
+    );
+}
+
+/// Read `errno` the way a consumer of the winmd would: look up the symbol
+/// its ImplMap imports, in the library it names, and call it after a
+/// failed syscall.
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+#[test]
+fn errno_accessor_reads_errno_after_failed_syscall() {
+    use std::ffi::{CString, c_char, c_int, c_void};
+
+    unsafe extern "C" {
+        fn dlopen(filename: *const c_char, flags: c_int) -> *mut c_void;
+        fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+        fn access(path: *const c_char, mode: c_int) -> c_int;
+    }
+    const RTLD_NOW: c_int = 2;
+
+    let index = open_index();
+    let method = index
+        .expect("GlobalsTest", "Apis")
+        .methods()
+        .find(|m| m.name() == "errno")
+        .expect("errno");
+    let impl_map = method.impl_map().expect("ImplMap");
+    let library = CString::new(format!("lib{}.so.6", impl_map.import_scope().name())).unwrap();
+    let symbol = CString::new(impl_map.import_name()).unwrap();
+
+    unsafe {
+        let handle = dlopen(library.as_ptr(), RTLD_NOW);
+        assert!(!handle.is_null(), "dlopen {library:?}");
+        let accessor = dlsym(handle, symbol.as_ptr());
+        assert!(!accessor.is_null(), "dlsym {symbol:?}");
+        let errno: unsafe extern "C" fn() -> *mut c_int = std::mem::transmute(accessor);
+
+        *errno() = 0;
+        assert_eq!(
+            access(c"/nonexistent/path/that/does/not/exist".as_ptr(), 0),
+            -1
+        );
+        // ENOENT
+        assert_eq!(*errno(), 2);
+    }
+}
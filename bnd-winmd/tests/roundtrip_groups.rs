@@ -0,0 +1,66 @@
+//! Round-trip integration test: `constant_group_prefixes` moves constants
+//! into a sub-namespace `Apis` class per prefix.
+
+use std::path::Path;
+use std::sync::LazyLock;
+
+static GROUPS_WINMD: LazyLock<Vec<u8>> = LazyLock::new(|| {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/groups/groups.toml");
+    bnd_winmd::generate(&path).expect("generate groups winmd")
+});
+
+fn open_index() -> windows_metadata::reader::TypeIndex {
+    let file = windows_metadata::reader::File::new(GROUPS_WINMD.clone()).expect("parse winmd");
+    windows_metadata::reader::TypeIndex::new(vec![file])
+}
+
+fn constants(index: &windows_metadata::reader::TypeIndex, namespace: &str) -> Vec<String> {
+    let mut names: Vec<String> = index
+        .expect(namespace, "Apis")
+        .fields()
+        .map(|f| f.name().to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn grouped_constants_land_in_prefix_namespace() {
+    let index = open_index();
+
+    assert_eq!(
+        constants(&index, "GroupsTest.GRP_OP"),
+        ["GRP_OP_ALL", "GRP_OP_NO_TICKET"]
+    );
+    assert_eq!(
+        constants(&index, "GroupsTest.GRP_V"),
+        ["GRP_V_ERR_UNSPECIFIED", "GRP_V_OK"]
+    );
+}
+
+#[test]
+fn ungrouped_constants_and_functions_stay_on_apis() {
+    let index = open_index();
+
+    assert_eq!(constants(&index, "GroupsTest"), ["GRP_VERSION"]);
+    let apis = index.expect("GroupsTest", "Apis");
+    assert!(apis.methods().any(|m| m.name() == "grp_init"));
+    assert_eq!(
+        index.expect("GroupsTest.GRP_OP", "Apis").methods().count(),
+        0,
+        "groups hold constants only"
+    );
+}
+
+#[test]
+fn grouped_constant_keeps_its_value() {
+    let index = open_index();
+
+    let field = index
+        .expect("GroupsTest.GRP_OP", "Apis")
+        .fields()
+        .find(|f| f.name() == "GRP_OP_NO_TICKET")
+        .expect("GRP_OP_NO_TICKET");
+    let value = field.constant().expect("literal").value();
+    assert_eq!(value, windows_metadata::Value::I32(0x4000));
+}
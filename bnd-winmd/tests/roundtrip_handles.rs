@@ -0,0 +1,105 @@
+//! Round-trip integration test: `opaque_handles` typedefs become
+//! pointer-sized handle structs passed by value.
+
+use std::path::Path;
+use std::sync::LazyLock;
+
+use windows_metadata::{HasAttributes, Type};
+
+static HANDLES_WINMD: LazyLock<Vec<u8>> = LazyLock::new(|| {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/handles/handles.toml");
+    bnd_winmd::generate(&path).expect("generate handles winmd")
+});
+
+fn open_index() -> windows_metadata::reader::TypeIndex {
+    let file = windows_metadata::reader::File::new(HANDLES_WINMD.clone()).expect("parse winmd");
+    windows_metadata::reader::TypeIndex::new(vec![file])
+}
+
+fn signature(
+    index: &windows_metadata::reader::TypeIndex,
+    name: &str,
+) -> windows_metadata::Signature {
+    index
+        .expect("HandlesTest", "Apis")
+        .methods()
+        .find(|m| m.name() == name)
+        .unwrap_or_else(|| panic!("{name} not found"))
+        .signature(&[])
+}
+
+fn handle() -> Type {
+    Type::named("HandlesTest", "HDL_CTX")
+}
+
+#[test]
+fn handle_typedef_wraps_a_single_pointer() {
+    let index = open_index();
+
+    let td = index.expect("HandlesTest", "HDL_CTX");
+    let fields: Vec<(String, Type)> = td
+        .fields()
+        .map(|f| (f.name().to_string(), f.ty()))
+        .collect();
+    assert_eq!(
+        fields,
+        vec![("Value".to_string(), Type::PtrMut(Box::new(Type::Void), 1))]
+    );
+    assert!(td.has_attribute("NativeTypedefAttribute"));
+}
+
+#[test]
+fn handle_pointers_are_passed_by_value() {
+    let index = open_index();
+
+    assert_eq!(signature(&index, "hdl_new").return_type, handle());
+    assert_eq!(signature(&index, "hdl_free").types, vec![handle()]);
+    // `const HDL_CTX *` is the handle too; `HDL_CTX **` keeps one level.
+    assert_eq!(
+        signature(&index, "hdl_clone").types,
+        vec![handle(), Type::PtrMut(Box::new(handle()), 1)]
+    );
+
+    let holder = index.expect("HandlesTest", "HdlHolder");
+    let ctx = holder.fields().find(|f| f.name() == "ctx").unwrap();
+    assert_eq!(ctx.ty(), handle());
+}
+
+#[test]
+fn unlisted_opaque_typedef_keeps_pointer() {
+    let index = open_index();
+
+    assert_eq!(
+        signature(&index, "hdl_plain_use").types,
+        vec![Type::PtrMut(
+            Box::new(Type::named("HandlesTest", "HDL_PLAIN")),
+            1
+        )]
+    );
+}
+
+#[test]
+fn forward_declared_struct_becomes_handle() {
+    let index = open_index();
+    let session = Type::named("HandlesTest", "hdl_session");
+
+    let td = index.expect("HandlesTest", "hdl_session");
+    assert!(td.has_attribute("NativeTypedefAttribute"));
+    assert_eq!(
+        td.fields().map(|f| f.ty()).collect::<Vec<_>>(),
+        vec![Type::PtrMut(Box::new(Type::Void), 1)]
+    );
+
+    assert_eq!(signature(&index, "hdl_session_open").return_type, session);
+    assert_eq!(signature(&index, "hdl_session_close").types, vec![session]);
+}
+
+#[test]
+fn unlisted_forward_declared_struct_is_void_pointer() {
+    let index = open_index();
+
+    assert_eq!(
+        signature(&index, "hdl_other_use").types,
+        vec![Type::PtrMut(Box::new(Type::Void), 1)]
+    );
+}
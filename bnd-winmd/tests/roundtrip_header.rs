@@ -0,0 +1,50 @@
+//! Round-trip integration test: a single header generates without a TOML
+//! file, with namespace and library derived from its name.
+
+use std::path::{Path, PathBuf};
+
+fn simple_header() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/simple/simple.h")
+}
+
+fn open_index(winmd: Vec<u8>) -> windows_metadata::reader::TypeIndex {
+    let file = windows_metadata::reader::File::new(winmd).expect("parse winmd");
+    windows_metadata::reader::TypeIndex::new(vec![file])
+}
+
+#[test]
+fn header_generates_with_explicit_names() {
+    let winmd = bnd_winmd::generate_from_header(&simple_header(), Some("Foo"), Some("foo"))
+        .expect("generate from header");
+    let index = open_index(winmd);
+
+    let create = index
+        .expect("Foo", "Apis")
+        .methods()
+        .find(|m| m.name() == "create_widget")
+        .expect("create_widget");
+    assert_eq!(
+        create.impl_map().expect("ImplMap").import_scope().name(),
+        "foo"
+    );
+    index.expect("Foo", "Rect");
+}
+
+#[test]
+fn names_default_to_the_file_stem() {
+    let cfg = bnd_winmd::config::header_config(&simple_header(), None, None).expect("config");
+    assert_eq!(cfg.output[0].name, "simple");
+    assert_eq!(cfg.output[0].file, Path::new("simple.winmd"));
+    assert_eq!(cfg.partition[0].namespace, "simple");
+    assert_eq!(cfg.partition[0].library, "simple");
+    assert_eq!(cfg.partition[0].traverse, [simple_header()]);
+
+    let index =
+        open_index(bnd_winmd::generate_from_config(&cfg, Path::new(".")).expect("generate"));
+    assert!(
+        index
+            .expect("simple", "Apis")
+            .methods()
+            .any(|m| m.name() == "widget_count")
+    );
+}
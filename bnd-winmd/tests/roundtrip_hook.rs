@@ -0,0 +1,66 @@
+//! Integration test: `generate_from_config_with` lets a hook edit the model
+//! before it is emitted.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use windows_metadata::Type;
+
+fn fixture() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/simple/simple.toml")
+}
+
+fn generate_with(
+    hook: impl FnMut(&mut Vec<bnd_winmd::model::Partition>, &mut bnd_winmd::model::TypeRegistry),
+) -> anyhow::Result<windows_metadata::reader::TypeIndex> {
+    let path = fixture();
+    let cfg = bnd_winmd::config::load_config(&path).expect("load config");
+    let winmd = bnd_winmd::generate_from_config_with(&cfg, path.parent().unwrap(), hook)?;
+    let file = windows_metadata::reader::File::new(winmd).expect("parse winmd");
+    Ok(windows_metadata::reader::TypeIndex::new(vec![file]))
+}
+
+#[test]
+fn hook_renames_a_struct() {
+    let renames = HashMap::from([("Rect".to_string(), "Rectangle".to_string())]);
+    let index = generate_with(|partitions, registry| {
+        for partition in partitions.iter_mut() {
+            for s in &mut partition.structs {
+                if s.name == "Rect" {
+                    s.name = "Rectangle".to_string();
+                }
+            }
+            partition.rename_type_references(&renames);
+        }
+        let namespace = registry.types.remove("Rect").expect("Rect registered");
+        registry.types.insert("Rectangle".to_string(), namespace);
+    })
+    .expect("generate with hook");
+
+    assert!(!index.contains("SimpleTest", "Rect"));
+    index.expect("SimpleTest", "Rectangle");
+    let create = index
+        .expect("SimpleTest", "Apis")
+        .methods()
+        .find(|m| m.name() == "create_widget")
+        .expect("create_widget")
+        .signature(&[]);
+    assert_eq!(create.types[1], Type::named("SimpleTest", "Rectangle"));
+}
+
+#[test]
+fn references_left_dangling_by_a_hook_are_an_error() {
+    // Renames the struct and its registry entry, but not its uses.
+    let err = generate_with(|partitions, registry| {
+        for s in partitions.iter_mut().flat_map(|p| &mut p.structs) {
+            if s.name == "Rect" {
+                s.name = "Rectangle".to_string();
+            }
+        }
+        let namespace = registry.types.remove("Rect").expect("Rect registered");
+        registry.types.insert("Rectangle".to_string(), namespace);
+    })
+    .err()
+    .expect("Rect is still referenced");
+    assert!(format!("{err:#}").contains("`Rect`"), "{err:#}");
+}
@@ -0,0 +1,229 @@
+//! Integration test: `[[type_import]]` from several winmds, with assembly /
+//! version pinning, exact namespace matching and `AssemblyRef` scopes.
+
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use windows_metadata::Type;
+
+fn fixture_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/imports")
+}
+
+/// Generate the two upstream winmds (`Alpha` at version 1.2.0.0, `Beta` at
+/// the default version) once, into a temp directory.
+static IMPORT_DIR: LazyLock<PathBuf> = LazyLock::new(|| {
+    let dir = std::env::temp_dir().join(format!("bnd_winmd_imports_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    for name in ["alpha", "beta"] {
+        let config = fixture_dir().join(format!("{name}.toml"));
+        bnd_winmd::run(&config, Some(&dir.join(format!("{name}.winmd"))))
+            .unwrap_or_else(|e| panic!("generate {name} winmd: {e:#}"));
+    }
+    dir
+});
+
+/// Generate `consumer.h` with the given `[[type_import]]` tables.
+fn generate_consumer(test: &str, imports: &str) -> anyhow::Result<Vec<u8>> {
+    let config = IMPORT_DIR.join(format!("{test}.toml"));
+    let toml = format!(
+        r#"
+[output]
+name = "Consumer"
+
+{imports}
+
+[[partition]]
+namespace = "Consumer"
+library = "consumer"
+headers = ['{header}']
+traverse = ['{header}']
+"#,
+        header = fixture_dir().join("consumer.h").display(),
+    );
+    std::fs::write(&config, toml).unwrap();
+    bnd_winmd::generate(&config)
+}
+
+fn param_type(winmd: Vec<u8>, function: &str) -> Type {
+    let file = windows_metadata::reader::File::new(winmd).expect("parse winmd");
+    let index = windows_metadata::reader::TypeIndex::new(vec![file]);
+    let sig = index
+        .expect("Consumer", "Apis")
+        .methods()
+        .find(|m| m.name() == function)
+        .unwrap_or_else(|| panic!("{function} not found"))
+        .signature(&[]);
+    sig.types[0].clone()
+}
+
+const BOTH: &str = r#"
+[[type_import]]
+winmd = "alpha.winmd"
+namespace = "Alpha"
+assembly = "Alpha"
+version = "1.2.0.0"
+
+[[type_import]]
+winmd = "beta.winmd"
+namespace = "Beta"
+"#;
+
+#[test]
+fn types_from_both_winmds_are_imported() {
+    let winmd = generate_consumer("both", BOTH).expect("generate consumer");
+
+    assert_eq!(
+        param_type(winmd.clone(), "consume_point"),
+        Type::PtrMut(Box::new(Type::named("Alpha.Types", "AlphaPoint")), 1)
+    );
+    assert_eq!(
+        param_type(winmd.clone(), "consume_size"),
+        Type::PtrMut(Box::new(Type::named("Beta", "BetaSize")), 1)
+    );
+    // Prefix matching pulls in the Beta.Inner sub-namespace too.
+    assert_eq!(
+        param_type(winmd, "consume_inner"),
+        Type::PtrMut(Box::new(Type::named("Beta.Inner", "BetaInner")), 1)
+    );
+}
+
+#[test]
+fn output_version_is_stamped() {
+    let alpha = std::fs::read(IMPORT_DIR.join("alpha.winmd")).unwrap();
+    let identity = bnd_winmd::metadata::read_assembly(&alpha).unwrap();
+    assert_eq!(identity.name, "Alpha");
+    assert_eq!(identity.version.to_string(), "1.2.0.0");
+
+    let beta = std::fs::read(IMPORT_DIR.join("beta.winmd")).unwrap();
+    let identity = bnd_winmd::metadata::read_assembly(&beta).unwrap();
+    assert_eq!(identity.version.to_string(), "255.255.255.255");
+}
+
+#[test]
+fn version_mismatch_is_an_error() {
+    let err = generate_consumer(
+        "wrong_version",
+        r#"
+[[type_import]]
+winmd = "alpha.winmd"
+namespace = "Alpha"
+version = "2.0"
+"#,
+    )
+    .expect_err("version 2.0 must not match 1.2.0.0");
+    let msg = format!("{err:#}");
+    assert!(
+        msg.contains("requested version 2.0.0.0, but assembly `Alpha` is version 1.2.0.0"),
+        "{msg}"
+    );
+}
+
+#[test]
+fn assembly_mismatch_is_an_error() {
+    let err = generate_consumer(
+        "wrong_assembly",
+        r#"
+[[type_import]]
+winmd = "beta.winmd"
+namespace = "Beta"
+assembly = "Alpha"
+"#,
+    )
+    .expect_err("beta.winmd is not the Alpha assembly");
+    let msg = format!("{err:#}");
+    assert!(
+        msg.contains("expected assembly `Alpha`, found `Beta`"),
+        "{msg}"
+    );
+}
+
+#[test]
+fn missing_winmd_is_an_error() {
+    let err = generate_consumer(
+        "missing_winmd",
+        r#"
+[[type_import]]
+winmd = "does_not_exist.winmd"
+namespace = "Gamma"
+"#,
+    )
+    .expect_err("a missing winmd must be an error, not a panic");
+    let msg = format!("{err:#}");
+    assert!(msg.contains("does_not_exist.winmd"), "{msg}");
+    assert!(msg.contains("run the upstream gen crate first"), "{msg}");
+}
+
+#[test]
+fn exact_namespace_match_skips_sub_namespaces() {
+    let err = generate_consumer(
+        "exact",
+        r#"
+[[type_import]]
+winmd = "alpha.winmd"
+namespace = "Alpha.Types"
+namespace_match = "exact"
+
+[[type_import]]
+winmd = "beta.winmd"
+namespace = "Beta"
+namespace_match = "exact"
+"#,
+    )
+    .expect_err("BetaInner is in Beta.Inner, which an exact match excludes");
+    let msg = format!("{err:#}");
+    assert!(msg.contains("`BetaInner`"), "{msg}");
+    assert!(!msg.contains("`BetaSize`"), "{msg}");
+    assert!(!msg.contains("`AlphaPoint`"), "{msg}");
+}
+
+#[test]
+fn imported_type_refs_are_scoped_to_their_assembly() {
+    use bnd_winmd::metadata::{AssemblyIdentity, ResolutionScope, type_refs};
+
+    let winmd = generate_consumer("scopes", BOTH).expect("generate consumer");
+    let refs = type_refs(&winmd).expect("read TypeRefs");
+    let scope_of = |name: &str| {
+        refs.iter()
+            .find(|r| r.name == name)
+            .unwrap_or_else(|| panic!("no TypeRef to {name}"))
+            .scope
+            .clone()
+    };
+
+    assert_eq!(
+        scope_of("AlphaPoint"),
+        ResolutionScope::AssemblyRef(AssemblyIdentity {
+            name: "Alpha".to_string(),
+            version: "1.2.0.0".parse().unwrap(),
+        })
+    );
+    assert_eq!(
+        scope_of("BetaSize"),
+        ResolutionScope::AssemblyRef(AssemblyIdentity {
+            name: "Beta".to_string(),
+            version: "255.255.255.255".parse().unwrap(),
+        })
+    );
+}
+
+#[test]
+fn malformed_output_version_is_reported() {
+    let dir = std::env::temp_dir().join(format!("bnd_winmd_versions_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    for (table, version, expected) in [
+        ("[output]", "1.2.3.4.5", "more than four parts"),
+        ("[[output]]", "1.x", "invalid assembly version `1.x`"),
+    ] {
+        let config = dir.join("versions.toml");
+        std::fs::write(
+            &config,
+            format!("{table}\nname = \"Alpha\"\nversion = \"{version}\"\n"),
+        )
+        .unwrap();
+        let err = bnd_winmd::config::load_config(&config).expect_err("version is malformed");
+        let msg = format!("{err:#}");
+        assert!(msg.contains(expected), "{table}: {msg}");
+    }
+}
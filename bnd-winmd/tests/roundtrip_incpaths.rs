@@ -0,0 +1,73 @@
+//! Round-trip integration test: partition-level `include_paths` resolve
+//! headers and `#include`s for that partition only, and headers are
+//! searched for through every include root in order.
+
+use std::path::Path;
+
+use windows_metadata::Type;
+
+fn fixture(name: &str) -> std::path::PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../tests/fixtures/incpaths")
+        .join(name)
+}
+
+#[test]
+fn partition_include_paths_find_headers() {
+    let winmd = bnd_winmd::generate(&fixture("incpaths.toml")).expect("generate incpaths winmd");
+    let file = windows_metadata::reader::File::new(winmd).expect("parse winmd");
+    let index = windows_metadata::reader::TypeIndex::new(vec![file]);
+
+    // `vendor.h` itself was found through the partition's include path.
+    index.expect("IncPaths.Vendor", "VendorHandle");
+    let vendor_apis = index.expect("IncPaths.Vendor", "Apis");
+    assert!(vendor_apis.methods().any(|m| m.name() == "vendor_open"));
+
+    // `#include <vendor.h>` in app.h was resolved via `-I`.
+    let app_start = index
+        .expect("IncPaths.App", "Apis")
+        .methods()
+        .find(|m| m.name() == "app_start")
+        .expect("app_start missing");
+    assert_eq!(
+        app_start.signature(&[]).types,
+        vec![Type::PtrMut(
+            Box::new(Type::named("IncPaths.Vendor", "VendorHandle")),
+            1
+        )]
+    );
+}
+
+#[test]
+fn partition_include_paths_do_not_leak() {
+    let err = format!(
+        "{:#}",
+        bnd_winmd::generate(&fixture("missing_path.toml"))
+            .expect_err("app partition has no include path for vendor.h")
+    );
+    assert!(err.contains("vendor.h"), "{err}");
+    assert!(err.contains("IncPaths.App"), "{err}");
+}
+
+#[test]
+fn header_is_found_through_second_include_path() {
+    let winmd = bnd_winmd::generate(&fixture("search_order.toml")).expect("generate winmd");
+    let file = windows_metadata::reader::File::new(winmd).expect("parse winmd");
+    let index = windows_metadata::reader::TypeIndex::new(vec![file]);
+
+    let apis = index.expect("IncPaths.Extra", "Apis");
+    assert!(apis.methods().any(|m| m.name() == "extra_count"));
+}
+
+#[test]
+fn missing_header_lists_roots_tried() {
+    let err = format!(
+        "{:#}",
+        bnd_winmd::generate(&fixture("search_missing.toml")).expect_err("nowhere.h does not exist")
+    );
+    assert!(err.contains("nowhere.h"), "{err}");
+    assert!(err.contains("IncPaths.Missing"), "{err}");
+    for root in ["incpaths", "vendor/include", "extra/include"] {
+        assert!(err.contains(root), "{root} not listed in:\n{err}");
+    }
+}
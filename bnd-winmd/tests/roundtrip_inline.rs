@@ -0,0 +1,61 @@
+//! Round-trip integration test: `static` / `inline` functions have no symbol
+//! to import, so they are skipped, or marked with `inline_functions = "mark"`.
+
+use std::path::Path;
+use std::sync::LazyLock;
+
+use windows_metadata::reader::HasAttributes;
+
+static INLINE_WINMD: LazyLock<Vec<u8>> = LazyLock::new(|| {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/inline/inline.toml");
+    bnd_winmd::generate(&path).expect("generate inline winmd")
+});
+
+/// `(name, has InlineFunctionAttribute)` of every method on `namespace.Apis`.
+/// Marked methods must import from `FORCEINLINE`, which windows-bindgen skips.
+fn methods(namespace: &str) -> Vec<(String, bool)> {
+    let file = windows_metadata::reader::File::new(INLINE_WINMD.clone()).expect("parse winmd");
+    let index = windows_metadata::reader::TypeIndex::new(vec![file]);
+    index
+        .expect(namespace, "Apis")
+        .methods()
+        .map(|m| {
+            let marked = m.find_attribute("InlineFunctionAttribute").is_some();
+            let scope = m
+                .impl_map()
+                .expect("ImplMap")
+                .import_scope()
+                .name()
+                .to_string();
+            assert_eq!(
+                scope == "FORCEINLINE",
+                marked,
+                "{}: scope {scope}",
+                m.name()
+            );
+            (m.name().to_string(), marked)
+        })
+        .collect()
+}
+
+#[test]
+fn inline_functions_are_skipped_by_default() {
+    let methods = methods("InlineTest");
+    let names: Vec<&str> = methods.iter().map(|(n, _)| n.as_str()).collect();
+    assert_eq!(names, vec!["thrice", "exported"]);
+}
+
+#[test]
+fn inline_functions_can_be_marked() {
+    let methods = methods("InlineTest.Marked");
+    assert_eq!(
+        methods,
+        vec![
+            ("swap16".to_string(), true),
+            ("twice".to_string(), true),
+            ("zero".to_string(), true),
+            ("thrice".to_string(), false),
+            ("exported".to_string(), false),
+        ]
+    );
+}
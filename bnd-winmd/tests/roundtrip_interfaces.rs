@@ -0,0 +1,139 @@
+//! Integration test: `[[type_import]]` of a COM-style interface — the
+//! interface keeps its IID and `IFoo *` collapses to the interface type.
+
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use windows_metadata::reader::HasAttributes;
+use windows_metadata::writer::{AttributeType, HasAttribute, MemberRefParent, TypeDefOrRef};
+use windows_metadata::{Signature, Type, TypeAttributes, Value};
+
+/// `{5a1d3c4e-9b2f-4e61-8c7a-0f1e2d3c4b5a}`
+const IID: (u32, u16, u16, [u8; 8]) = (
+    0x5a1d3c4e,
+    0x9b2f,
+    0x4e61,
+    [0x8c, 0x7a, 0x0f, 0x1e, 0x2d, 0x3c, 0x4b, 0x5a],
+);
+
+fn fixture_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/interfaces")
+}
+
+fn guid_args() -> Vec<(String, Value)> {
+    let (a, b, c, d) = IID;
+    let mut args = vec![
+        (String::new(), Value::U32(a)),
+        (String::new(), Value::U16(b)),
+        (String::new(), Value::U16(c)),
+    ];
+    args.extend(d.iter().map(|byte| (String::new(), Value::U8(*byte))));
+    args
+}
+
+/// Write the upstream `Com` winmd (a single interface with a
+/// `GuidAttribute`) and a consumer config importing it, into a temp
+/// directory.
+static IMPORT_DIR: LazyLock<PathBuf> = LazyLock::new(|| {
+    let dir = std::env::temp_dir().join(format!("bnd_winmd_interfaces_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let mut file = windows_metadata::writer::File::new("Com");
+    let td = file.TypeDef(
+        "Com",
+        "IWidgetSink",
+        TypeDefOrRef::default(),
+        TypeAttributes::Public | TypeAttributes::Interface | TypeAttributes::Abstract,
+    );
+    let args = guid_args();
+    let attr_ref = file.TypeRef("Windows.Win32.Foundation.Metadata", "GuidAttribute");
+    let ctor_sig = Signature {
+        types: args.iter().map(|(_, value)| value.ty()).collect(),
+        ..Signature::default()
+    };
+    let ctor = file.MemberRef(".ctor", &ctor_sig, MemberRefParent::TypeRef(attr_ref));
+    file.Attribute(
+        HasAttribute::TypeDef(td),
+        AttributeType::MemberRef(ctor),
+        &args,
+    );
+    std::fs::write(dir.join("com.winmd"), file.into_stream()).unwrap();
+
+    let toml = format!(
+        r#"
+[output]
+name = "Consumer"
+
+[[type_import]]
+winmd = "com.winmd"
+namespace = "Com"
+
+[[partition]]
+namespace = "Consumer"
+library = "consumer"
+headers = ['{header}']
+traverse = ['{header}']
+"#,
+        header = fixture_dir().join("consumer.h").display(),
+    );
+    std::fs::write(dir.join("consumer.toml"), toml).unwrap();
+    dir
+});
+
+fn param_type(winmd: Vec<u8>, function: &str) -> Type {
+    let file = windows_metadata::reader::File::new(winmd).expect("parse winmd");
+    let index = windows_metadata::reader::TypeIndex::new(vec![file]);
+    let sig = index
+        .expect("Consumer", "Apis")
+        .methods()
+        .find(|m| m.name() == function)
+        .unwrap_or_else(|| panic!("{function} not found"))
+        .signature(&[]);
+    sig.types[0].clone()
+}
+
+#[test]
+fn interface_pointer_collapses_to_interface() {
+    let winmd = bnd_winmd::generate(&IMPORT_DIR.join("consumer.toml")).expect("generate");
+
+    assert_eq!(
+        param_type(winmd.clone(), "attach_sink"),
+        Type::named("Com", "IWidgetSink")
+    );
+    assert_eq!(
+        param_type(winmd, "query_sink"),
+        Type::PtrMut(Box::new(Type::named("Com", "IWidgetSink")), 1)
+    );
+}
+
+#[test]
+fn interface_iid_is_recorded_in_registry() {
+    let config = IMPORT_DIR.join("consumer.toml");
+    let cfg = bnd_winmd::config::load_config(&config).expect("load config");
+    let json = bnd_winmd::dump_model(&cfg, &IMPORT_DIR).expect("dump model");
+    let model: serde_json::Value = serde_json::from_str(&json).expect("parse dump");
+
+    assert_eq!(
+        model["registry"]["interfaces"]["IWidgetSink"],
+        "5a1d3c4e-9b2f-4e61-8c7a-0f1e2d3c4b5a"
+    );
+}
+
+#[test]
+fn imported_interface_keeps_guid_attribute() {
+    let bytes = std::fs::read(IMPORT_DIR.join("com.winmd")).expect("read com.winmd");
+    let file = windows_metadata::reader::File::new(bytes).expect("parse winmd");
+    let index = windows_metadata::reader::TypeIndex::new(vec![file]);
+    let td = index.expect("Com", "IWidgetSink");
+
+    let values: Vec<Value> = td
+        .find_attribute("GuidAttribute")
+        .expect("GuidAttribute")
+        .value()
+        .into_iter()
+        .map(|(_, value)| value)
+        .collect();
+    let expected: Vec<Value> = guid_args().into_iter().map(|(_, value)| value).collect();
+    assert_eq!(values, expected);
+}
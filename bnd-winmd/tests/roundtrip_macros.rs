@@ -0,0 +1,173 @@
+//! Round-trip integration test: `evaluate_macros` folds constant-expression
+//! `#define`s.
+
+use std::path::Path;
+use std::sync::LazyLock;
+
+use windows_metadata::{HasAttributes, Type, Value};
+
+static MACROS_WINMD: LazyLock<Vec<u8>> = LazyLock::new(|| {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/macros/macros.toml");
+    bnd_winmd::generate(&path).expect("generate macros winmd")
+});
+
+fn constants(namespace: &str) -> Vec<(String, Value)> {
+    let file = windows_metadata::reader::File::new(MACROS_WINMD.clone()).expect("parse winmd");
+    let index = windows_metadata::reader::TypeIndex::new(vec![file]);
+    index
+        .expect(namespace, "Apis")
+        .fields()
+        .map(|f| {
+            let value = f.constant().expect("constant value").value();
+            (f.name().to_string(), value)
+        })
+        .collect()
+}
+
+fn value<'a>(constants: &'a [(String, Value)], name: &str) -> Option<&'a Value> {
+    constants.iter().find(|(n, _)| n == name).map(|(_, v)| v)
+}
+
+#[test]
+fn expression_macros_are_folded() {
+    let constants = constants("MacrosTest");
+
+    assert_eq!(value(&constants, "PAGE_SHIFT"), Some(&Value::I32(12)));
+    assert_eq!(value(&constants, "PAGE_SIZE"), Some(&Value::I32(4096)));
+    assert_eq!(value(&constants, "PAGE_MASK"), Some(&Value::I32(4095)));
+    assert_eq!(
+        value(&constants, "MAP_SHARED_ANON"),
+        Some(&Value::I32(0x21))
+    );
+    assert_eq!(value(&constants, "HUGE_SPAN"), Some(&Value::U64(1 << 40)));
+}
+
+#[test]
+fn non_constant_macros_are_skipped() {
+    let constants = constants("MacrosTest");
+
+    for name in ["NEXT_COUNTER", "GREETING", "ANSWER"] {
+        assert_eq!(value(&constants, name), None, "{name} should be skipped");
+    }
+}
+
+#[test]
+fn evaluation_is_opt_in() {
+    let constants = constants("MacrosTest.Plain");
+
+    assert!(value(&constants, "PAGE_SHIFT").is_some());
+    assert_eq!(value(&constants, "PAGE_SIZE"), None);
+}
+
+#[test]
+fn literal_boundaries_keep_their_value() {
+    // Without evaluation, so only the literal parser sees these.
+    let constants = constants("MacrosTest.Plain");
+
+    assert_eq!(
+        value(&constants, "LIMIT_I64_MAX"),
+        Some(&Value::I64(i64::MAX))
+    );
+    assert_eq!(
+        value(&constants, "LIMIT_I64_MIN"),
+        Some(&Value::I64(i64::MIN))
+    );
+    assert_eq!(
+        value(&constants, "LIMIT_I64_MIN_HEX"),
+        Some(&Value::I64(i64::MIN))
+    );
+    assert_eq!(
+        value(&constants, "LIMIT_U64_MAX"),
+        Some(&Value::U64(u64::MAX))
+    );
+    assert_eq!(
+        value(&constants, "LIMIT_ABOVE_I32"),
+        Some(&Value::I64(1 << 32))
+    );
+}
+
+#[test]
+fn wide_literals_are_string_constants() {
+    let file = windows_metadata::reader::File::new(MACROS_WINMD.clone()).expect("parse winmd");
+    let index = windows_metadata::reader::TypeIndex::new(vec![file]);
+    let wide = |name: &str| {
+        let field = index
+            .expect("MacrosTest.Plain", "Apis")
+            .fields()
+            .find(|f| f.name() == name)
+            .unwrap_or_else(|| panic!("{name} missing"));
+        let native_type = match field
+            .find_attribute("NativeTypeNameAttribute")
+            .expect("NativeTypeNameAttribute")
+            .value()
+            .as_slice()
+        {
+            [(_, Value::Utf8(c_name))] => c_name.clone(),
+            other => panic!("unexpected NativeTypeNameAttribute value: {other:?}"),
+        };
+        (
+            field.ty(),
+            field.constant().expect("constant").value(),
+            native_type,
+        )
+    };
+
+    assert_eq!(
+        wide("LIMIT_U128_MAX"),
+        (
+            Type::String,
+            Value::Utf16("0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF".to_string()),
+            "unsigned __int128".to_string()
+        )
+    );
+    assert_eq!(
+        wide("LIMIT_ABOVE_U64"),
+        (
+            Type::String,
+            Value::Utf16("18446744073709551616".to_string()),
+            "unsigned __int128".to_string()
+        )
+    );
+    assert_eq!(
+        wide("LIMIT_TOO_NEGATIVE"),
+        (
+            Type::String,
+            Value::Utf16("-0x8000000000000001".to_string()),
+            "__int128".to_string()
+        )
+    );
+}
+
+#[test]
+fn char_literal_macros_are_code_points() {
+    let constants = constants("MacrosTest.Plain");
+
+    assert_eq!(
+        value(&constants, "PATH_SEP"),
+        Some(&Value::I32(b'/' as i32))
+    );
+    assert_eq!(value(&constants, "NEWLINE"), Some(&Value::I32(10)));
+    assert_eq!(value(&constants, "OCTAL_A"), Some(&Value::I32(65)));
+    assert_eq!(value(&constants, "WIDE_E_ACUTE"), Some(&Value::I32(0xe9)));
+    assert_eq!(value(&constants, "SMILEY"), Some(&Value::U32(0x263a)));
+}
+
+#[test]
+fn multi_char_literal_packs_bytes() {
+    let constants = constants("MacrosTest.Plain");
+
+    assert_eq!(
+        value(&constants, "FOURCC_RIFF"),
+        Some(&Value::I32(0x5249_4646))
+    );
+    assert_eq!(value(&constants, "TOO_LONG"), None);
+}
+
+#[test]
+fn high_char_follows_char_signedness() {
+    let signed = constants("MacrosTest.SignedChar");
+    assert_eq!(value(&signed, "HIGH_CHAR"), Some(&Value::I32(-1)));
+
+    let unsigned = constants("MacrosTest.UnsignedChar");
+    assert_eq!(value(&unsigned, "HIGH_CHAR"), Some(&Value::I32(0xff)));
+}
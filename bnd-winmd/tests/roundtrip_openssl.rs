@@ -52,7 +52,14 @@ fn types_pem_password_cb_delegate() {
     let index = open_index();
 
     // pem_password_cb should be a delegate typedef in openssl.types
-    let _ = index.expect("openssl.types", "pem_password_cb");
+    let td = index.expect("openssl.types", "pem_password_cb");
+    assert_eq!(
+        td.category(),
+        windows_metadata::reader::TypeCategory::Delegate
+    );
+    let invoke = td.methods().find(|m| m.name() == "Invoke").expect("Invoke");
+    // (char *buf, int size, int rwflag, void *userdata)
+    assert_eq!(invoke.signature(&[]).types.len(), 4);
 }
 
 // ---------------------------------------------------------------------------
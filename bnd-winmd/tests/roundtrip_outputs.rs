@@ -0,0 +1,116 @@
+//! Integration test: `[[output]]` tables split one config run into several
+//! winmds, each holding only the partitions it selects.
+
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use windows_metadata::reader::{File, TypeIndex};
+
+/// Copy the `outputs` fixture into a temp directory and `run` it there, so
+/// the winmds land next to the copied config.
+static OUTPUTS: LazyLock<Vec<PathBuf>> = LazyLock::new(|| {
+    let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/outputs");
+    let dir = std::env::temp_dir().join(format!("bnd_winmd_outputs_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    for file in ["core.h", "net.h", "outputs.toml"] {
+        std::fs::copy(src.join(file), dir.join(file)).unwrap();
+    }
+    bnd_winmd::run(&dir.join("outputs.toml"), None).unwrap_or_else(|e| panic!("run outputs: {e:#}"))
+});
+
+fn open_index(path: &Path) -> TypeIndex {
+    let file = File::new(std::fs::read(path).unwrap()).expect("parse winmd");
+    TypeIndex::new(vec![file])
+}
+
+fn type_names(index: &TypeIndex) -> Vec<String> {
+    let mut names: Vec<String> = index
+        .types()
+        .filter(|td| !td.namespace().is_empty())
+        .map(|td| format!("{}.{}", td.namespace(), td.name()))
+        .collect();
+    names.sort();
+    names
+}
+
+fn method_names(index: &TypeIndex, namespace: &str) -> Vec<String> {
+    index
+        .expect(namespace, "Apis")
+        .methods()
+        .map(|m| m.name().to_string())
+        .collect()
+}
+
+#[test]
+fn each_output_is_written() {
+    let names: Vec<&str> = OUTPUTS
+        .iter()
+        .map(|p| p.file_name().unwrap().to_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["outputs-core.winmd", "outputs-net.winmd"]);
+
+    let core = std::fs::read(&OUTPUTS[0]).unwrap();
+    let identity = bnd_winmd::metadata::read_assembly(&core).unwrap();
+    assert_eq!(identity.name, "OutputsCore");
+    assert_eq!(identity.version.to_string(), "1.0.0.0");
+
+    let net = std::fs::read(&OUTPUTS[1]).unwrap();
+    assert_eq!(
+        bnd_winmd::metadata::read_assembly(&net).unwrap().name,
+        "OutputsNet"
+    );
+}
+
+#[test]
+fn outputs_contain_only_their_partitions() {
+    let core = open_index(&OUTPUTS[0]);
+    assert_eq!(
+        type_names(&core),
+        vec!["Outputs.Core.Apis", "Outputs.Core.CorePoint"]
+    );
+    assert_eq!(method_names(&core, "Outputs.Core"), vec!["core_init"]);
+
+    let net = open_index(&OUTPUTS[1]);
+    assert_eq!(
+        type_names(&net),
+        vec!["Outputs.Net.Apis", "Outputs.Net.NetAddr"]
+    );
+    assert_eq!(
+        method_names(&net, "Outputs.Net"),
+        vec!["net_send", "net_locate"]
+    );
+}
+
+#[test]
+fn cross_output_references_resolve_against_the_sibling() {
+    let core_types = bnd_winmd::verify::imported_types(&OUTPUTS[..1]).unwrap();
+    bnd_winmd::verify::verify_file(&OUTPUTS[1], &core_types)
+        .unwrap_or_else(|e| panic!("net winmd failed verification: {e:#}"));
+
+    use bnd_winmd::metadata::{ResolutionScope, type_refs};
+    let net = std::fs::read(&OUTPUTS[1]).unwrap();
+    let point = type_refs(&net)
+        .unwrap()
+        .into_iter()
+        .find(|r| r.name == "CorePoint")
+        .expect("TypeRef to CorePoint");
+    let ResolutionScope::AssemblyRef(assembly) = point.scope else {
+        panic!(
+            "CorePoint is not scoped to an AssemblyRef: {:?}",
+            point.scope
+        );
+    };
+    assert_eq!(assembly.version.to_string(), "1.0.0.0");
+}
+
+#[test]
+fn generate_needs_a_single_output() {
+    let config =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/outputs/outputs.toml");
+    let err = bnd_winmd::generate(&config).expect_err("two outputs");
+    assert!(format!("{err:#}").contains("generate_all"), "{err:#}");
+
+    let winmds = bnd_winmd::generate_all(&config).expect("generate all");
+    assert_eq!(winmds.len(), 2);
+}
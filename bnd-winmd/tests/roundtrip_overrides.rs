@@ -0,0 +1,71 @@
+//! Round-trip integration test: `namespace_overrides` moves individual
+//! symbols into another partition.
+
+use std::path::Path;
+use std::sync::LazyLock;
+
+use windows_metadata::Type;
+
+static OVERRIDES_WINMD: LazyLock<Vec<u8>> = LazyLock::new(|| {
+    let path =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/overrides/overrides.toml");
+    bnd_winmd::generate(&path).expect("generate overrides winmd")
+});
+
+fn open_index() -> windows_metadata::reader::TypeIndex {
+    let file = windows_metadata::reader::File::new(OVERRIDES_WINMD.clone()).expect("parse winmd");
+    windows_metadata::reader::TypeIndex::new(vec![file])
+}
+
+fn names(index: &windows_metadata::reader::TypeIndex) -> Vec<(String, String)> {
+    index
+        .types()
+        .map(|td| (td.namespace().to_string(), td.name().to_string()))
+        .collect()
+}
+
+#[test]
+fn overridden_struct_moves_namespace() {
+    let index = open_index();
+    let types = names(&index);
+    let has = |ns: &str, name: &str| types.iter().any(|(n, t)| n == ns && t == name);
+
+    assert!(has("OverrideTest.Common", "Point"), "types: {types:?}");
+    assert!(!has("OverrideTest.Shapes", "Point"), "types: {types:?}");
+    assert!(has("OverrideTest.Shapes", "Box"), "types: {types:?}");
+}
+
+#[test]
+fn overridden_struct_referenced_cross_partition() {
+    let index = open_index();
+    let boxed = index.expect("OverrideTest.Shapes", "Box");
+    let field_types: Vec<Type> = boxed.fields().map(|f| f.ty()).collect();
+    let point = Type::named("OverrideTest.Common", "Point");
+    assert_eq!(field_types, vec![point.clone(), point]);
+}
+
+#[test]
+fn overridden_function_and_constant_move_to_target_apis() {
+    let index = open_index();
+
+    let common = index.expect("OverrideTest.Common", "Apis");
+    let methods: Vec<String> = common.methods().map(|m| m.name().to_string()).collect();
+    assert!(
+        methods.contains(&"common_version".to_string()),
+        "{methods:?}"
+    );
+    assert!(methods.contains(&"point_len".to_string()), "{methods:?}");
+    let fields: Vec<String> = common.fields().map(|f| f.name().to_string()).collect();
+    assert_eq!(fields, vec!["POINT_ORIGIN"]);
+
+    let shapes = index.expect("OverrideTest.Shapes", "Apis");
+    let methods: Vec<String> = shapes.methods().map(|m| m.name().to_string()).collect();
+    assert_eq!(methods, vec!["box_area"]);
+    assert_eq!(shapes.fields().count(), 0);
+
+    let point_len = common.methods().find(|m| m.name() == "point_len").unwrap();
+    let impl_map = point_len
+        .impl_map()
+        .expect("point_len should be a P/Invoke");
+    assert_eq!(impl_map.import_scope().name(), "shapes");
+}
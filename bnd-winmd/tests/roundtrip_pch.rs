@@ -0,0 +1,94 @@
+//! Integration test: a partition `pch` built with `build_pch` yields the
+//! same winmd as parsing the headers from scratch.
+
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+fn fixture_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/multi")
+}
+
+/// Precompile both partitions of the multi fixture into a temp directory
+/// and write a copy of `multi.toml` that loads them.
+static PCH_DIR: LazyLock<PathBuf> = LazyLock::new(|| {
+    let dir = std::env::temp_dir().join(format!("bnd_winmd_pch_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let config = fixture_dir().join("multi.toml");
+    let cfg = bnd_winmd::config::load_config(&config).expect("load config");
+    for (namespace, pch) in [
+        ("MultiTest.Types", "types.pch"),
+        ("MultiTest.Widgets", "widgets.pch"),
+    ] {
+        bnd_winmd::build_pch(&cfg, &fixture_dir(), namespace, &dir.join(pch))
+            .unwrap_or_else(|e| panic!("build {pch}: {e:#}"));
+    }
+
+    let toml = format!(
+        r#"
+[output]
+name = "MultiTest"
+
+[[partition]]
+namespace = "MultiTest.Types"
+library = "simple"
+headers = ['{types}']
+traverse = ['{types}']
+pch = "types.pch"
+
+[[partition]]
+namespace = "MultiTest.Widgets"
+library = "simple"
+headers = ['{types}', '{widget}']
+traverse = ['{widget}']
+pch = "widgets.pch"
+"#,
+        types = fixture_dir().join("types.h").display(),
+        widget = fixture_dir().join("widget.h").display(),
+    );
+    std::fs::write(dir.join("multi_pch.toml"), toml).unwrap();
+    dir
+});
+
+#[test]
+fn pch_output_is_byte_identical() {
+    let plain = bnd_winmd::generate(&fixture_dir().join("multi.toml")).expect("generate");
+    let with_pch = bnd_winmd::generate(&PCH_DIR.join("multi_pch.toml")).expect("generate with pch");
+
+    assert!(
+        plain == with_pch,
+        "winmd differs when loading the precompiled headers"
+    );
+}
+
+#[test]
+fn missing_pch_is_an_error() {
+    let toml = PCH_DIR.join("missing_pch.toml");
+    std::fs::write(
+        &toml,
+        format!(
+            r#"
+[output]
+name = "MultiTest"
+
+[[partition]]
+namespace = "MultiTest.Types"
+library = "simple"
+headers = ['{types}']
+pch = "nowhere.pch"
+"#,
+            types = fixture_dir().join("types.h").display(),
+        ),
+    )
+    .unwrap();
+
+    let err = format!(
+        "{:#}",
+        bnd_winmd::generate(&toml).expect_err("pch does not exist")
+    );
+    assert!(
+        err.contains("nowhere.pch"),
+        "error should name the pch, got:\n{err}"
+    );
+}
@@ -31,17 +31,21 @@ fn fcntl_functions_present() {
         "creat missing. Methods: {methods:?}"
     );
 
-    // open is variadic and should be skipped
-    assert!(
-        !methods.contains(&"open".to_string()),
-        "open should be skipped (variadic). Methods: {methods:?}"
-    );
-
-    // fcntl is variadic and should be skipped
-    assert!(
-        !methods.contains(&"fcntl".to_string()),
-        "fcntl should be skipped (variadic). Methods: {methods:?}"
-    );
+    // open and fcntl are variadic and are emitted with the VARARG
+    // calling convention, keeping their fixed params
+    for name in ["open", "fcntl"] {
+        let method = apis
+            .methods()
+            .find(|m| m.name() == name)
+            .unwrap_or_else(|| panic!("{name} missing. Methods: {methods:?}"));
+        assert!(
+            method
+                .signature(&[])
+                .flags
+                .contains(windows_metadata::MethodCallAttributes::VARARG),
+            "{name} should carry the VARARG calling convention"
+        );
+    }
 }
 
 #[test]
@@ -132,6 +136,28 @@ fn unistd_functions_present() {
     check("fsync");
 }
 
+#[test]
+fn unistd_read_write_buffer_direction() {
+    use windows_metadata::{HasAttributes, ParamAttributes};
+
+    let index = open_index();
+    let apis = index.expect("posix.unistd", "Apis");
+    let buf_param = |name: &str| {
+        let method = apis.methods().find(|m| m.name() == name).unwrap();
+        method.params().find(|p| p.name() == "__buf").unwrap()
+    };
+
+    // ssize_t write(int fd, const void *buf, size_t n)
+    let write_buf = buf_param("write");
+    assert_eq!(write_buf.flags(), ParamAttributes::In);
+    assert!(write_buf.has_attribute("ConstAttribute"));
+
+    // ssize_t read(int fd, void *buf, size_t nbytes)
+    let read_buf = buf_param("read");
+    assert_eq!(read_buf.flags(), ParamAttributes::In | ParamAttributes::Out);
+    assert!(!read_buf.has_attribute("ConstAttribute"));
+}
+
 #[test]
 fn unistd_constants_present() {
     let index = open_index();
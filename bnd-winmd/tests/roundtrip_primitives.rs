@@ -0,0 +1,235 @@
+//! Round-trip integration test for target-dependent C primitive types.
+
+use std::path::Path;
+use std::sync::LazyLock;
+
+use windows_metadata::{HasAttributes, Type};
+
+static PRIMITIVES_WINMD: LazyLock<(Vec<u8>, bnd_winmd::Report)> = LazyLock::new(|| {
+    let path =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/primitives/primitives.toml");
+    bnd_winmd::generate_with_report(&path).expect("generate primitives winmd")
+});
+
+fn open_index() -> windows_metadata::reader::TypeIndex {
+    let file =
+        windows_metadata::reader::File::new(PRIMITIVES_WINMD.0.clone()).expect("parse winmd");
+    windows_metadata::reader::TypeIndex::new(vec![file])
+}
+
+/// `long double` is emitted as an opaque byte array sized by clang, so the
+/// struct layout round-trips exactly (16 bytes on x86-64).
+#[test]
+fn long_double_field_keeps_target_size() {
+    let index = open_index();
+
+    let holder = index.expect("PrimitivesTest", "LongDoubleHolder");
+    let field = holder
+        .fields()
+        .find(|f| f.name() == "value")
+        .expect("value field");
+    assert_eq!(
+        field.ty(),
+        Type::ArrayFixed(Box::new(Type::U8), 16),
+        "long double should be emitted as [u8; sizeof(long double)]"
+    );
+
+    let layout = holder.class_layout().expect("LongDoubleHolder layout");
+    assert_eq!(layout.class_size(), 16);
+}
+
+#[test]
+fn long_double_struct_size_includes_padding() {
+    let index = open_index();
+
+    let padded = index.expect("PrimitivesTest", "PaddedLongDouble");
+    let layout = padded.class_layout().expect("PaddedLongDouble layout");
+    // char + 15 bytes padding + 16-byte long double
+    assert_eq!(layout.class_size(), 32);
+    assert_eq!(layout.packing_size(), 16);
+
+    // The byte-array `long double` has alignment 1, so the 15 bytes of C
+    // padding must be spelled out for sequential layout to place it at 16.
+    let fields: Vec<(String, Type)> = padded
+        .fields()
+        .map(|f| (f.name().to_string(), f.ty()))
+        .collect();
+    assert_eq!(
+        fields,
+        vec![
+            ("tag".to_string(), Type::I8),
+            (
+                "_padding0".to_string(),
+                Type::ArrayFixed(Box::new(Type::U8), 15)
+            ),
+            (
+                "value".to_string(),
+                Type::ArrayFixed(Box::new(Type::U8), 16)
+            ),
+        ]
+    );
+}
+
+#[test]
+fn long_double_by_value_function_is_dropped() {
+    let dropped = PRIMITIVES_WINMD
+        .1
+        .dropped("PrimitivesTest")
+        .find(|d| d.name == "ld_identity")
+        .expect("ld_identity should be reported as dropped");
+    assert!(
+        dropped.reason.contains("long double"),
+        "unexpected reason: {}",
+        dropped.reason
+    );
+
+    let index = open_index();
+    let apis = index.expect("PrimitivesTest", "Apis");
+    assert!(!apis.methods().any(|m| m.name() == "ld_identity"));
+    assert!(
+        apis.methods().any(|m| m.name() == "ld_scale"),
+        "a pointer to long double should not drop ld_scale"
+    );
+}
+
+#[test]
+fn int128_by_value_functions_are_dropped() {
+    for name in ["i128_add", "u128_from"] {
+        let dropped = PRIMITIVES_WINMD
+            .1
+            .dropped("PrimitivesTest")
+            .find(|d| d.name == name)
+            .unwrap_or_else(|| panic!("{name} should be reported as dropped"));
+        assert!(
+            dropped.reason.contains("__int128"),
+            "unexpected reason: {}",
+            dropped.reason
+        );
+    }
+
+    let index = open_index();
+    let methods: Vec<String> = index
+        .expect("PrimitivesTest", "Apis")
+        .methods()
+        .map(|m| m.name().to_string())
+        .collect();
+    assert!(!methods.iter().any(|m| m == "i128_add" || m == "u128_from"));
+    let store = index
+        .expect("PrimitivesTest", "Apis")
+        .methods()
+        .find(|m| m.name() == "u128_store")
+        .expect("a pointer to __int128 should not drop u128_store");
+    assert_eq!(
+        store.signature(&[]).types[0],
+        Type::PtrMut(Box::new(Type::ArrayFixed(Box::new(Type::U8), 16)), 1)
+    );
+}
+
+#[test]
+fn long_is_64_bit_under_lp64() {
+    let index = open_index();
+
+    let holder = index.expect("PrimitivesTest", "LongHolder");
+    let types: Vec<(String, Type)> = holder
+        .fields()
+        .map(|f| (f.name().to_string(), f.ty()))
+        .collect();
+    assert_eq!(
+        types,
+        vec![("l".to_string(), Type::I64), ("ul".to_string(), Type::U64)]
+    );
+}
+
+#[test]
+fn long_is_32_bit_under_llp64() {
+    let index = open_index();
+
+    let holder = index.expect("PrimitivesTest.Llp64", "WinLongHolder");
+    let types: Vec<(String, Type)> = holder
+        .fields()
+        .map(|f| (f.name().to_string(), f.ty()))
+        .collect();
+    assert_eq!(
+        types,
+        vec![("l".to_string(), Type::I32), ("ul".to_string(), Type::U32)]
+    );
+
+    let layout = holder.class_layout().expect("WinLongHolder layout");
+    assert_eq!(layout.class_size(), 8);
+}
+
+#[test]
+fn wide_char_fields_have_char_width() {
+    let index = open_index();
+
+    let chars = index.expect("PrimitivesTest", "WideChars");
+    let types: Vec<(String, Type)> = chars
+        .fields()
+        .map(|f| (f.name().to_string(), f.ty()))
+        .collect();
+    assert_eq!(
+        types,
+        vec![
+            ("w".to_string(), Type::U32),
+            ("u16".to_string(), Type::U16),
+            ("u32".to_string(), Type::U32),
+        ]
+    );
+
+    let w = chars.fields().find(|f| f.name() == "w").unwrap();
+    assert!(
+        w.has_attribute("NativeTypeNameAttribute"),
+        "wchar_t field should carry a NativeTypeName hint"
+    );
+}
+
+#[test]
+fn wchar_pointer_param_element_width() {
+    let index = open_index();
+
+    let apis = index.expect("PrimitivesTest", "Apis");
+    let wide_len = apis
+        .methods()
+        .find(|m| m.name() == "wide_len")
+        .expect("wide_len");
+    let sig = wide_len.signature(&[]);
+    assert_eq!(sig.types, vec![Type::PtrMut(Box::new(Type::U32), 1)]);
+
+    let llp64 = index.expect("PrimitivesTest.Llp64", "Apis");
+    let win_wide_len = llp64
+        .methods()
+        .find(|m| m.name() == "win_wide_len")
+        .expect("win_wide_len");
+    let sig = win_wide_len.signature(&[]);
+    assert_eq!(sig.types, vec![Type::PtrMut(Box::new(Type::U16), 1)]);
+    assert_eq!(sig.return_type, Type::U32);
+}
+
+/// `size_t` maps to `usize` by name, also through an alias and whatever
+/// integer the platform defines it as, while `unsigned long` stays `u64`.
+#[test]
+fn size_typedefs_are_pointer_sized() {
+    let index = open_index();
+
+    let holder = index.expect("PrimitivesTest", "SizeHolder");
+    let fields: Vec<(String, Type)> = holder
+        .fields()
+        .map(|f| (f.name().to_string(), f.ty()))
+        .collect();
+    assert_eq!(
+        fields,
+        vec![
+            ("len".to_string(), Type::USize),
+            ("cap".to_string(), Type::USize),
+            ("addr".to_string(), Type::USize),
+            ("raw".to_string(), Type::U64),
+        ]
+    );
+
+    let byte_count = index
+        .expect("PrimitivesTest", "byte_count")
+        .fields()
+        .find(|f| f.name() == "Value")
+        .expect("byte_count Value field");
+    assert_eq!(byte_count.ty(), Type::USize);
+}
@@ -0,0 +1,159 @@
+//! Round-trip integration test: `volatile` and `restrict` qualified
+//! declarations are kept, with the qualifiers dropped.
+
+use std::path::Path;
+use std::sync::LazyLock;
+
+use windows_metadata::{HasAttributes, ParamAttributes, Type, Value};
+
+static QUALIFIERS_WINMD: LazyLock<Vec<u8>> = LazyLock::new(|| {
+    let path =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/qualifiers/qualifiers.toml");
+    let (winmd, report) =
+        bnd_winmd::generate_with_report(&path).expect("generate qualifiers winmd");
+    assert!(report.is_clean(), "dropped: {report:#?}");
+    winmd
+});
+
+fn open_index() -> windows_metadata::reader::TypeIndex {
+    let file = windows_metadata::reader::File::new(QUALIFIERS_WINMD.clone()).expect("parse winmd");
+    windows_metadata::reader::TypeIndex::new(vec![file])
+}
+
+#[test]
+fn volatile_fields_keep_their_struct() {
+    let index = open_index();
+    let state = index.expect("QualifiersTest", "SignalState");
+    let fields: Vec<(String, Type)> = state
+        .fields()
+        .map(|f| (f.name().to_string(), f.ty()))
+        .collect();
+    assert_eq!(
+        fields,
+        vec![
+            // sig_atomic_t is not traversed, so it resolves to its int.
+            ("pending".to_string(), Type::I32),
+            ("counter".to_string(), Type::I32),
+            ("cursor".to_string(), Type::PtrMut(Box::new(Type::I32), 1)),
+            ("mmio".to_string(), Type::PtrMut(Box::new(Type::U8), 1)),
+        ]
+    );
+}
+
+#[test]
+fn volatile_typedef_of_struct_is_a_passthrough() {
+    let index = open_index();
+    let matches = index
+        .types()
+        .filter(|td| td.namespace() == "QualifiersTest" && td.name() == "Register")
+        .count();
+    assert_eq!(matches, 1, "Register must be defined once");
+
+    let register = index.expect("QualifiersTest", "Register");
+    let field = register.fields().next().expect("value field");
+    assert_eq!(field.name(), "value");
+    assert_eq!(field.ty(), Type::U32);
+}
+
+#[test]
+fn restrict_and_volatile_pointer_params_survive() {
+    let index = open_index();
+    let apis = index.expect("QualifiersTest", "Apis");
+    let signature = |name: &str| {
+        apis.methods()
+            .find(|m| m.name() == name)
+            .unwrap_or_else(|| panic!("{name} was dropped"))
+            .signature(&[])
+    };
+
+    // `const` on `src` is carried by its [In] attribute, not the type.
+    let copy = signature("copy_bytes");
+    assert_eq!(
+        copy.types,
+        vec![
+            Type::PtrMut(Box::new(Type::I8), 1),
+            Type::PtrMut(Box::new(Type::I8), 1),
+            Type::I32,
+        ]
+    );
+
+    let base = signature("register_base");
+    assert_eq!(base.return_type, Type::PtrMut(Box::new(Type::I32), 1));
+    assert_eq!(
+        base.types,
+        vec![Type::PtrMut(
+            Box::new(Type::named("QualifiersTest", "Register")),
+            1
+        )]
+    );
+}
+
+/// `(flags, has ConstAttribute, NativeTypeName)` of the first parameter.
+fn first_param(name: &str) -> (ParamAttributes, bool, Option<String>) {
+    let index = open_index();
+    let method = index
+        .expect("QualifiersTest", "Apis")
+        .methods()
+        .find(|m| m.name() == name)
+        .unwrap_or_else(|| panic!("{name} was dropped"));
+    let param = method.params().find(|p| p.sequence() == 1).unwrap();
+    let native_type =
+        param
+            .find_attribute("NativeTypeNameAttribute")
+            .map(|attr| match attr.value().as_slice() {
+                [(_, Value::Utf8(spelling))] => spelling.clone(),
+                other => panic!("unexpected NativeTypeName value: {other:?}"),
+            });
+    (
+        param.flags(),
+        param.has_attribute("ConstAttribute"),
+        native_type,
+    )
+}
+
+#[test]
+fn nested_pointer_constness_is_kept() {
+    // Outer and inner const: [In, Const] plus the full C spelling.
+    assert_eq!(
+        first_param("count_args"),
+        (
+            ParamAttributes::In,
+            true,
+            Some("const char *const *".to_string())
+        )
+    );
+    // Mutable outer pointer to const strings.
+    assert_eq!(
+        first_param("sort_names"),
+        (
+            ParamAttributes::In | ParamAttributes::Out,
+            false,
+            Some("const char **".to_string())
+        )
+    );
+    // Only the outer level is const, which ConstAttribute already says.
+    assert_eq!(first_param("fill_args"), (ParamAttributes::In, true, None));
+}
+
+#[test]
+fn nested_pointer_constness_is_in_the_model() {
+    let path =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/qualifiers/qualifiers.toml");
+    let cfg = bnd_winmd::config::load_config(&path).expect("load config");
+    let json = bnd_winmd::dump_model(&cfg, path.parent().unwrap()).expect("dump model");
+    let model: serde_json::Value = serde_json::from_str(&json).expect("parse dump");
+
+    let count_args = model["partitions"][0]["functions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|f| f["name"] == "count_args")
+        .expect("count_args in dump");
+    assert_eq!(
+        count_args["params"][0]["ty"],
+        serde_json::json!({ "Ptr": {
+            "pointee": { "Ptr": { "pointee": "I8", "is_const": true } },
+            "is_const": true
+        } })
+    );
+}
@@ -0,0 +1,373 @@
+//! Round-trip integration test: struct/union layout edge cases in records.h.
+
+use std::path::Path;
+use std::sync::LazyLock;
+
+use windows_metadata::Type;
+
+static RECORDS_WINMD: LazyLock<Vec<u8>> = LazyLock::new(|| {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/records/records.toml");
+    bnd_winmd::generate(&path).expect("generate records winmd")
+});
+
+fn open_index() -> windows_metadata::reader::TypeIndex {
+    let file = windows_metadata::reader::File::new(RECORDS_WINMD.clone()).expect("parse winmd");
+    windows_metadata::reader::TypeIndex::new(vec![file])
+}
+
+fn field_names(td: &windows_metadata::reader::TypeDef) -> Vec<String> {
+    td.fields().map(|f| f.name().to_string()).collect()
+}
+
+#[test]
+fn anonymous_members_become_synthetic_fields() {
+    let index = open_index();
+
+    let tagged = index.expect("RecordsTest", "Tagged");
+    assert_eq!(
+        field_names(&tagged),
+        vec!["kind", "Anonymous1", "Anonymous2"],
+        "anonymous members should be kept in declaration order"
+    );
+
+    // int + 4 padding + union(8) + struct(4) + 4 padding
+    let layout = tagged.class_layout().expect("Tagged layout");
+    assert_eq!(layout.class_size(), 24);
+
+    let union = index.expect("RecordsTest", "Tagged_Anonymous1");
+    assert!(
+        union
+            .flags()
+            .contains(windows_metadata::TypeAttributes::ExplicitLayout),
+        "Tagged_Anonymous1 should be a union"
+    );
+    assert_eq!(field_names(&union), vec!["i", "d"]);
+    assert_eq!(union.class_layout().expect("union layout").class_size(), 8);
+
+    let inner = index.expect("RecordsTest", "Tagged_Anonymous2");
+    assert_eq!(field_names(&inner), vec!["lo", "hi"]);
+}
+
+#[test]
+fn single_anonymous_member_is_named_anonymous() {
+    let index = open_index();
+
+    let word = index.expect("RecordsTest", "Word");
+    assert_eq!(field_names(&word), vec!["Anonymous"]);
+    assert_eq!(word.class_layout().expect("Word layout").class_size(), 4);
+
+    let union = index.expect("RecordsTest", "Word_Anonymous");
+    assert_eq!(field_names(&union), vec!["raw", "bytes"]);
+}
+
+#[test]
+fn union_size_is_largest_member() {
+    let index = open_index();
+
+    let number = index.expect("RecordsTest", "Number");
+    assert!(
+        number
+            .flags()
+            .contains(windows_metadata::TypeAttributes::ExplicitLayout),
+        "Number should have ExplicitLayout"
+    );
+    assert_eq!(field_names(&number), vec!["c", "i", "d", "raw"]);
+
+    let layout = number.class_layout().expect("Number layout");
+    assert_eq!(layout.class_size(), 16);
+    assert_eq!(layout.packing_size(), 8);
+}
+
+/// Field types in declaration order, so sequential offsets can be checked.
+fn field_types(td: &windows_metadata::reader::TypeDef) -> Vec<(String, Type)> {
+    td.fields()
+        .map(|f| (f.name().to_string(), f.ty()))
+        .collect()
+}
+
+#[test]
+fn over_aligned_field_gets_explicit_padding() {
+    let index = open_index();
+
+    let td = index.expect("RecordsTest", "OverAligned");
+    // tag@0, value@16, size 32: sequential layout needs 15 bytes before
+    // `value` and 12 trailing bytes to match C.
+    assert_eq!(
+        field_types(&td),
+        vec![
+            ("tag".to_string(), Type::I8),
+            (
+                "_padding0".to_string(),
+                Type::ArrayFixed(Box::new(Type::U8), 15)
+            ),
+            ("value".to_string(), Type::I32),
+            (
+                "_padding1".to_string(),
+                Type::ArrayFixed(Box::new(Type::U8), 12)
+            ),
+        ]
+    );
+    let layout = td.class_layout().expect("OverAligned layout");
+    assert_eq!(layout.class_size(), 32);
+    assert_eq!(layout.packing_size(), 16);
+}
+
+#[test]
+fn natural_padding_needs_no_extra_fields() {
+    let index = open_index();
+
+    let td = index.expect("RecordsTest", "NaturallyPadded");
+    assert_eq!(field_names(&td), vec!["tag", "value", "tail"]);
+    assert_eq!(td.class_layout().expect("layout").class_size(), 24);
+}
+
+#[test]
+fn flexible_array_member_is_zero_length_array() {
+    let index = open_index();
+
+    let message = index.expect("RecordsTest", "Message");
+    assert_eq!(
+        field_types(&message),
+        vec![
+            ("len".to_string(), Type::U32),
+            ("kind".to_string(), Type::U16),
+            ("data".to_string(), Type::ArrayFixed(Box::new(Type::I8), 0)),
+        ]
+    );
+    assert_eq!(message.class_layout().expect("layout").class_size(), 8);
+
+    // The flexible member's alignment still places it after padding.
+    let samples = index.expect("RecordsTest", "Samples");
+    assert_eq!(field_names(&samples), vec!["tag", "values"]);
+    assert_eq!(samples.class_layout().expect("layout").class_size(), 4);
+}
+
+#[test]
+fn incomplete_array_parameter_stays_pointer() {
+    let index = open_index();
+
+    let apis = index.expect("RecordsTest", "Apis");
+    let checksum = apis
+        .methods()
+        .find(|m| m.name() == "message_checksum")
+        .expect("message_checksum");
+    assert_eq!(
+        checksum.signature(&[]).types[0],
+        Type::PtrMut(Box::new(Type::I8), 1)
+    );
+}
+
+/// `(name, bit offset, width)` from each NativeBitfieldAttribute on `field`.
+fn bitfields(td: &windows_metadata::reader::TypeDef, field: &str) -> Vec<(String, i64, i64)> {
+    use windows_metadata::{HasAttributes, Value};
+
+    let field = td.fields().find(|f| f.name() == field).unwrap();
+    field
+        .attributes()
+        .filter(|a| a.ctor().parent().name() == "NativeBitfieldAttribute")
+        .map(|a| match a.value().as_slice() {
+            [
+                (_, Value::Utf8(name)),
+                (_, Value::I64(offset)),
+                (_, Value::I64(width)),
+            ] => (name.clone(), *offset, *width),
+            other => panic!("unexpected NativeBitfieldAttribute args: {other:?}"),
+        })
+        .collect()
+}
+
+#[test]
+fn bool_bitfields_share_one_byte() {
+    let index = open_index();
+
+    let flags = index.expect("RecordsTest", "BoolFlags");
+    assert_eq!(
+        field_types(&flags),
+        vec![("_bitfield".to_string(), Type::U8)]
+    );
+    assert_eq!(flags.class_layout().expect("layout").class_size(), 1);
+    assert_eq!(
+        bitfields(&flags, "_bitfield"),
+        vec![
+            ("a".to_string(), 0, 1),
+            ("b".to_string(), 1, 1),
+            ("c".to_string(), 2, 1),
+            ("d".to_string(), 3, 1),
+        ]
+    );
+}
+
+#[test]
+fn uint_bitfields_share_storage_unit() {
+    let index = open_index();
+
+    let mode = index.expect("RecordsTest", "Mode");
+    assert_eq!(
+        field_types(&mode),
+        vec![
+            ("_bitfield".to_string(), Type::U32),
+            ("count".to_string(), Type::I32),
+        ]
+    );
+    assert_eq!(mode.class_layout().expect("layout").class_size(), 8);
+    assert_eq!(
+        bitfields(&mode, "_bitfield"),
+        vec![
+            ("read".to_string(), 0, 1),
+            ("write".to_string(), 1, 1),
+            ("exec".to_string(), 2, 1),
+            ("owner".to_string(), 8, 8),
+        ]
+    );
+}
+
+#[test]
+fn mixed_width_bitfields_widen_their_storage() {
+    let index = open_index();
+
+    let mixed = index.expect("RecordsTest", "MixedBits");
+    assert_eq!(
+        field_types(&mixed),
+        vec![("_bitfield".to_string(), Type::U32)]
+    );
+    assert_eq!(mixed.class_layout().expect("layout").class_size(), 4);
+    assert_eq!(
+        bitfields(&mixed, "_bitfield"),
+        vec![
+            ("low".to_string(), 0, 4),
+            ("mid".to_string(), 4, 12),
+            ("high".to_string(), 16, 16),
+        ]
+    );
+}
+
+#[test]
+fn straddling_bitfields_cover_their_bytes() {
+    let index = open_index();
+
+    let packed = index.expect("RecordsTest", "PackedBits");
+    assert_eq!(
+        field_types(&packed),
+        vec![
+            ("tag".to_string(), Type::U8),
+            (
+                "_bitfield1".to_string(),
+                Type::ArrayFixed(Box::new(Type::U8), 2)
+            ),
+            (
+                "_bitfield2".to_string(),
+                Type::ArrayFixed(Box::new(Type::U8), 1)
+            ),
+        ]
+    );
+    assert_eq!(packed.class_layout().expect("layout").class_size(), 4);
+    // Offsets are relative to the storage field, as in win32metadata.
+    assert_eq!(
+        bitfields(&packed, "_bitfield1"),
+        vec![("low".to_string(), 0, 4), ("wide".to_string(), 4, 12)]
+    );
+    assert_eq!(
+        bitfields(&packed, "_bitfield2"),
+        vec![("top".to_string(), 0, 8)]
+    );
+}
+
+#[test]
+fn packed_structs_carry_their_packing() {
+    let index = open_index();
+
+    // (name, size, packing, fields): packing comes from the C alignment, so
+    // windows-bindgen emits `packed(N)` and nothing is re-padded.
+    for (name, size, packing, fields) in [
+        ("WireHeader", 7, 1, vec!["tag", "len", "flags"]),
+        ("PackedPair", 9, 1, vec!["a", "b"]),
+        ("Pack2", 6, 2, vec!["a", "b"]),
+    ] {
+        let td = index.expect("RecordsTest", name);
+        assert_eq!(field_names(&td), fields, "{name} fields");
+        let layout = td.class_layout().expect("packed struct layout");
+        assert_eq!(layout.class_size(), size, "{name} size");
+        assert_eq!(layout.packing_size(), packing, "{name} packing");
+    }
+}
+
+#[test]
+fn atomic_fields_use_their_value_type() {
+    let index = open_index();
+
+    let td = index.expect("RecordsTest", "AtomicCounter");
+    let fields: Vec<(String, Type)> = td
+        .fields()
+        .map(|f| (f.name().to_string(), f.ty()))
+        .collect();
+    assert_eq!(
+        fields,
+        vec![
+            ("tag".to_string(), Type::I8),
+            ("count".to_string(), Type::I32),
+            ("total".to_string(), Type::U64),
+            ("head".to_string(), Type::PtrMut(Box::new(Type::Void), 1)),
+            // Typedef value types fall back to a same-sized integer.
+            ("ticket".to_string(), Type::U16),
+        ]
+    );
+
+    // tag@0, count@4, total@8, head@16, ticket@24, padded to 8.
+    let layout = td.class_layout().expect("AtomicCounter layout");
+    assert_eq!(layout.class_size(), 32);
+}
+
+#[test]
+fn int128_fields_keep_c_size_and_alignment() {
+    let index = open_index();
+
+    let td = index.expect("RecordsTest", "Wide128");
+    let bytes16 = || Type::ArrayFixed(Box::new(Type::U8), 16);
+    // tag@0, value@16, uvalue@32: the byte arrays have alignment 1, so the
+    // gap after `tag` must be spelled out.
+    assert_eq!(
+        field_types(&td),
+        vec![
+            ("tag".to_string(), Type::I8),
+            (
+                "_padding0".to_string(),
+                Type::ArrayFixed(Box::new(Type::U8), 15)
+            ),
+            ("value".to_string(), bytes16()),
+            ("uvalue".to_string(), bytes16()),
+        ]
+    );
+    let layout = td.class_layout().expect("Wide128 layout");
+    assert_eq!(layout.class_size(), 48);
+    assert_eq!(layout.packing_size(), 16);
+}
+
+#[test]
+fn anonymous_struct_typedef_is_one_named_struct() {
+    let index = open_index();
+
+    assert_eq!(index.get("RecordsTest", "Extent").count(), 1);
+    let extent = index.expect("RecordsTest", "Extent");
+    assert_eq!(
+        field_types(&extent),
+        vec![
+            ("width".to_string(), Type::I32),
+            ("height".to_string(), Type::I32),
+            ("scale".to_string(), Type::F64),
+        ]
+    );
+    assert_eq!(extent.class_layout().expect("layout").class_size(), 16);
+
+    let area = index
+        .expect("RecordsTest", "Apis")
+        .methods()
+        .find(|m| m.name() == "extent_area")
+        .expect("extent_area");
+    assert_eq!(
+        area.signature(&[]).types,
+        vec![Type::PtrConst(
+            Box::new(Type::named("RecordsTest", "Extent")),
+            1
+        )]
+    );
+}
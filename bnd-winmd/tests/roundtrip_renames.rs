@@ -0,0 +1,76 @@
+//! Round-trip integration test: `[renames]` renames symbols consistently.
+
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use windows_metadata::Type;
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../tests/fixtures/renames")
+        .join(name)
+}
+
+static RENAMES_WINMD: LazyLock<Vec<u8>> = LazyLock::new(|| {
+    bnd_winmd::generate(&fixture("renames.toml")).expect("generate renames winmd")
+});
+
+fn open_index() -> windows_metadata::reader::TypeIndex {
+    let file = windows_metadata::reader::File::new(RENAMES_WINMD.clone()).expect("parse winmd");
+    windows_metadata::reader::TypeIndex::new(vec![file])
+}
+
+#[test]
+fn renamed_struct_is_used_by_param() {
+    let index = open_index();
+
+    assert!(index.contains("RenamesTest", "FileStat"));
+    assert!(!index.contains("RenamesTest", "stat_info"));
+
+    let apis = index.expect("RenamesTest", "Apis");
+    let get = apis
+        .methods()
+        .find(|m| m.name() == "GetFileStat")
+        .expect("GetFileStat");
+    assert_eq!(
+        get.signature(&[]).types,
+        vec![
+            Type::PtrMut(Box::new(Type::I8), 1),
+            Type::PtrMut(Box::new(Type::named("RenamesTest", "FileStat")), 1),
+        ]
+    );
+    // The P/Invoke still binds the C symbol.
+    assert_eq!(get.impl_map().expect("ImplMap").import_name(), "stat_file");
+}
+
+#[test]
+fn renamed_constant() {
+    let index = open_index();
+
+    let apis = index.expect("RenamesTest", "Apis");
+    let fields: Vec<&str> = apis.fields().map(|f| f.name()).collect();
+    assert_eq!(fields, vec!["MODE_DIR"]);
+}
+
+#[test]
+fn function_rename_collision_is_an_error() {
+    let err = bnd_winmd::generate(&fixture("function_collision.toml"))
+        .expect_err("stat_file and stat_reset collide");
+    let msg = format!("{err:#}");
+    assert!(msg.contains("collision"), "got: {msg}");
+    assert!(
+        msg.contains("`stat_file`") && msg.contains("`stat_reset`"),
+        "got: {msg}"
+    );
+}
+
+#[test]
+fn type_rename_collision_is_an_error() {
+    let err = bnd_winmd::generate(&fixture("type_collision.toml"))
+        .expect_err("stat_info and stat_kind collide");
+    let msg = format!("{err:#}");
+    assert!(
+        msg.contains("`stat_info`") && msg.contains("`stat_kind`"),
+        "got: {msg}"
+    );
+}
@@ -0,0 +1,100 @@
+//! Integration test: `generate_with_report` lists declarations that could
+//! not be extracted.
+
+use std::path::{Path, PathBuf};
+
+use bnd_winmd::model::DeclKind;
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../tests/fixtures/dropped")
+        .join(name)
+}
+
+fn methods(winmd: Vec<u8>, namespace: &str) -> Vec<String> {
+    let file = windows_metadata::reader::File::new(winmd).expect("parse winmd");
+    let index = windows_metadata::reader::TypeIndex::new(vec![file]);
+    index
+        .expect(namespace, "Apis")
+        .methods()
+        .map(|m| m.name().to_string())
+        .collect()
+}
+
+#[test]
+fn unsupported_declarations_are_reported() {
+    let (winmd, report) =
+        bnd_winmd::generate_with_report(&fixture("dropped.toml")).expect("generate dropped winmd");
+
+    assert!(!report.is_clean());
+    let dropped: Vec<(&str, DeclKind)> = report
+        .dropped("DroppedTest")
+        .map(|d| (d.name.as_str(), d.kind))
+        .collect();
+    assert_eq!(
+        dropped,
+        vec![
+            ("WideRecord", DeclKind::Struct),
+            ("wide_is_zero", DeclKind::Function),
+        ]
+    );
+    let function = report
+        .dropped("DroppedTest")
+        .find(|d| d.kind == DeclKind::Function)
+        .unwrap();
+    assert!(
+        function.reason.contains("parameter 'value'") && function.reason.contains("complex"),
+        "reason: {}",
+        function.reason
+    );
+
+    // Everything else is still emitted.
+    let file = windows_metadata::reader::File::new(winmd).expect("parse winmd");
+    let index = windows_metadata::reader::TypeIndex::new(vec![file]);
+    let apis = index.expect("DroppedTest", "Apis");
+    let methods: Vec<&str> = apis.methods().map(|m| m.name()).collect();
+    assert_eq!(methods, vec!["narrow_add"]);
+    assert!(index.get("DroppedTest", "WideRecord").next().is_none());
+}
+
+#[test]
+fn unsupported_typedef_is_void_by_default() {
+    let (winmd, report) = bnd_winmd::generate_with_report(&fixture("complex_int.toml"))
+        .expect("generate complex_int winmd");
+
+    assert!(report.is_clean(), "dropped: {report:#?}");
+    assert_eq!(
+        methods(winmd, "ComplexIntTest"),
+        ["cint_norm", "cint_make", "scalar_add"]
+    );
+}
+
+#[test]
+fn strict_signatures_drop_functions_using_unsupported_typedefs() {
+    let (winmd, report) =
+        bnd_winmd::generate_with_report(&fixture("strict.toml")).expect("generate strict winmd");
+
+    let dropped: Vec<(&str, DeclKind)> = report
+        .dropped("ComplexIntTest")
+        .map(|d| (d.name.as_str(), d.kind))
+        .collect();
+    assert_eq!(
+        dropped,
+        vec![
+            ("cint", DeclKind::Typedef),
+            ("cint_norm", DeclKind::Function),
+            ("cint_make", DeclKind::Function),
+        ]
+    );
+    let cint_norm = report
+        .dropped("ComplexIntTest")
+        .find(|d| d.name == "cint_norm")
+        .unwrap();
+    assert!(
+        cint_norm.reason.contains("parameter 'v'") && cint_norm.reason.contains("cint"),
+        "reason: {}",
+        cint_norm.reason
+    );
+
+    assert_eq!(methods(winmd, "ComplexIntTest"), ["scalar_add"]);
+}
@@ -0,0 +1,29 @@
+//! Integration test: generating a fixture twice yields a byte-identical
+//! winmd, including the config tables (`namespace_overrides`, `renames`,
+//! `param_directions`) that are read into hash maps.
+
+use std::path::Path;
+
+fn assert_reproducible(fixture: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../tests/fixtures")
+        .join(fixture);
+    let first = bnd_winmd::generate(&path).unwrap_or_else(|e| panic!("generate {fixture}: {e:#}"));
+    let second = bnd_winmd::generate(&path).unwrap_or_else(|e| panic!("generate {fixture}: {e:#}"));
+    assert!(first == second, "{fixture}: winmd differs between runs");
+}
+
+#[test]
+fn namespace_overrides_are_reproducible() {
+    assert_reproducible("overrides/overrides.toml");
+}
+
+#[test]
+fn renames_are_reproducible() {
+    assert_reproducible("renames/renames.toml");
+}
+
+#[test]
+fn function_config_tables_are_reproducible() {
+    assert_reproducible("functions/functions.toml");
+}
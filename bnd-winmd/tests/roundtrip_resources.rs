@@ -0,0 +1,65 @@
+//! Round-trip integration test: `resource_pairs` link handle typedefs and
+//! allocating functions to the function that releases them.
+
+use std::path::Path;
+use std::sync::LazyLock;
+
+use windows_metadata::{HasAttributes, Value};
+
+static RESOURCES_WINMD: LazyLock<Vec<u8>> = LazyLock::new(|| {
+    let path =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/resources/resources.toml");
+    bnd_winmd::generate(&path).expect("generate resources winmd")
+});
+
+fn open_index() -> windows_metadata::reader::TypeIndex {
+    let file = windows_metadata::reader::File::new(RESOURCES_WINMD.clone()).expect("parse winmd");
+    windows_metadata::reader::TypeIndex::new(vec![file])
+}
+
+/// The function name carried by attribute `name` on `row`.
+fn free_function<'a>(row: &impl HasAttributes<'a>, name: &str) -> Option<String> {
+    let attr = row.find_attribute(name)?;
+    match attr.value().as_slice() {
+        [(_, Value::Utf8(function))] => Some(function.clone()),
+        other => panic!("unexpected {name} value: {other:?}"),
+    }
+}
+
+#[test]
+fn handle_names_its_free_function() {
+    let index = open_index();
+    let bio = index.expect("ResourceTest", "BIO");
+    let free = free_function(&bio, "RAIIFreeAttribute").expect("RAIIFreeAttribute on BIO");
+    assert_eq!(free, "BIO_free");
+
+    // windows-bindgen resolves the name in the handle's namespace.
+    assert!(
+        index
+            .expect("ResourceTest", "Apis")
+            .methods()
+            .any(|m| m.name() == free),
+        "{free} is not a method of ResourceTest.Apis"
+    );
+}
+
+#[test]
+fn allocating_function_names_its_free_function() {
+    let index = open_index();
+    let apis = index.expect("ResourceTest", "Apis");
+    let method = |name: &str| {
+        apis.methods()
+            .find(|m| m.name() == name)
+            .unwrap_or_else(|| panic!("{name} not found"))
+    };
+
+    assert_eq!(
+        free_function(&method("opendir"), "FreeWithAttribute").as_deref(),
+        Some("closedir")
+    );
+    assert_eq!(free_function(&method("BIO_new"), "FreeWithAttribute"), None);
+    assert_eq!(
+        free_function(&index.expect("ResourceTest", "DIR"), "RAIIFreeAttribute"),
+        None
+    );
+}
@@ -0,0 +1,103 @@
+//! Round-trip integration test: structs that point at themselves emit once,
+//! with pointer fields naming the struct being emitted.
+
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use windows_metadata::Type;
+
+fn fixture() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/selfref/selfref.toml")
+}
+
+static SELFREF_WINMD: LazyLock<Vec<u8>> =
+    LazyLock::new(|| bnd_winmd::generate(&fixture()).expect("generate selfref winmd"));
+
+fn open_index() -> windows_metadata::reader::TypeIndex {
+    let file = windows_metadata::reader::File::new(SELFREF_WINMD.clone()).expect("parse winmd");
+    windows_metadata::reader::TypeIndex::new(vec![file])
+}
+
+fn field_types(index: &windows_metadata::reader::TypeIndex, name: &str) -> Vec<(String, Type)> {
+    index
+        .expect("SelfRefTest", name)
+        .fields()
+        .map(|f| (f.name().to_string(), f.ty()))
+        .collect()
+}
+
+fn ptr_to(name: &str) -> Type {
+    Type::PtrMut(Box::new(Type::named("SelfRefTest", name)), 1)
+}
+
+#[test]
+fn self_pointer_names_the_struct() {
+    let index = open_index();
+    let nodes = index.get("SelfRefTest", "node").count();
+    assert_eq!(nodes, 1, "node should be emitted exactly once");
+    assert_eq!(
+        field_types(&index, "node"),
+        [
+            ("next".to_string(), ptr_to("node")),
+            ("val".to_string(), Type::I32),
+        ]
+    );
+
+    let head = index
+        .expect("SelfRefTest", "Apis")
+        .methods()
+        .find(|m| m.name() == "list_length")
+        .expect("list_length")
+        .signature(&[]);
+    assert_eq!(
+        head.types,
+        [Type::PtrConst(
+            Box::new(Type::named("SelfRefTest", "node")),
+            1
+        )]
+    );
+}
+
+#[test]
+fn typedef_and_mutual_pointers_resolve() {
+    let index = open_index();
+    let tree: Vec<(String, Type)> = field_types(&index, "tree");
+    assert_eq!(tree[0].0, "left");
+    assert_eq!(tree[1].0, "right");
+    assert_eq!(tree[2], ("key".to_string(), Type::I32));
+
+    assert_eq!(
+        field_types(&index, "child"),
+        [("owner".to_string(), ptr_to("parent"))]
+    );
+    assert_eq!(
+        field_types(&index, "parent"),
+        [
+            ("first".to_string(), Type::named("SelfRefTest", "child")),
+            ("rest".to_string(), ptr_to("child")),
+        ]
+    );
+}
+
+#[test]
+fn by_value_self_reference_in_model_json_is_rejected() {
+    let cfg = bnd_winmd::config::load_config(&fixture()).expect("load config");
+    let dump = bnd_winmd::dump_model(&cfg, fixture().parent().unwrap()).expect("dump model");
+    let mut model: serde_json::Value = serde_json::from_str(&dump).expect("dump is valid JSON");
+
+    // Turn `struct node *next` into `struct node next`.
+    let node = model["partitions"][0]["structs"]
+        .as_array_mut()
+        .unwrap()
+        .iter_mut()
+        .find(|s| s["name"] == "node")
+        .expect("node in dump");
+    node["fields"][0]["ty"] = serde_json::json!({ "Named": { "name": "node", "resolved": null } });
+
+    let err = bnd_winmd::emit_from_model_json(&model.to_string(), "SelfRefTest")
+        .expect_err("node contains itself");
+    assert!(
+        format!("{err:#}").contains("`node` contains itself by value (node -> node)"),
+        "{err:#}"
+    );
+}
@@ -0,0 +1,53 @@
+//! Round-trip integration test: partitions sharing a namespace are merged
+//! into one `Apis` class.
+
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../tests/fixtures/shared")
+        .join(name)
+}
+
+static SHARED_WINMD: LazyLock<Vec<u8>> =
+    LazyLock::new(|| bnd_winmd::generate(&fixture("shared.toml")).expect("generate shared winmd"));
+
+fn open_index() -> windows_metadata::reader::TypeIndex {
+    let file = windows_metadata::reader::File::new(SHARED_WINMD.clone()).expect("parse winmd");
+    windows_metadata::reader::TypeIndex::new(vec![file])
+}
+
+#[test]
+fn one_apis_class_per_namespace() {
+    let index = open_index();
+    let apis = index
+        .types()
+        .filter(|td| td.namespace() == "SharedTest" && td.name() == "Apis")
+        .count();
+    assert_eq!(apis, 1);
+}
+
+#[test]
+fn apis_holds_union_of_functions() {
+    let index = open_index();
+    let mut methods: Vec<String> = index
+        .expect("SharedTest", "Apis")
+        .methods()
+        .map(|m| m.name().to_string())
+        .collect();
+    methods.sort();
+    assert_eq!(methods, ["io_read", "io_write", "net_send", "shared_init"]);
+}
+
+#[test]
+fn apis_holds_union_of_constants() {
+    let index = open_index();
+    let mut constants: Vec<String> = index
+        .expect("SharedTest", "Apis")
+        .fields()
+        .map(|f| f.name().to_string())
+        .collect();
+    constants.sort();
+    assert_eq!(constants, ["IO_BUFSIZE", "NET_PORT", "SHARED_VERSION"]);
+}
@@ -3,6 +3,8 @@
 use std::path::Path;
 use std::sync::LazyLock;
 
+use windows_metadata::{HasAttributes, Value};
+
 static SIMPLE_WINMD: LazyLock<Vec<u8>> = LazyLock::new(|| {
     let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/simple/simple.toml");
     bnd_winmd::generate(&path).expect("generate simple winmd")
@@ -93,6 +95,25 @@ fn roundtrip_struct_fields() {
     assert!(fields.contains(&"height".to_string()));
 }
 
+#[test]
+fn roundtrip_struct_size_attribute() {
+    let index = open_index();
+
+    let size_align = |name: &str| {
+        let attr = index
+            .expect("SimpleTest", name)
+            .find_attribute("NativeStructSizeAttribute")
+            .unwrap_or_else(|| panic!("{name} has no NativeStructSizeAttribute"));
+        match attr.value().as_slice() {
+            [(_, Value::I64(size)), (_, Value::I64(align))] => (*size, *align),
+            other => panic!("unexpected NativeStructSizeAttribute value: {other:?}"),
+        }
+    };
+    assert_eq!(size_align("Rect"), (16, 4));
+    // Pointer first: 8-byte aligned on 64-bit targets.
+    assert_eq!(size_align("Widget").1, size_of::<usize>() as i64);
+}
+
 #[test]
 fn roundtrip_union_fields() {
     let index = open_index();
@@ -214,11 +235,21 @@ fn roundtrip_function_params() {
         .find(|m| m.name() == "create_widget")
         .expect("create_widget not found");
 
-    let params: Vec<String> = create.params().map(|p| p.name().to_string()).collect();
-    // Should have a return param + 3 params, or just 3 named params depending on emit
-    assert!(
-        params.len() >= 3,
-        "create_widget should have at least 3 params, got: {params:?}"
+    let params: Vec<(String, windows_metadata::ParamAttributes)> = create
+        .params()
+        .map(|p| (p.name().to_string(), p.flags()))
+        .collect();
+    assert_eq!(
+        params,
+        vec![
+            ("name".to_string(), windows_metadata::ParamAttributes::In),
+            (
+                "bounds".to_string(),
+                windows_metadata::ParamAttributes::default()
+            ),
+            // Trailing output pointer of a function returning an int.
+            ("out".to_string(), windows_metadata::ParamAttributes::Out),
+        ]
     );
 }
 
@@ -0,0 +1,68 @@
+//! Round-trip integration test: declaration sites survive as
+//! `SourceLocationAttribute`s when `emit_source_locations = true`.
+
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use windows_metadata::{HasAttributes, Value};
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../tests/fixtures/multi")
+        .join(name)
+}
+
+static SOURCES_WINMD: LazyLock<Vec<u8>> = LazyLock::new(|| {
+    bnd_winmd::generate(&fixture("source_locations.toml")).expect("generate winmd")
+});
+
+fn open_index(winmd: &[u8]) -> windows_metadata::reader::TypeIndex {
+    let file = windows_metadata::reader::File::new(winmd.to_vec()).expect("parse winmd");
+    windows_metadata::reader::TypeIndex::new(vec![file])
+}
+
+fn source<'a>(row: &impl HasAttributes<'a>) -> Option<(String, u32)> {
+    let attr = row.find_attribute("SourceLocationAttribute")?;
+    match attr.value().as_slice() {
+        [(_, Value::Utf8(file)), (_, Value::U32(line))] => Some((file.clone(), *line)),
+        other => panic!("unexpected SourceLocationAttribute value: {other:?}"),
+    }
+}
+
+#[test]
+fn struct_points_at_its_header() {
+    let index = open_index(&SOURCES_WINMD);
+
+    let rect = index.expect("MultiTest.Types", "Rect");
+    assert_eq!(source(&rect), Some(("types.h".to_string(), 11)));
+
+    let widget = index.expect("MultiTest.Widgets", "Widget");
+    assert_eq!(source(&widget), Some(("widget.h".to_string(), 5)));
+}
+
+#[test]
+fn enum_typedef_and_function_have_locations() {
+    let index = open_index(&SOURCES_WINMD);
+
+    let color = index.expect("MultiTest.Types", "Color");
+    assert_eq!(source(&color), Some(("types.h".to_string(), 4)));
+
+    let compare = index.expect("MultiTest.Types", "CompareFunc");
+    assert_eq!(source(&compare), Some(("types.h".to_string(), 19)));
+
+    let create = index
+        .expect("MultiTest.Widgets", "Apis")
+        .methods()
+        .find(|m| m.name() == "create_widget")
+        .expect("create_widget");
+    assert_eq!(source(&create), Some(("widget.h".to_string(), 12)));
+}
+
+#[test]
+fn locations_are_off_by_default() {
+    let winmd = bnd_winmd::generate(&fixture("multi.toml")).expect("generate winmd");
+    let index = open_index(&winmd);
+
+    let rect = index.expect("MultiTest.Types", "Rect");
+    assert_eq!(source(&rect), None);
+}
@@ -0,0 +1,37 @@
+//! Integration test: `stats` counts the extracted declarations without
+//! emitting a winmd.
+
+use std::path::Path;
+
+use bnd_winmd::model::PartitionStats;
+
+#[test]
+fn simple_fixture_counts() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/simple/simple.toml");
+    let cfg = bnd_winmd::config::load_config(&path).expect("load config");
+    let stats = bnd_winmd::stats(&cfg, path.parent().unwrap()).expect("stats");
+
+    assert_eq!(
+        stats.partitions,
+        vec![PartitionStats {
+            namespace: "SimpleTest".to_string(),
+            // Rect, Widget, Value, NetAddr, its NetAddr_addr union and PackedHeader.
+            structs: 6,
+            enums: 1,
+            functions: 3,
+            typedefs: 1,
+            constants: 3,
+            variables: 0,
+            dropped: 0,
+        }]
+    );
+    // Every struct, enum and typedef is registered.
+    assert_eq!(stats.registry_types, 8);
+
+    let text = stats.to_string();
+    assert!(
+        text.starts_with("SimpleTest: 6 structs, 1 enums, 3 functions"),
+        "{text}"
+    );
+    assert!(text.ends_with("type registry: 8 types"), "{text}");
+}
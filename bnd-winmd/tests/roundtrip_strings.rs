@@ -0,0 +1,73 @@
+//! Round-trip integration test: `string_pointers` emits `char *` as the
+//! win32metadata `PSTR` / `PCSTR` string types.
+
+use std::path::Path;
+use std::sync::LazyLock;
+
+use windows_metadata::Type;
+
+static STRINGS_WINMD: LazyLock<Vec<u8>> = LazyLock::new(|| {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/strings/strings.toml");
+    bnd_winmd::generate(&path).expect("generate strings winmd")
+});
+
+fn signature(name: &str) -> (Type, Vec<Type>) {
+    let file = windows_metadata::reader::File::new(STRINGS_WINMD.clone()).expect("parse winmd");
+    let index = windows_metadata::reader::TypeIndex::new(vec![file]);
+    let sig = index
+        .expect("StringTest", "Apis")
+        .methods()
+        .find(|m| m.name() == name)
+        .unwrap_or_else(|| panic!("{name} not found"))
+        .signature(&[]);
+    (sig.return_type, sig.types)
+}
+
+#[test]
+fn char_pointers_become_string_types() {
+    let (ret, params) = signature("str_copy");
+    assert_eq!(ret, Type::I32);
+    assert_eq!(
+        params,
+        [
+            Type::named("Windows.Win32.Foundation", "PSTR"),
+            Type::named("Windows.Win32.Foundation", "PCSTR"),
+            Type::U64,
+        ]
+    );
+
+    let (ret, _) = signature("str_version");
+    assert_eq!(ret, Type::named("Windows.Win32.Foundation", "PCSTR"));
+}
+
+#[test]
+fn byte_buffers_and_typedefs_are_unchanged() {
+    let (_, params) = signature("bytes_sum");
+    assert_eq!(params[0], Type::PtrMut(Box::new(Type::U8), 1));
+
+    let (_, params) = signature("str_take");
+    assert_eq!(params, [Type::named("StringTest", "owned_str")]);
+}
+
+/// `MappingFlags` of the ImplMap row importing `name`.
+fn impl_map_flags(name: &str) -> u16 {
+    bnd_winmd::metadata::impl_map_flags(&STRINGS_WINMD)
+        .expect("read ImplMap rows")
+        .into_iter()
+        .find(|(import, _)| import == name)
+        .unwrap_or_else(|| panic!("no ImplMap for {name}"))
+        .1
+}
+
+#[test]
+fn impl_map_records_the_string_charset() {
+    use bnd_winmd::metadata::{CHARSET_ANSI, CHARSET_UNICODE};
+    const CHARSET_MASK: u16 = 0x6;
+
+    assert_eq!(impl_map_flags("str_copy") & CHARSET_MASK, CHARSET_ANSI);
+    assert_eq!(impl_map_flags("str_version") & CHARSET_MASK, CHARSET_ANSI);
+    assert_eq!(impl_map_flags("wstr_len") & CHARSET_MASK, CHARSET_UNICODE);
+    // Mixed widths, and byte buffers that aren't strings, get neither.
+    assert_eq!(impl_map_flags("str_widen") & CHARSET_MASK, 0);
+    assert_eq!(impl_map_flags("bytes_sum") & CHARSET_MASK, 0);
+}
@@ -0,0 +1,80 @@
+//! Round-trip integration test: `strip_prefix` renames symbols consistently.
+
+use std::path::Path;
+use std::sync::LazyLock;
+
+use windows_metadata::Type;
+
+static STRIP_WINMD: LazyLock<Vec<u8>> = LazyLock::new(|| {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/strip/strip.toml");
+    bnd_winmd::generate(&path).expect("generate strip winmd")
+});
+
+fn open_index() -> windows_metadata::reader::TypeIndex {
+    let file = windows_metadata::reader::File::new(STRIP_WINMD.clone()).expect("parse winmd");
+    windows_metadata::reader::TypeIndex::new(vec![file])
+}
+
+#[test]
+fn stripped_method_keeps_original_import_name() {
+    let index = open_index();
+
+    let apis = index.expect("StripTest.Evp", "Apis");
+    let methods: Vec<String> = apis.methods().map(|m| m.name().to_string()).collect();
+    assert!(
+        methods.contains(&"sha256".to_string()),
+        "methods: {methods:?}"
+    );
+    assert!(
+        methods.contains(&"MD_size".to_string()),
+        "methods: {methods:?}"
+    );
+    assert!(!methods.contains(&"EVP_sha256".to_string()));
+
+    let sha256 = apis.methods().find(|m| m.name() == "sha256").unwrap();
+    let impl_map = sha256.impl_map().expect("sha256 should be a P/Invoke");
+    assert_eq!(impl_map.import_name(), "EVP_sha256");
+}
+
+#[test]
+fn stripped_types_and_constants() {
+    let index = open_index();
+
+    let md = index.expect("StripTest.Evp", "MD");
+    assert_eq!(md.fields().count(), 2);
+
+    let apis = index.expect("StripTest.Evp", "Apis");
+    let fields: Vec<String> = apis.fields().map(|f| f.name().to_string()).collect();
+    assert_eq!(fields, vec!["MAX_MD_SIZE"]);
+}
+
+#[test]
+fn cross_partition_reference_follows_rename() {
+    let index = open_index();
+
+    let apis = index.expect("StripTest.Digest", "Apis");
+    let init = apis
+        .methods()
+        .find(|m| m.name() == "digest_init")
+        .expect("digest_init");
+    let sig = init.signature(&[]);
+    assert_eq!(
+        sig.types,
+        vec![Type::PtrMut(
+            Box::new(Type::named("StripTest.Evp", "MD")),
+            1
+        )]
+    );
+}
+
+#[test]
+fn collision_after_stripping_is_an_error() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/strip/collision.toml");
+    let err = bnd_winmd::generate(&path).expect_err("lib_open and open collide");
+    let msg = format!("{err:#}");
+    assert!(msg.contains("collision"), "got: {msg}");
+    assert!(
+        msg.contains("lib_open") && msg.contains("`open`"),
+        "got: {msg}"
+    );
+}
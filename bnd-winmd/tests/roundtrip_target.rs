@@ -0,0 +1,125 @@
+//! Round-trip integration test: a top-level `target` is parsed for and
+//! recorded in the winmd as a `TargetAttribute`, and decides which side of
+//! an `#ifdef __x86_64__` is extracted.
+
+use std::path::{Path, PathBuf};
+
+use windows_metadata::{HasAttributes, Type, Value};
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../tests/fixtures/simple")
+        .join(name)
+}
+
+/// `(triple, data_model)` of the winmd's `TargetAttribute`, if any.
+fn target(winmd: Vec<u8>) -> Option<(String, String)> {
+    let file = windows_metadata::reader::File::new(winmd).expect("parse winmd");
+    let index = windows_metadata::reader::TypeIndex::new(vec![file]);
+    let holder = index
+        .get("SimpleTest", bnd_winmd::emit::TARGET_TYPE)
+        .next()?;
+    let attr = holder
+        .find_attribute("TargetAttribute")
+        .expect("TargetAttribute");
+    match attr.value().as_slice() {
+        [(_, Value::Utf8(triple)), (_, Value::Utf8(data_model))] => {
+            Some((triple.clone(), data_model.clone()))
+        }
+        other => panic!("unexpected TargetAttribute value: {other:?}"),
+    }
+}
+
+#[test]
+fn target_is_recorded_with_its_data_model() {
+    let winmd = bnd_winmd::generate(&fixture("target.toml")).expect("generate winmd");
+    assert_eq!(
+        target(winmd),
+        Some(("x86_64-pc-windows-msvc".to_string(), "llp64".to_string()))
+    );
+}
+
+#[test]
+fn target_is_passed_to_clang_and_shown_in_stats() {
+    let path = fixture("target.toml");
+    let cfg = bnd_winmd::config::load_config(&path).expect("load config");
+    assert!(
+        cfg.partition[0]
+            .clang_args
+            .contains(&"--target=x86_64-pc-windows-msvc".to_string())
+    );
+
+    let stats = bnd_winmd::stats(&cfg, path.parent().unwrap()).expect("stats");
+    let text = stats.to_string();
+    assert!(
+        text.starts_with("target: x86_64-pc-windows-msvc (llp64)\n"),
+        "{text}"
+    );
+}
+
+#[test]
+fn data_model_must_match_the_target() {
+    use bnd_winmd::config::DataModel;
+
+    let path = fixture("target.toml");
+    let base_dir = path.parent().unwrap();
+    let mut cfg = bnd_winmd::config::load_config(&path).expect("load config");
+    cfg.data_model = Some(DataModel::Llp64);
+    bnd_winmd::generate_from_config(&cfg, base_dir).expect("matching data_model");
+
+    cfg.data_model = Some(DataModel::Lp64);
+    let err = bnd_winmd::generate_from_config(&cfg, base_dir)
+        .expect_err("lp64 contradicts a Windows target");
+    let message = format!("{err:#}");
+    assert!(
+        message.contains("data_model `lp64` does not match target `x86_64-pc-windows-msvc`"),
+        "{message}"
+    );
+}
+
+#[test]
+fn no_target_attribute_by_default() {
+    let winmd = bnd_winmd::generate(&fixture("simple.toml")).expect("generate winmd");
+    assert_eq!(target(winmd), None);
+}
+
+/// `(name, type)` of each field of `ArchCondTest.Context`.
+fn context_fields(cfg: &bnd_winmd::config::Config) -> Vec<(String, Type)> {
+    let base_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/archcond");
+    let winmd = bnd_winmd::generate_from_config(cfg, &base_dir).expect("generate winmd");
+    let file = windows_metadata::reader::File::new(winmd).expect("parse winmd");
+    let index = windows_metadata::reader::TypeIndex::new(vec![file]);
+    index
+        .expect("ArchCondTest", "Context")
+        .fields()
+        .map(|f| (f.name().to_string(), f.ty()))
+        .collect()
+}
+
+#[test]
+fn target_selects_the_preprocessor_branch() {
+    let path =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/archcond/archcond.toml");
+    let mut cfg = bnd_winmd::config::load_config(&path).expect("load config");
+    assert_eq!(context_fields(&cfg), vec![("rip".to_string(), Type::U64)]);
+
+    cfg.partition[0].clang_args = vec!["--target=aarch64-unknown-linux-gnu".to_string()];
+    assert_eq!(context_fields(&cfg), vec![("pc".to_string(), Type::U32)]);
+}
+
+#[test]
+fn host_target_is_passed_without_one_configured() {
+    let path =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/archcond/archcond.toml");
+    let mut cfg = bnd_winmd::config::load_config(&path).expect("load config");
+    cfg.partition[0].clang_args.clear();
+    let host = format!("--target={}", bnd_winmd::config::host_triple());
+    assert!(cfg.partition[0].clang_args().contains(&host));
+
+    let expected = if cfg!(target_arch = "x86_64") {
+        ("rip".to_string(), Type::U64)
+    } else {
+        ("pc".to_string(), Type::U32)
+    };
+    assert_eq!(context_fields(&cfg), vec![expected]);
+}
@@ -0,0 +1,140 @@
+//! Round-trip integration test: a 32-bit `target` lays structs out with
+//! 4-byte pointers and `long`, whatever the host.
+
+use std::path::Path;
+use std::sync::LazyLock;
+
+use windows_metadata::{HasAttributes, Type, Value};
+
+static TARGET32_WINMD: LazyLock<Vec<u8>> = LazyLock::new(|| {
+    let path =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/target32/target32.toml");
+    bnd_winmd::generate(&path).expect("generate target32 winmd")
+});
+
+fn open_index() -> windows_metadata::reader::TypeIndex {
+    let file = windows_metadata::reader::File::new(TARGET32_WINMD.clone()).expect("parse winmd");
+    windows_metadata::reader::TypeIndex::new(vec![file])
+}
+
+/// The `value__` type and the variant values of enum `namespace.name`.
+fn enum_fields(
+    index: &windows_metadata::reader::TypeIndex,
+    namespace: &str,
+    name: &str,
+) -> (Type, Vec<(String, Value)>) {
+    let mut storage = None;
+    let mut variants = Vec::new();
+    for field in index.expect(namespace, name).fields() {
+        match field.constant() {
+            Some(constant) => variants.push((field.name().to_string(), constant.value())),
+            None => storage = Some(field.ty()),
+        }
+    }
+    (storage.expect("value__ field"), variants)
+}
+
+#[test]
+fn pointer_struct_has_32_bit_layout() {
+    let index = open_index();
+    let node = index.expect("Target32Test", "Node");
+
+    let fields: Vec<(String, Type)> = node
+        .fields()
+        .map(|f| (f.name().to_string(), f.ty()))
+        .collect();
+    assert_eq!(
+        fields,
+        [
+            (
+                "next".to_string(),
+                Type::PtrMut(Box::new(Type::named("Target32Test", "Node")), 1)
+            ),
+            ("value".to_string(), Type::I32),
+            ("data".to_string(), Type::PtrMut(Box::new(Type::Void), 1)),
+            ("id".to_string(), Type::I64),
+        ]
+    );
+
+    // i686 aligns `long long` to 4 inside structs: 4 + 4 + 4 + 8.
+    let layout = node.class_layout().expect("Node layout");
+    assert_eq!(layout.class_size(), 20);
+    assert_eq!(layout.packing_size(), 4);
+}
+
+/// `size_t` is `unsigned int` on i686, but still maps to `usize`, and
+/// lays out as 4 bytes.
+#[test]
+fn size_t_is_pointer_sized_on_32_bit_targets() {
+    let index = open_index();
+    let buffer = index.expect("Target32Test", "Buffer");
+
+    let fields: Vec<(String, Type)> = buffer
+        .fields()
+        .map(|f| (f.name().to_string(), f.ty()))
+        .collect();
+    assert_eq!(
+        fields,
+        [
+            ("data".to_string(), Type::PtrMut(Box::new(Type::Void), 1)),
+            ("len".to_string(), Type::USize),
+        ]
+    );
+    let layout = buffer.class_layout().expect("Buffer layout");
+    assert_eq!(layout.class_size(), 8);
+
+    let fill = index
+        .expect("Target32Test", "Apis")
+        .methods()
+        .find(|m| m.name() == "buffer_fill")
+        .expect("buffer_fill");
+    let sig = fill.signature(&[]);
+    assert_eq!(sig.return_type, Type::USize);
+    assert_eq!(sig.types[1], Type::USize);
+}
+
+#[test]
+fn target_records_the_ilp32_data_model() {
+    let index = open_index();
+    let holder = index.expect("Target32Test", bnd_winmd::emit::TARGET_TYPE);
+    let attr = holder
+        .find_attribute("TargetAttribute")
+        .expect("TargetAttribute");
+    match attr.value().as_slice() {
+        [(_, Value::Utf8(triple)), (_, Value::Utf8(data_model))] => {
+            assert_eq!(triple, "i686-unknown-linux-gnu");
+            assert_eq!(data_model, "ilp32");
+        }
+        other => panic!("unexpected TargetAttribute value: {other:?}"),
+    }
+
+    let sum = index
+        .expect("Target32Test", "Apis")
+        .methods()
+        .find(|m| m.name() == "node_sum")
+        .expect("node_sum");
+    assert_eq!(sum.signature(&[]).return_type, Type::I32);
+}
+
+#[test]
+fn pointer_sized_enum_is_stored_as_32_bit() {
+    let expected = (
+        Type::U32,
+        vec![
+            ("WIDTH_NARROW".to_string(), Value::U32(1)),
+            ("WIDTH_WIDE".to_string(), Value::U32(2)),
+        ],
+    );
+    assert_eq!(
+        enum_fields(&open_index(), "Target32Test", "Width"),
+        expected
+    );
+
+    // Without a top-level `target`, the partition's own `--target` decides.
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../tests/fixtures/target32/partition_target.toml");
+    let winmd = bnd_winmd::generate(&path).expect("generate target32 args winmd");
+    let file = windows_metadata::reader::File::new(winmd).expect("parse winmd");
+    let index = windows_metadata::reader::TypeIndex::new(vec![file]);
+    assert_eq!(enum_fields(&index, "Target32Args", "Width"), expected);
+}
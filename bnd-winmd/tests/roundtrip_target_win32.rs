@@ -0,0 +1,70 @@
+//! Round-trip integration test: 32-bit Windows keeps `long` and pointers
+//! 4 bytes, like other 32-bit targets, rather than taking the LLP64 widths
+//! of 64-bit Windows.
+
+use std::path::Path;
+use std::sync::LazyLock;
+
+use windows_metadata::{HasAttributes, Type, Value};
+
+static WIN32_WINMD: LazyLock<Vec<u8>> = LazyLock::new(|| {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/target32/win32.toml");
+    bnd_winmd::generate(&path).expect("generate win32 winmd")
+});
+
+fn open_index() -> windows_metadata::reader::TypeIndex {
+    let file = windows_metadata::reader::File::new(WIN32_WINMD.clone()).expect("parse winmd");
+    windows_metadata::reader::TypeIndex::new(vec![file])
+}
+
+/// `size_t` is 4 bytes on i686 Windows, so it maps to `usize` instead of
+/// being rejected as a mismatched pointer-sized integer.
+#[test]
+fn size_t_is_pointer_sized_on_32_bit_windows() {
+    let index = open_index();
+    let buffer = index.expect("Win32Test", "Buffer");
+
+    let fields: Vec<(String, Type)> = buffer
+        .fields()
+        .map(|f| (f.name().to_string(), f.ty()))
+        .collect();
+    assert_eq!(
+        fields,
+        [
+            ("data".to_string(), Type::PtrMut(Box::new(Type::Void), 1)),
+            ("len".to_string(), Type::USize),
+        ]
+    );
+    let layout = buffer.class_layout().expect("Buffer layout");
+    assert_eq!(layout.class_size(), 8);
+
+    let fill = index
+        .expect("Win32Test", "Apis")
+        .methods()
+        .find(|m| m.name() == "buffer_fill")
+        .expect("buffer_fill");
+    assert_eq!(fill.signature(&[]).return_type, Type::USize);
+}
+
+#[test]
+fn target_records_the_win32_data_model() {
+    let index = open_index();
+    let holder = index.expect("Win32Test", bnd_winmd::emit::TARGET_TYPE);
+    let attr = holder
+        .find_attribute("TargetAttribute")
+        .expect("TargetAttribute");
+    match attr.value().as_slice() {
+        [(_, Value::Utf8(triple)), (_, Value::Utf8(data_model))] => {
+            assert_eq!(triple, "i686-pc-windows-msvc");
+            assert_eq!(data_model, "win32");
+        }
+        other => panic!("unexpected TargetAttribute value: {other:?}"),
+    }
+
+    let sum = index
+        .expect("Win32Test", "Apis")
+        .methods()
+        .find(|m| m.name() == "node_sum")
+        .expect("node_sum");
+    assert_eq!(sum.signature(&[]).return_type, Type::I32);
+}
@@ -0,0 +1,82 @@
+//! Integration test: the emit phase logs each TypeDef's layout decision
+//! under a span naming the declaration.
+
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// A `MakeWriter` collecting everything written into a shared buffer.
+#[derive(Clone, Default)]
+struct Capture(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Capture {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> tracing_subscriber::fmt::MakeWriter<'a> for Capture {
+    type Writer = Capture;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+/// Generate the simple fixture and return the emit phase's debug log.
+fn emit_log() -> String {
+    let capture = Capture::default();
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::DEBUG)
+        .with_ansi(false)
+        .with_writer(capture.clone())
+        .finish();
+
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/simple/simple.toml");
+    tracing::subscriber::with_default(subscriber, || {
+        bnd_winmd::generate(&path).expect("generate simple winmd")
+    });
+
+    let bytes = capture.0.lock().unwrap().clone();
+    String::from_utf8(bytes).expect("utf-8 log")
+}
+
+/// The log line of the struct `name`'s layout decision.
+fn struct_line<'a>(log: &'a str, name: &str) -> &'a str {
+    log.lines()
+        .find(|line| line.contains("emitted struct") && line.contains(&format!("name={name} ")))
+        .unwrap_or_else(|| panic!("no layout logged for {name} in:\n{log}"))
+}
+
+#[test]
+fn struct_layout_decisions_are_logged() {
+    let log = emit_log();
+
+    let rect = struct_line(&log, "Rect");
+    assert!(rect.contains("struct{name=Rect}"), "{rect}");
+    assert!(rect.contains("layout=\"sequential\""), "{rect}");
+    assert!(rect.contains("size=16"), "{rect}");
+    assert!(rect.contains("packing=4"), "{rect}");
+
+    let value = struct_line(&log, "Value");
+    assert!(value.contains("layout=\"explicit\""), "{value}");
+}
+
+#[test]
+fn methods_are_logged_in_their_partition() {
+    let log = emit_log();
+
+    assert!(
+        log.lines()
+            .any(|line| line.contains("partition{namespace=SimpleTest}")
+                && line.contains("method{name=")
+                && line.contains("emitted function")),
+        "no method span in:\n{log}"
+    );
+    assert!(log.contains("emitted winmd"), "{log}");
+}
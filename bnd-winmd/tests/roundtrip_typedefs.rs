@@ -0,0 +1,156 @@
+//! Round-trip integration test: typedef-of-typedef chains resolve to the
+//! nearest emitted alias, or to the terminal type.
+
+use std::path::Path;
+use std::sync::LazyLock;
+
+use windows_metadata::Type;
+use windows_metadata::reader::TypeCategory;
+
+static TYPEDEFS_WINMD: LazyLock<Vec<u8>> = LazyLock::new(|| {
+    let path =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/typedefs/typedefs.toml");
+    bnd_winmd::generate(&path).expect("generate typedefs winmd")
+});
+
+fn open_index() -> windows_metadata::reader::TypeIndex {
+    let file = windows_metadata::reader::File::new(TYPEDEFS_WINMD.clone()).expect("parse winmd");
+    windows_metadata::reader::TypeIndex::new(vec![file])
+}
+
+fn signature(
+    index: &windows_metadata::reader::TypeIndex,
+    name: &str,
+) -> windows_metadata::Signature {
+    index
+        .expect("TypedefsTest", "Apis")
+        .methods()
+        .find(|m| m.name() == name)
+        .unwrap_or_else(|| panic!("{name} not found"))
+        .signature(&[])
+}
+
+fn typedef_value(index: &windows_metadata::reader::TypeIndex, name: &str) -> Type {
+    index
+        .expect("TypedefsTest", name)
+        .fields()
+        .find(|f| f.name() == "Value")
+        .unwrap_or_else(|| panic!("{name} has no Value field"))
+        .ty()
+}
+
+#[test]
+fn chain_through_unextracted_aliases_resolves_to_terminal() {
+    let index = open_index();
+
+    // object_id_t → mid_id_t → base_id_t → unsigned int
+    assert_eq!(typedef_value(&index, "object_id_t"), Type::U32);
+    assert_eq!(
+        signature(&index, "next_id").return_type,
+        Type::named("TypedefsTest", "object_id_t")
+    );
+    assert_eq!(signature(&index, "release_id").types, vec![Type::U32]);
+    assert!(index.get("TypedefsTest", "mid_id_t").next().is_none());
+}
+
+#[test]
+fn chain_of_emitted_aliases_keeps_each_link() {
+    let index = open_index();
+
+    assert_eq!(
+        typedef_value(&index, "Pin"),
+        Type::named("TypedefsTest", "Anchor")
+    );
+    assert_eq!(
+        typedef_value(&index, "Anchor"),
+        Type::named("TypedefsTest", "Origin")
+    );
+    assert_eq!(
+        signature(&index, "make_pin").return_type,
+        Type::named("TypedefsTest", "Pin")
+    );
+}
+
+#[test]
+fn overlong_chain_falls_back_to_canonical_type() {
+    let index = open_index();
+
+    assert_eq!(signature(&index, "deep_value").return_type, Type::U64);
+}
+
+#[test]
+fn callback_typedefs_are_delegates() {
+    let index = open_index();
+
+    for name in ["read_cb", "write_fn", "read_cb_alias"] {
+        let td = index.expect("TypedefsTest", name);
+        assert_eq!(td.category(), TypeCategory::Delegate, "{name}");
+        let invoke = td
+            .methods()
+            .find(|m| m.name() == "Invoke")
+            .unwrap_or_else(|| panic!("{name} has no Invoke"));
+        let sig = invoke.signature(&[]);
+        assert_eq!(sig.return_type, Type::I32, "{name}");
+        assert_eq!(sig.types.len(), 2, "{name}");
+    }
+
+    // `write_fn *` is the delegate itself, not a pointer to it.
+    assert_eq!(
+        signature(&index, "set_callbacks").types,
+        vec![
+            Type::named("TypedefsTest", "read_cb"),
+            Type::named("TypedefsTest", "write_fn"),
+            Type::named("TypedefsTest", "read_cb_alias"),
+        ]
+    );
+}
+
+#[test]
+fn struct_callback_fields_reference_delegates() {
+    let index = open_index();
+
+    for name in ["alloc_cb", "free_fn"] {
+        let td = index.expect("TypedefsTest", name);
+        assert_eq!(td.category(), TypeCategory::Delegate, "{name}");
+    }
+
+    let fields: Vec<(String, Type)> = index
+        .expect("TypedefsTest", "Allocator")
+        .fields()
+        .map(|f| (f.name().to_string(), f.ty()))
+        .collect();
+    assert_eq!(
+        fields,
+        vec![
+            (
+                "zalloc".to_string(),
+                Type::named("TypedefsTest", "alloc_cb")
+            ),
+            ("zfree".to_string(), Type::named("TypedefsTest", "free_fn")),
+            // Anonymous: stored as a pointer-sized integer.
+            ("flush".to_string(), Type::ISize),
+            ("opaque".to_string(), Type::PtrMut(Box::new(Type::Void), 1)),
+        ]
+    );
+}
+
+#[test]
+fn array_typedef_is_fixed_buffer() {
+    let index = open_index();
+
+    let key = index.expect("TypedefsTest", "key_t");
+    assert_eq!(key.category(), TypeCategory::Struct);
+    let layout = key.class_layout().expect("ClassLayout on key_t");
+    assert_eq!(layout.class_size(), 32);
+    assert_eq!(layout.packing_size(), 1);
+    assert_eq!(
+        typedef_value(&index, "key_t"),
+        Type::ArrayFixed(Box::new(Type::U8), 32)
+    );
+
+    // As a parameter the array decays, typedef or not.
+    assert_eq!(
+        signature(&index, "derive_key").types[1],
+        Type::PtrMut(Box::new(Type::U8), 1)
+    );
+}
@@ -0,0 +1,34 @@
+//! Round-trip integration test: a partition with only types emits no `Apis`
+//! class unless `emit_empty_apis` asks for one.
+
+use std::path::{Path, PathBuf};
+
+fn fixture() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/typesonly/typesonly.toml")
+}
+
+fn open_index(winmd: Vec<u8>) -> windows_metadata::reader::TypeIndex {
+    let file = windows_metadata::reader::File::new(winmd).expect("parse winmd");
+    windows_metadata::reader::TypeIndex::new(vec![file])
+}
+
+#[test]
+fn types_only_partition_has_no_apis() {
+    let index = open_index(bnd_winmd::generate(&fixture()).expect("generate typesonly winmd"));
+    index.expect("TypesOnlyTest", "Point");
+    index.expect("TypesOnlyTest", "Shape");
+    assert!(!index.contains("TypesOnlyTest", "Apis"));
+}
+
+#[test]
+fn emit_empty_apis_keeps_an_empty_class() {
+    let mut cfg = bnd_winmd::config::load_config(&fixture()).expect("load config");
+    cfg.emit_empty_apis = true;
+    let winmd = bnd_winmd::generate_from_config(&cfg, fixture().parent().unwrap())
+        .expect("generate typesonly winmd");
+    let index = open_index(winmd);
+
+    let apis = index.expect("TypesOnlyTest", "Apis");
+    assert_eq!(apis.methods().count(), 0);
+    assert_eq!(apis.fields().count(), 0);
+}
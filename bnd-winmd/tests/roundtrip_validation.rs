@@ -49,3 +49,41 @@ fn unresolved_does_not_report_known_types() {
         "error should NOT mention 'KnownStruct' (it's properly traversed), got:\n{err}"
     );
 }
+
+#[test]
+fn missing_include_is_reported() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../tests/fixtures/missing_include/missing_include.toml");
+    let err = format!(
+        "{:#}",
+        bnd_winmd::generate(&path).expect_err("should fail due to a missing #include")
+    );
+
+    assert!(
+        err.contains("does_not_exist.h"),
+        "error should name the missing header, got:\n{err}"
+    );
+    assert!(
+        err.contains("missing_include.h:5:"),
+        "error should point at the #include line, got:\n{err}"
+    );
+    assert!(
+        err.contains("include_paths"),
+        "error should suggest checking include_paths, got:\n{err}"
+    );
+}
+
+#[test]
+fn missing_partition_header_is_reported() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../tests/fixtures/missing_include/missing_header.toml");
+    let err = format!(
+        "{:#}",
+        bnd_winmd::generate(&path).expect_err("should fail due to a missing header")
+    );
+
+    assert!(
+        err.contains("no_such_header.h") && err.contains("not found"),
+        "error should say the header was not found, got:\n{err}"
+    );
+}
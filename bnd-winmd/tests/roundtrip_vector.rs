@@ -0,0 +1,107 @@
+//! Round-trip integration test: GCC/clang vector types are stored as fixed
+//! buffers of their element.
+
+use std::path::Path;
+use std::sync::LazyLock;
+
+use windows_metadata::Type;
+
+static VECTOR_WINMD: LazyLock<(Vec<u8>, bnd_winmd::Report)> = LazyLock::new(|| {
+    let config = Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/fixtures/vector/vector.toml");
+    bnd_winmd::generate_with_report(&config).expect("generate vector winmd")
+});
+
+fn open_index() -> windows_metadata::reader::TypeIndex {
+    let file = windows_metadata::reader::File::new(VECTOR_WINMD.0.clone()).expect("parse winmd");
+    windows_metadata::reader::TypeIndex::new(vec![file])
+}
+
+fn fields(index: &windows_metadata::reader::TypeIndex, name: &str) -> Vec<(String, Type)> {
+    index
+        .expect("VectorTest", name)
+        .fields()
+        .map(|f| (f.name().to_string(), f.ty()))
+        .collect()
+}
+
+#[test]
+fn vector_typedef_is_a_sixteen_byte_buffer() {
+    let index = open_index();
+    let v4sf = index.expect("VectorTest", "v4sf");
+
+    assert_eq!(
+        fields(&index, "v4sf"),
+        [(
+            "Value".to_string(),
+            Type::ArrayFixed(Box::new(Type::F32), 4)
+        )]
+    );
+    let layout = v4sf.class_layout().expect("v4sf layout");
+    assert_eq!(layout.class_size(), 16);
+    assert_eq!(layout.packing_size(), 16);
+}
+
+#[test]
+fn struct_with_vector_field_keeps_c_offsets() {
+    let index = open_index();
+
+    // `position` is 16-aligned in C but emitted as `[f32; 4]`.
+    assert_eq!(
+        fields(&index, "Particle"),
+        [
+            ("id".to_string(), Type::I32),
+            (
+                "_padding0".to_string(),
+                Type::ArrayFixed(Box::new(Type::U8), 12)
+            ),
+            ("position".to_string(), Type::named("VectorTest", "v4sf")),
+        ]
+    );
+    let layout = index
+        .expect("VectorTest", "Particle")
+        .class_layout()
+        .expect("Particle layout");
+    assert_eq!(layout.class_size(), 32);
+}
+
+#[test]
+fn inline_vector_fields_record_their_element() {
+    let index = open_index();
+
+    assert_eq!(
+        fields(&index, "Lanes"),
+        [
+            ("tag".to_string(), Type::U8),
+            (
+                "_padding0".to_string(),
+                Type::ArrayFixed(Box::new(Type::U8), 15)
+            ),
+            (
+                "lanes".to_string(),
+                Type::ArrayFixed(Box::new(Type::I32), 4)
+            ),
+            (
+                "weights".to_string(),
+                Type::ArrayFixed(Box::new(Type::F64), 2)
+            ),
+        ]
+    );
+}
+
+#[test]
+fn vector_by_value_functions_are_dropped() {
+    let report = &VECTOR_WINMD.1;
+    let dropped: Vec<&str> = report
+        .dropped("VectorTest")
+        .map(|d| d.name.as_str())
+        .collect();
+    assert_eq!(dropped, ["v4sf_add"]);
+
+    let index = open_index();
+    let methods: Vec<String> = index
+        .expect("VectorTest", "Apis")
+        .methods()
+        .map(|m| m.name().to_string())
+        .collect();
+    assert_eq!(methods, ["particle_norm"]);
+}
@@ -0,0 +1,116 @@
+//! Integration test: `verify` accepts generated winmds and rejects a
+//! deliberately broken one.
+
+use std::path::Path;
+
+use bnd_winmd::model::TypeRegistry;
+use windows_metadata::writer::{File, TypeDefOrRef};
+use windows_metadata::{
+    FieldAttributes, MethodAttributes, MethodImplAttributes, PInvokeAttributes, Signature, Type,
+    TypeAttributes,
+};
+
+fn generate(fixture: &str) -> Vec<u8> {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../tests/fixtures")
+        .join(fixture);
+    bnd_winmd::generate(&path).unwrap_or_else(|e| panic!("generate {fixture}: {e:#}"))
+}
+
+/// A winmd with one of each violation `verify` looks for.
+fn broken_winmd() -> Vec<u8> {
+    let mut file = File::new("Broken");
+    let valuetype = file.TypeRef("System", "ValueType");
+    let object = file.TypeRef("System", "Object");
+
+    file.TypeDef(
+        "Broken",
+        "Point",
+        TypeDefOrRef::TypeRef(valuetype),
+        TypeAttributes::Public,
+    );
+    file.Field(
+        "next",
+        &Type::PtrMut(Box::new(Type::named("Broken", "Missing")), 1),
+        FieldAttributes::Public,
+    );
+
+    file.TypeDef(
+        "Broken",
+        "Point",
+        TypeDefOrRef::TypeRef(valuetype),
+        TypeAttributes::Public,
+    );
+    file.Field("x", &Type::I32, FieldAttributes::Public);
+
+    file.TypeDef(
+        "Broken",
+        "Apis",
+        TypeDefOrRef::TypeRef(object),
+        TypeAttributes::Public | TypeAttributes::Abstract | TypeAttributes::Sealed,
+    );
+    let sig = Signature {
+        return_type: Type::named("Broken", "Point"),
+        ..Signature::default()
+    };
+    let linked = file.MethodDef(
+        "linked",
+        &sig,
+        MethodAttributes::Public | MethodAttributes::Static,
+        MethodImplAttributes::PreserveSig,
+    );
+    file.ImplMap(linked, PInvokeAttributes::CallConvCdecl, "linked", "broken");
+    file.MethodDef(
+        "unlinked",
+        &Signature::default(),
+        MethodAttributes::Public | MethodAttributes::Static,
+        MethodImplAttributes::PreserveSig,
+    );
+
+    file.into_stream()
+}
+
+#[test]
+fn generated_winmds_verify() {
+    for fixture in [
+        "simple/simple.toml",
+        "multi/multi.toml",
+        "records/records.toml",
+    ] {
+        let bytes = generate(fixture);
+        bnd_winmd::verify(&bytes, &TypeRegistry::default())
+            .unwrap_or_else(|e| panic!("{fixture} failed verification: {e:#}"));
+    }
+}
+
+#[test]
+fn broken_winmd_is_rejected() {
+    let err = bnd_winmd::verify(&broken_winmd(), &TypeRegistry::default())
+        .expect_err("broken winmd must not verify");
+    let msg = format!("{err:#}");
+
+    assert!(
+        msg.contains("dangling type reference `Broken.Missing` in field `Broken.Point.next`"),
+        "{msg}"
+    );
+    assert!(msg.contains("duplicate type `Broken.Point`"), "{msg}");
+    assert!(
+        msg.contains("method `Broken.Apis.unlinked` has no ImplMap"),
+        "{msg}"
+    );
+    assert!(!msg.contains("Broken.Apis.linked"), "{msg}");
+}
+
+#[test]
+fn registered_types_are_not_dangling() {
+    let mut registry = TypeRegistry::default();
+    registry.register("Missing", "Broken");
+
+    let err = bnd_winmd::verify(&broken_winmd(), &registry).expect_err("still broken");
+    assert!(!format!("{err:#}").contains("Broken.Missing"), "{err:#}");
+}
+
+#[test]
+fn garbage_is_not_a_winmd() {
+    assert!(bnd_winmd::verify(b"not a winmd", &TypeRegistry::default()).is_err());
+}
@@ -47,6 +47,13 @@ impl Default for NetAddr_addr {
         unsafe { core::mem::zeroed() }
     }
 }
+#[repr(C, packed(1))]
+#[derive(Clone, Copy, Default)]
+pub struct PackedHeader {
+    pub kind: u8,
+    pub length: u32,
+    pub checksum: u16,
+}
 #[repr(C, packed(4))]
 #[derive(Clone, Copy, Default)]
 pub struct Rect {
@@ -35,6 +35,15 @@ mod tests {
         assert_eq!(r.width, 100);
     }
 
+    #[test]
+    fn test_packed_struct_layout() {
+        // #pragma pack(1): 1 + 4 + 2 bytes, no padding
+        assert_eq!(std::mem::size_of::<PackedHeader>(), 7);
+        assert_eq!(std::mem::align_of::<PackedHeader>(), 1);
+        assert_eq!(std::mem::offset_of!(PackedHeader, length), 1);
+        assert_eq!(std::mem::offset_of!(PackedHeader, checksum), 5);
+    }
+
     #[test]
     fn test_create_and_destroy_widget() {
         unsafe {
@@ -52,6 +52,7 @@ windows_link::link!("z" "C" fn gzgetc_(file : gzFile) -> i32);
 windows_link::link!("z" "C" fn gzgets(file : gzFile, buf : *mut i8, len : i32) -> *mut i8);
 windows_link::link!("z" "C" fn gzoffset(param0 : gzFile) -> i64);
 windows_link::link!("z" "C" fn gzopen(param0 : *const i8, param1 : *const i8) -> gzFile);
+windows_link::link!("z" "C" fn gzprintf(file : gzFile, format : *const i8, ...) -> i32);
 windows_link::link!("z" "C" fn gzputc(file : gzFile, c : i32) -> i32);
 windows_link::link!("z" "C" fn gzputs(file : gzFile, s : *const i8) -> i32);
 windows_link::link!("z" "C" fn gzread(file : gzFile, buf : voidp, len : u32) -> i32);
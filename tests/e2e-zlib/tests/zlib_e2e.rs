@@ -136,3 +136,28 @@ fn gz_file_s_has_three_fields() {
     let _ = s.next;
     let _ = s.pos;
 }
+
+// ---------------------------------------------------------------------------
+// Variadic functions
+// ---------------------------------------------------------------------------
+
+#[test]
+fn gzprintf_formats_variadic_args() {
+    let path = std::env::temp_dir().join(format!("bnd_gzprintf_{}.gz", std::process::id()));
+    let c_path = std::ffi::CString::new(path.to_str().unwrap()).unwrap();
+
+    let file = unsafe { gzopen(c_path.as_ptr(), c"wb".as_ptr()) };
+    assert!(!file.is_null(), "gzopen for writing failed");
+    let written = unsafe { gzprintf(file, c"%s-%d".as_ptr(), c"abc".as_ptr(), 42i32) };
+    assert_eq!(written, 6, "gzprintf returns the uncompressed length");
+    assert_eq!(unsafe { gzclose(file) }, Z_OK);
+
+    let file = unsafe { gzopen(c_path.as_ptr(), c"rb".as_ptr()) };
+    assert!(!file.is_null(), "gzopen for reading failed");
+    let mut buf = [0u8; 16];
+    let read = unsafe { gzread(file, buf.as_mut_ptr().cast(), buf.len() as u32) };
+    assert_eq!(unsafe { gzclose(file) }, Z_OK);
+    let _ = std::fs::remove_file(&path);
+
+    assert_eq!(&buf[..read as usize], b"abc-42");
+}
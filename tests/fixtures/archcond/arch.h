@@ -0,0 +1,10 @@
+/* Declarations that differ by target architecture. */
+
+typedef struct Context {
+#ifdef __x86_64__
+    unsigned long long rip;
+#else
+    unsigned int pc;
+#endif
+} Context;
+
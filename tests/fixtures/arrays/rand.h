@@ -0,0 +1,5 @@
+/* Buffer-plus-length functions for array_params. */
+
+int RAND_bytes(unsigned char *buf, int num);
+int RAND_seed(int flags, const void *seed, int len);
+int RAND_status(void);
@@ -0,0 +1,10 @@
+#pragma once
+
+// Test fixture: Apple block pointers (parsed with `-fblocks`) are kept as
+// opaque pointers instead of dropping the declaration.
+
+typedef void (^completion_t)(int status);
+
+int run_async(int job, void (^done)(int status));
+
+int run_with(completion_t callback);
@@ -0,0 +1,12 @@
+#pragma once
+
+// Test fixture: non-default calling conventions (parsed for x64 Windows,
+// where clang accepts all of them).
+
+int __cdecl cc_cdecl(int a);
+
+int __vectorcall cc_vectorcall(double x, double y);
+
+int __attribute__((ms_abi)) cc_ms_abi(int a);
+
+int __attribute__((sysv_abi)) cc_sysv_abi(int a);
@@ -0,0 +1,4 @@
+#pragma once
+#include "common.h"
+
+int shared_open(handle_t h, struct Shared *out);
@@ -0,0 +1,8 @@
+#pragma once
+
+// Shared by both partitions below.
+typedef unsigned int handle_t;
+
+struct Shared {
+    handle_t owner;
+};
@@ -0,0 +1,8 @@
+#pragma once
+
+// Test fixture: the one header `broken.toml` gets right.
+
+typedef struct Point {
+    int x;
+    int y;
+} Point;
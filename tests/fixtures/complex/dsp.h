@@ -0,0 +1,5 @@
+#pragma once
+#include "numbers.h"
+
+// Uses `double _Complex` from a second partition.
+void fft(double _Complex *data, unsigned long n);
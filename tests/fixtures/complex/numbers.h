@@ -0,0 +1,15 @@
+#pragma once
+
+// Test fixture: C99 `_Complex` types.
+
+typedef struct Sample {
+    int id;
+    double _Complex value;
+} Sample;
+
+typedef struct Pair {
+    float _Complex a;
+    float _Complex b;
+} Pair;
+
+double _Complex scale(double _Complex z, double k);
@@ -0,0 +1,5 @@
+#pragma once
+
+typedef struct {
+    int value;
+} AlphaOne;
@@ -0,0 +1,15 @@
+#pragma once
+
+#include "cpp_types.h"
+
+// C++ only: `static_assert` as a keyword, `bool` without <stdbool.h>, and a
+// struct named without the `struct` keyword.
+static_assert(sizeof(CppPoint) == 8, "CppPoint must be two ints");
+
+extern "C" {
+
+bool cpp_point_is_origin(const CppPoint *p);
+
+int cpp_point_sum(CppPoint p);
+
+}
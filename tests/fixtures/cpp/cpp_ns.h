@@ -0,0 +1,25 @@
+#pragma once
+
+// Test fixture: an API declared inside C++ namespaces.
+
+namespace cppns {
+
+struct NsPoint {
+    int x;
+    int y;
+};
+
+extern "C" {
+int ns_point_sum(NsPoint p);
+}
+
+namespace v2 {
+int ns_point_dot(const NsPoint *a, const NsPoint *b);
+}
+
+// Not listed in `cpp_namespaces`: skipped.
+namespace detail {
+int ns_internal(int x);
+}
+
+}
@@ -0,0 +1,22 @@
+#pragma once
+
+// Test fixture: headers that only compile as C++.
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+struct CppPoint {
+    int x;
+    int y;
+};
+
+#ifdef __cplusplus
+}
+#endif
+
+// Scoped enum with a fixed base: stored as its base, not as int.
+enum class CppLevel : unsigned char {
+    CPP_LEVEL_LOW = 1,
+    CPP_LEVEL_HIGH = 2,
+};
@@ -0,0 +1,18 @@
+#pragma once
+
+// Test fixture: a struct whose layout depends on feature-test style macros,
+// like `struct stat` under `_FILE_OFFSET_BITS`.
+
+typedef struct Record {
+#if defined(RECORD_OFFSET_BITS) && RECORD_OFFSET_BITS == 64
+    long long offset;
+#else
+    int offset;
+#endif
+    int flags;
+#ifdef RECORD_TAGGED
+    int tag;
+#endif
+} Record;
+
+int record_read(Record *out);
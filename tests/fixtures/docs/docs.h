@@ -0,0 +1,39 @@
+#pragma once
+
+/**
+ * Connection settings.
+ *
+ * Passed by pointer to doc_connect.
+ */
+struct DocSettings {
+    int timeout;
+    int retries;
+};
+
+/// Result of a connection attempt.
+typedef enum {
+    DOC_OK = 0,
+    DOC_TIMEOUT = 1,
+} DocStatus;
+
+/// Open a connection using `settings`.
+/// Returns DOC_OK on success.
+DocStatus doc_connect(const struct DocSettings* settings);
+
+// A plain comment is not documentation.
+void doc_close(void);
+
+/**
+ * Default port for doc_connect.
+ *
+ * Override it in DocSettings.
+ */
+#define DOC_DEFAULT_PORT 8080
+
+/// Upper bound for DocSettings.retries.
+#define DOC_MAX_RETRIES 5
+
+#define DOC_FLAG_FAST 0x1 // skip the handshake
+
+// A plain comment above a macro is not documentation.
+#define DOC_UNDOCUMENTED 7
@@ -0,0 +1,12 @@
+#pragma once
+
+// Test fixture: a typedef of an unsupported type (an integer `_Complex`, a
+// GNU extension) is mapped to void by default, and dropped together with
+// the functions using it under `strict_signatures`.
+
+typedef _Complex int cint;
+
+int cint_norm(cint v);
+cint cint_make(int re, int im);
+
+int scalar_add(int a, int b);
@@ -0,0 +1,14 @@
+#pragma once
+
+// Test fixture: declarations using a type bnd-winmd cannot represent
+// (GNU integer `_Complex` types) are dropped and reported, the rest is still
+// emitted.
+
+typedef struct {
+    int low;
+    _Complex int wide;
+} WideRecord;
+
+int wide_is_zero(_Complex int value);
+
+int narrow_add(int a, int b);
@@ -0,0 +1,12 @@
+#pragma once
+#include "levels.h"
+
+// Functions in another namespace taking enums defined in levels.h
+int set_level(Level level);
+int set_mode(enum Mode mode);
+int set_hidden(enum Hidden hidden);
+
+typedef struct {
+    Level level;
+    enum Mode mode;
+} Settings;
@@ -0,0 +1,16 @@
+#pragma once
+
+typedef enum {
+    LEVEL_LOW  = 0,
+    LEVEL_HIGH = 1,
+} Level;
+
+enum Mode {
+    MODE_READ  = 1,
+    MODE_WRITE = 2,
+};
+
+// Filtered out of the types partition; references fall back to its integer.
+enum Hidden {
+    HIDDEN_ON = 1,
+};
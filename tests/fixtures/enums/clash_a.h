@@ -0,0 +1,8 @@
+#pragma once
+
+// Test fixture: `MODE_DEFAULT` is also a variant of `Mode` in clash_b.h.
+typedef enum {
+    LEVEL_LOW    = 0,
+    LEVEL_HIGH   = 1,
+    MODE_DEFAULT = 2,
+} Level;
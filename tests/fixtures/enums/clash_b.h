@@ -0,0 +1,6 @@
+#pragma once
+
+typedef enum {
+    MODE_DEFAULT = 0,
+    MODE_FAST    = 1,
+} Mode;
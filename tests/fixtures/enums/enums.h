@@ -0,0 +1,98 @@
+#pragma once
+
+// Test fixture: enum shapes and the bitmask heuristic.
+
+// Sequential values — must NOT be treated as flags
+typedef enum {
+    COLOR_RED   = 0,
+    COLOR_GREEN = 1,
+    COLOR_BLUE  = 2,
+} Color;
+
+// Distinct powers of two — detected as flags
+typedef enum {
+    PERM_NONE  = 0,
+    PERM_READ  = 1,
+    PERM_WRITE = 2,
+    PERM_EXEC  = 4,
+} Perms;
+
+// Looks like flags, but forced plain via `plain_enums`
+typedef enum {
+    CHANNEL_LEFT  = 1,
+    CHANNEL_RIGHT = 2,
+    CHANNEL_LFE   = 8,
+} Channel;
+
+// Too few values for the heuristic, forced flags via `flags_enums`
+typedef enum {
+    SINGLE_ONLY = 16,
+} Single;
+
+// A value above INT_MAX gives the enum an unsigned base type
+typedef enum {
+    HIGH_BIT_CLEAR = 0,
+    HIGH_BIT_SET   = 0x80000000,
+} HighBit;
+
+// A negative value next to one above UINT_MAX needs a signed 64-bit base
+typedef enum {
+    WIDE_NEG = -1,
+    WIDE_BIG = 0x100000000,
+} Wide;
+
+// A fixed base spelled through a typedef sets the storage width, whatever
+// the values would need
+typedef unsigned long long base64_t;
+typedef enum : base64_t {
+    BIG_ONE = 1,
+    BIG_TWO = 2,
+} Big;
+
+// A char-sized base: `enum_repr = "int"` passes it as an int, while the
+// struct field keeps its single byte
+typedef enum : unsigned char {
+    TINY_LOW  = 1,
+    TINY_HIGH = 200,
+} Tiny;
+
+typedef struct {
+    Tiny kind;
+    unsigned char len;
+} TinyPacket;
+
+Tiny tiny_next(Tiny tiny);
+
+// Value aliases: distinct names sharing a value are all kept
+typedef enum {
+    WHENCE_SET     = 0,
+    WHENCE_CUR     = 1,
+    WHENCE_END     = 2,
+    WHENCE_DEFAULT = WHENCE_SET,
+} Whence;
+
+// Values computed from earlier variants, folded by clang
+typedef enum {
+    STEP_BASE    = -8,
+    STEP_NEXT,
+    STEP_FAR     = STEP_BASE + 10,
+    STEP_AFTER,
+    STEP_SHIFTED = 1 << 4,
+    STEP_MASK    = STEP_SHIFTED | STEP_AFTER,
+    STEP_NEG     = -STEP_MASK,
+} Step;
+
+// Anonymous enums: variants become constants with the enum's type
+enum {
+    BUF_SMALL = 64,
+    BUF_LARGE = 4096,
+};
+
+enum {
+    STATUS_ERROR = -1,
+    STATUS_OK    = 0,
+};
+
+enum {
+    MASK_HIGH = 0x100000000,
+};
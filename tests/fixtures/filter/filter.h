@@ -0,0 +1,24 @@
+#pragma once
+
+// Test fixture: include_symbols / exclude_symbols regex filtering.
+
+typedef struct {
+    int handle;
+} LibContext;
+
+typedef struct {
+    int unused;
+} OtherContext;
+
+typedef enum {
+    LIB_MODE_FAST = 0,
+    LIB_MODE_SAFE = 1,
+} LibMode;
+
+int lib_open(LibContext* ctx, LibMode mode);
+int lib_close(LibContext* ctx);
+int lib_reset_internal(LibContext* ctx);
+int other_open(OtherContext* ctx);
+
+#define LIB_VERSION 3
+#define OTHER_VERSION 7
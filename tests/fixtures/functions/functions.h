@@ -0,0 +1,49 @@
+#pragma once
+
+// Test fixture: function signature edge cases.
+
+// Variadic — the fixed params are kept and the VARARG bit is set
+int log_message(int level, const char* fmt, ...);
+
+// Non-variadic control
+int log_flush(void);
+
+// Deprecated — emitted with System.ObsoleteAttribute
+int log_open(const char* path) __attribute__((deprecated("use log_open_ex")));
+void log_reset(void) __attribute__((deprecated));
+
+struct __attribute__((deprecated)) LogConfig {
+    int level;
+};
+
+// Pointer constness — direction follows the pointed-to data
+int fill_buffer(void* dst, const void* src, char* const fixed);
+int copy_strings(char** out, const char** names, char* const* argv);
+
+// Output slots — a trailing output-named pointer of a function returning an
+// integer is `[Out]` only
+int read_count(const char* path, int* out_count);
+void read_into(int* out);
+
+// Directions overridden by `param_directions` in functions.toml
+int parse_header(const char* text, int* header);
+int query_size(int* size);
+int checksum(unsigned char* data, int* out);
+
+// Records passed and returned by value, like `div_t div(int, int)`
+typedef struct {
+    int quot;
+    int rem;
+} div_result;
+
+typedef union {
+    float f;
+    unsigned int bits;
+} FloatBits;
+
+div_result int_div(int numer, int denom);
+FloatBits float_bits(FloatBits value);
+
+// Pointer chains to a record keep every level and the record's TypeRef,
+// like `X509_STORE_CTX_get1_chain(STACK_OF(X509) **chain)`
+int list_results(div_result** results, int* count);
@@ -0,0 +1,15 @@
+/* Thread-local globals only reachable through an accessor macro, in the
+ * style of POSIX `errno`. */
+typedef struct io_state {
+    int last_error;
+    unsigned int retries;
+} io_state;
+
+extern int *__errno_location(void);
+#define errno (*__errno_location())
+
+extern io_state *__io_state_location(void);
+#define io_state_current (*__io_state_location())
+
+extern const char **__progname_location(void);
+#define progname (*__progname_location())
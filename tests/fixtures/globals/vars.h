@@ -0,0 +1,15 @@
+/* Global variables exported by a library, in the style of getopt. */
+typedef struct io_state {
+    int last_error;
+    unsigned int retries;
+} io_state;
+
+/** Index of the next argument to process. */
+extern int optind;
+extern char *optarg;
+extern const int version_major;
+extern io_state io_default;
+
+/* No exported symbol: internal linkage or per-thread storage. */
+static int hidden_counter;
+extern _Thread_local int per_thread;
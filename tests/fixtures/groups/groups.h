@@ -0,0 +1,12 @@
+/* Constants grouped into sub-namespaces by constant_group_prefixes. */
+
+#define GRP_OP_ALL 0x00000850
+#define GRP_OP_NO_TICKET 0x00004000
+
+#define GRP_V_OK 0
+#define GRP_V_ERR_UNSPECIFIED 1
+
+/* Starts with "GRP_V" but not the "GRP_V_" prefix: stays on Apis. */
+#define GRP_VERSION 3
+
+int grp_init(void);
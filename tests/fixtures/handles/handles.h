@@ -0,0 +1,29 @@
+#pragma once
+
+// Test fixture: opaque handle typedefs (OpenSSL style).
+
+typedef struct hdl_ctx_st HDL_CTX;
+typedef struct hdl_plain_st HDL_PLAIN;
+
+HDL_CTX *hdl_new(void);
+void hdl_free(HDL_CTX *ctx);
+int hdl_clone(const HDL_CTX *src, HDL_CTX **out);
+
+// Not listed in `opaque_handles`: keeps the pointer.
+int hdl_plain_use(HDL_PLAIN *plain);
+
+typedef struct {
+    HDL_CTX *ctx;
+    int flags;
+} HdlHolder;
+
+// Forward-declared only, with no typedef: listed in `opaque_handles` by tag.
+struct hdl_session;
+
+struct hdl_session *hdl_session_open(void);
+void hdl_session_close(struct hdl_session *session);
+
+// Forward-declared and not listed: stays `void *`.
+struct hdl_other;
+
+void hdl_other_use(struct hdl_other *other);
@@ -0,0 +1,5 @@
+/* Upstream "Alpha" library — imported by consumer.h. */
+typedef struct AlphaPoint {
+    int x;
+    int y;
+} AlphaPoint;
@@ -0,0 +1,5 @@
+/* Upstream "Beta" library — imported by consumer.h. */
+typedef struct BetaSize {
+    unsigned int width;
+    unsigned int height;
+} BetaSize;
@@ -0,0 +1,4 @@
+/* Lives in the Beta.Inner sub-namespace, to exercise exact matching. */
+typedef struct BetaInner {
+    int value;
+} BetaInner;
@@ -0,0 +1,5 @@
+#include "ext.h"
+
+int consume_point(const AlphaPoint *point);
+void consume_size(BetaSize *size);
+void consume_inner(BetaInner *inner);
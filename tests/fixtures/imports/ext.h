@@ -0,0 +1,5 @@
+/* Forward declarations of the imported types. Not traversed: the
+ * definitions come from the Alpha and Beta winmds. */
+typedef struct AlphaPoint AlphaPoint;
+typedef struct BetaSize BetaSize;
+typedef struct BetaInner BetaInner;
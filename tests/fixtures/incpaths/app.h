@@ -0,0 +1,4 @@
+#pragma once
+#include <vendor.h>
+
+int app_start(VendorHandle* handle);
@@ -0,0 +1,4 @@
+#pragma once
+
+// Only reachable through the second top-level include path.
+int extra_count(void);
@@ -0,0 +1,16 @@
+#pragma once
+
+// Test fixture: functions without an exported symbol.
+
+static inline unsigned short swap16(unsigned short x) {
+    return (unsigned short)((x << 8) | (x >> 8));
+}
+
+inline int twice(int x) { return 2 * x; }
+
+static int zero(void) { return 0; }
+
+// Has a symbol: declared extern, defined in the library.
+extern inline int thrice(int x) { return 3 * x; }
+
+int exported(int x);
@@ -0,0 +1,3 @@
+/* Forward declaration of a COM-style interface. Not traversed: the
+ * definition, with its IID, comes from the Com winmd. */
+typedef struct IWidgetSink IWidgetSink;
@@ -0,0 +1,4 @@
+#include "com.h"
+
+int attach_sink(IWidgetSink *sink);
+int query_sink(IWidgetSink **out);
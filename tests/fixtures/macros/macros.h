@@ -0,0 +1,41 @@
+#pragma once
+
+// Test fixture: #define constants written as expressions.
+
+#define PAGE_SHIFT 12
+#define PAGE_SIZE (1 << PAGE_SHIFT)
+#define PAGE_MASK (PAGE_SIZE - 1)
+
+#define MAP_SHARED 0x01
+#define MAP_ANONYMOUS 0x20
+#define MAP_SHARED_ANON (MAP_SHARED | MAP_ANONYMOUS)
+
+#define HUGE_SPAN (1ULL << 40)
+
+// Integer literals at the edges of i64 / u64.
+#define LIMIT_I64_MAX 9223372036854775807
+#define LIMIT_I64_MIN -9223372036854775808
+#define LIMIT_I64_MIN_HEX -0x8000000000000000
+#define LIMIT_U64_MAX 0xFFFFFFFFFFFFFFFFULL
+#define LIMIT_ABOVE_I32 4294967296
+// Wider than 64 bits: kept as text rather than wrapped.
+#define LIMIT_TOO_NEGATIVE -0x8000000000000001
+#define LIMIT_U128_MAX 0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFULL
+#define LIMIT_ABOVE_U64 18446744073709551616
+
+// Character literals.
+#define PATH_SEP '/'
+#define NEWLINE '\n'
+#define OCTAL_A '\101'
+#define HIGH_CHAR '\xff'
+#define WIDE_E_ACUTE L'\u00e9'
+#define SMILEY u'\x263a'
+#define FOURCC_RIFF 'RIFF'
+// Too long for an int: skipped.
+#define TOO_LONG 'abcde'
+
+// Not constants: never emitted.
+extern int counter;
+#define NEXT_COUNTER (counter + 1)
+#define GREETING "hello"
+#define ANSWER() 42
@@ -0,0 +1,7 @@
+// Test fixture: includes a header that does not exist. Extraction must fail
+// with a diagnostic naming the missing file instead of yielding an empty
+// partition.
+
+#include "does_not_exist.h"
+
+int still_declared(int x);
@@ -0,0 +1,10 @@
+#pragma once
+
+// Test fixture: the `Outputs.Core` partition, written to its own winmd.
+
+typedef struct {
+    int x;
+    int y;
+} CorePoint;
+
+int core_init(void);
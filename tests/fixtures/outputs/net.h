@@ -0,0 +1,15 @@
+#pragma once
+
+// Test fixture: the `Outputs.Net` partition, which references a type from
+// the other output.
+
+#include "core.h"
+
+typedef struct {
+    CorePoint at;
+    unsigned short port;
+} NetAddr;
+
+int net_send(const NetAddr *addr);
+
+int net_locate(CorePoint *out);
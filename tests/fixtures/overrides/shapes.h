@@ -0,0 +1,18 @@
+#pragma once
+
+// Point is shared by several libraries — namespace_overrides moves it (and
+// point_len) into OverrideTest.Common without splitting this header.
+typedef struct {
+    int x;
+    int y;
+} Point;
+
+typedef struct {
+    Point min;
+    Point max;
+} Box;
+
+int point_len(const Point* p);
+int box_area(const Box* b);
+
+#define POINT_ORIGIN 0
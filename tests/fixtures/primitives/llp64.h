@@ -0,0 +1,13 @@
+#pragma once
+
+// Test fixture: parsed with a Windows target triple, so `long` is 32-bit
+// and `wchar_t` is 16-bit.
+
+typedef __WCHAR_TYPE__ wchar_t;
+
+typedef struct {
+    long l;
+    unsigned long ul;
+} WinLongHolder;
+
+unsigned long win_wide_len(const wchar_t* s);
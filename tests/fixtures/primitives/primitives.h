@@ -0,0 +1,54 @@
+#pragma once
+
+// Test fixture: C primitive types that need target-aware mapping.
+
+#include "sizes.h"
+
+// Same definitions <stddef.h> / <uchar.h> provide, without needing them.
+typedef __WCHAR_TYPE__ wchar_t;
+typedef __CHAR16_TYPE__ char16_t;
+typedef __CHAR32_TYPE__ char32_t;
+
+// long double — 16 bytes on x86-64 (80-bit x87 value + padding)
+typedef struct {
+    long double value;
+} LongDoubleHolder;
+
+typedef struct {
+    char tag;
+    long double value;
+} PaddedLongDouble;
+
+// Dropped: the value travels in x87 registers. A pointer to one is fine.
+long double ld_identity(long double x);
+void ld_scale(long double *value, double by);
+
+// Dropped too: `__int128` travels in a register pair.
+__int128 i128_add(__int128 a, __int128 b);
+unsigned __int128 u128_from(unsigned long long hi, unsigned long long lo);
+void u128_store(unsigned __int128 *out, unsigned long long lo);
+
+// long / unsigned long — 64-bit under LP64 (the host data model here)
+typedef struct {
+    long l;
+    unsigned long ul;
+} LongHolder;
+
+// Wide character types
+typedef struct {
+    wchar_t w;
+    char16_t u16;
+    char32_t u32;
+} WideChars;
+
+unsigned long wide_len(const wchar_t* s);
+
+// size_t and its aliases are pointer-sized; a plain unsigned long is not.
+typedef struct {
+    size_t len;
+    buffer_len cap;
+    uintptr_t addr;
+    unsigned long raw;
+} SizeHolder;
+
+typedef size_t byte_count;
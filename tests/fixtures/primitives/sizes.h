@@ -0,0 +1,10 @@
+#pragma once
+
+// Stand-in for <stddef.h> / <stdint.h>: included by primitives.h but not
+// traversed, like a system header.
+
+typedef __SIZE_TYPE__ size_t;
+typedef __UINTPTR_TYPE__ uintptr_t;
+
+// An alias of size_t that no partition extracts.
+typedef size_t buffer_len;
@@ -0,0 +1,27 @@
+#pragma once
+
+#include <signal.h>
+
+// Signal-handler state: volatile fields, including a volatile typedef.
+typedef struct {
+    volatile sig_atomic_t pending;
+    volatile int counter;
+    int *volatile cursor;
+    volatile unsigned char *mmio;
+} SignalState;
+
+// A typedef that adds a qualifier to a struct of the same name.
+typedef volatile struct Register {
+    unsigned int value;
+} Register;
+
+// restrict-qualified pointer parameters.
+int copy_bytes(char *restrict dst, const char *restrict src, int len);
+
+// Pointer to volatile data and a volatile-qualified return value.
+volatile int *register_base(volatile Register *reg);
+
+// Constness below the outer pointer.
+int count_args(const char *const *argv);
+int sort_names(const char **names, int count);
+int fill_args(char *const *argv);
@@ -0,0 +1,152 @@
+#pragma once
+
+// Test fixture: struct/union layouts that need special handling.
+
+// C11 anonymous members — their fields are injected into the parent, and
+// there is no field declarator for them.
+typedef struct {
+    int kind;
+    union {
+        int i;
+        double d;
+    };
+    struct {
+        short lo;
+        short hi;
+    };
+} Tagged;
+
+// A single anonymous member (like glibc's `siginfo_t` internals)
+typedef struct {
+    union {
+        unsigned int raw;
+        unsigned char bytes[4];
+    };
+} Word;
+
+// Tag-named union — all members overlap, so the size is the largest member
+// (12 bytes, rounded up to the 8-byte alignment), not the sum.
+union Number {
+    char c;
+    int i;
+    double d;
+    unsigned char raw[12];
+};
+
+int number_kind(union Number n);
+
+// Over-aligned member: C puts `value` at offset 16, while sequential layout
+// with natural alignment would put it at 4 — explicit padding is required.
+struct OverAligned {
+    char tag;
+    int value __attribute__((aligned(16)));
+};
+
+// Natural padding only — sequential layout already matches C.
+struct NaturallyPadded {
+    char tag;
+    long long value;
+    short tail;
+};
+
+// Flexible array members occupy no space: sizeof(struct Message) is 8.
+struct Message {
+    unsigned int len;
+    unsigned short kind;
+    char data[];
+};
+
+struct Samples {
+    char tag;
+    int values[];
+};
+
+// An incomplete array parameter is still just a pointer.
+int message_checksum(const char payload[], unsigned int len);
+
+// Bitfields: four one-bit flags share a single byte.
+struct BoolFlags {
+    _Bool a : 1;
+    _Bool b : 1;
+    _Bool c : 1;
+    _Bool d : 1;
+};
+
+// glibc-style bitfields share one `unsigned int` unit, including an unnamed
+// padding member, before an ordinary field.
+struct Mode {
+    unsigned int read : 1;
+    unsigned int write : 1;
+    unsigned int exec : 1;
+    unsigned int : 5;
+    unsigned int owner : 8;
+    int count;
+};
+
+// Bitfields of different widths: `mid` starts in the byte `low` opened but
+// runs past it, so the storage widens to the `unsigned int` unit all three
+// share.
+struct MixedBits {
+    unsigned char low : 4;
+    unsigned int mid : 12;
+    unsigned int high : 16;
+};
+
+// Packed: `wide` straddles a byte boundary right after an ordinary field,
+// so its storage covers just the bytes it spans.
+struct __attribute__((packed)) PackedBits {
+    unsigned char tag;
+    unsigned int low : 4;
+    unsigned int wide : 12;
+    unsigned int top : 8;
+};
+
+// Packed structs keep C's byte-exact layout: alignment 1, no padding.
+#pragma pack(push, 1)
+struct WireHeader {
+    unsigned char tag;
+    unsigned int len;
+    unsigned short flags;
+};
+#pragma pack(pop)
+
+struct __attribute__((packed)) PackedPair {
+    char a;
+    long long b;
+};
+
+// `#pragma pack(2)` caps, rather than removes, member alignment.
+#pragma pack(push, 2)
+struct Pack2 {
+    char a;
+    int b;
+};
+#pragma pack(pop)
+
+// C11 atomics are emitted as their plain value type; layout is unchanged.
+typedef unsigned short ticket_t;
+struct AtomicCounter {
+    char tag;
+    _Atomic int count;
+    _Atomic(unsigned long long) total;
+    _Atomic(void *) head;
+    _Atomic(ticket_t) ticket;
+};
+
+// 128-bit integers are 16-byte aligned on x86-64; the byte arrays they are
+// emitted as need explicit padding to keep that layout.
+struct Wide128 {
+    char tag;
+    __int128 value;
+    unsigned __int128 uvalue;
+};
+
+// The common `typedef struct { ... } Name;` idiom: the struct has no tag,
+// so it is emitted once, under the typedef's name.
+typedef struct {
+    int width;
+    int height;
+    double scale;
+} Extent;
+
+int extent_area(const Extent *extent);
@@ -0,0 +1,18 @@
+#pragma once
+
+// Test fixture: symbols renamed through the top-level `[renames]` table.
+
+struct stat_info {
+    long size;
+    int mode;
+};
+
+typedef struct {
+    int kind;
+} stat_kind;
+
+int stat_file(const char *path, struct stat_info *out);
+
+int stat_reset(void);
+
+#define STAT_MODE_DIR 0x4000
@@ -0,0 +1,12 @@
+#pragma once
+
+// Test fixture: resources paired with the functions that release them.
+typedef struct bio_st BIO;
+
+BIO *BIO_new(void);
+int BIO_free(BIO *a);
+
+typedef struct __dirstream DIR;
+
+DIR *opendir(const char *name);
+int closedir(DIR *dirp);
@@ -0,0 +1,25 @@
+/* Structs that refer to themselves through pointers. */
+
+struct node {
+    struct node *next;
+    int val;
+};
+
+typedef struct tree tree_t;
+struct tree {
+    tree_t *left;
+    tree_t *right;
+    int key;
+};
+
+/* Mutually recursive: each points at the other. */
+struct parent;
+struct child {
+    struct parent *owner;
+};
+struct parent {
+    struct child first;
+    struct child *rest;
+};
+
+int list_length(const struct node *head);
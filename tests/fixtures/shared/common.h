@@ -0,0 +1,6 @@
+#pragma once
+
+// Test fixture: traversed by both partitions of the shared namespace.
+#define SHARED_VERSION 3
+
+int shared_init(void);
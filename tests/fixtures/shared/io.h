@@ -0,0 +1,7 @@
+#pragma once
+#include "common.h"
+
+#define IO_BUFSIZE 512
+
+int io_read(void *buf, int len);
+int io_write(const void *buf, int len);
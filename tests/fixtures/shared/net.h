@@ -0,0 +1,6 @@
+#pragma once
+#include "common.h"
+
+#define NET_PORT 8080
+
+int net_send(const void *buf, int len);
@@ -42,6 +42,15 @@ typedef struct {
     unsigned int scope_id;
 } NetAddr;
 
+// Packed struct (like on-the-wire headers): no padding, alignment 1
+#pragma pack(push, 1)
+typedef struct {
+    unsigned char kind;
+    unsigned int length;
+    unsigned short checksum;
+} PackedHeader;
+#pragma pack(pop)
+
 // Functions
 int create_widget(const char* name, Rect bounds, Widget* out);
 void destroy_widget(Widget* w);
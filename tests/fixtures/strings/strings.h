@@ -0,0 +1,16 @@
+#pragma once
+
+// Test fixture: `char *` as C strings under `string_pointers`.
+
+#include "wide.h"
+
+typedef char *owned_str;
+
+int str_copy(char *dst, const char *src, unsigned long len);
+const char *str_version(void);
+void str_take(owned_str s);
+int bytes_sum(const unsigned char *data, unsigned long len);
+
+// Wide strings, alone and mixed with narrow ones.
+int wstr_len(const wchar_t *s);
+int str_widen(wchar_t *dst, const char *src);
@@ -0,0 +1,6 @@
+#pragma once
+
+// Stand-in for <stddef.h>: included by strings.h but not traversed, like a
+// system header.
+
+typedef __WCHAR_TYPE__ wchar_t;
@@ -0,0 +1,6 @@
+#pragma once
+
+// Test fixture: `lib_open` and `open` collapse to the same name.
+
+int lib_open(int flags);
+int open(int flags);
@@ -0,0 +1,6 @@
+#pragma once
+
+#include "evp.h"
+
+// Uses EVP_MD from another partition — the reference must follow the rename.
+int digest_init(const EVP_MD* md);
@@ -0,0 +1,13 @@
+#pragma once
+
+// Test fixture: strip_prefix = ["EVP_"] turns EVP_* into plain names.
+
+typedef struct {
+    int nid;
+    int size;
+} EVP_MD;
+
+const EVP_MD* EVP_sha256(void);
+int EVP_MD_size(const EVP_MD* md);
+
+#define EVP_MAX_MD_SIZE 64
@@ -0,0 +1,28 @@
+#pragma once
+
+// Test fixture: parsed for a 32-bit target, where pointers and `long` are
+// 4 bytes.
+
+#include "sizes.h"
+
+typedef struct Node {
+    struct Node *next;
+    long value;
+    void *data;
+    long long id;
+} Node;
+
+long node_sum(const Node *head);
+
+typedef struct Buffer {
+    void *data;
+    size_t len;
+} Buffer;
+
+size_t buffer_fill(Buffer *buf, size_t len);
+
+// A pointer-sized enum base, stored with the target's pointer width
+typedef enum : size_t {
+    WIDTH_NARROW = 1,
+    WIDTH_WIDE = 2,
+} Width;
@@ -0,0 +1,6 @@
+#pragma once
+
+// Stand-in for <stddef.h>: included by nodes.h but not traversed, like a
+// system header. On i686 `__SIZE_TYPE__` is `unsigned int`.
+
+typedef __SIZE_TYPE__ size_t;
@@ -0,0 +1,50 @@
+#pragma once
+
+// Not traversed: these aliases provide types but are not extracted.
+
+typedef unsigned int base_id_t;
+typedef base_id_t mid_id_t;
+
+// A pathologically long chain, deeper than the extractor follows link by
+// link.
+typedef unsigned long long deep_0_t;
+typedef deep_0_t deep_1_t;
+typedef deep_1_t deep_2_t;
+typedef deep_2_t deep_3_t;
+typedef deep_3_t deep_4_t;
+typedef deep_4_t deep_5_t;
+typedef deep_5_t deep_6_t;
+typedef deep_6_t deep_7_t;
+typedef deep_7_t deep_8_t;
+typedef deep_8_t deep_9_t;
+typedef deep_9_t deep_10_t;
+typedef deep_10_t deep_11_t;
+typedef deep_11_t deep_12_t;
+typedef deep_12_t deep_13_t;
+typedef deep_13_t deep_14_t;
+typedef deep_14_t deep_15_t;
+typedef deep_15_t deep_16_t;
+typedef deep_16_t deep_17_t;
+typedef deep_17_t deep_18_t;
+typedef deep_18_t deep_19_t;
+typedef deep_19_t deep_20_t;
+typedef deep_20_t deep_21_t;
+typedef deep_21_t deep_22_t;
+typedef deep_22_t deep_23_t;
+typedef deep_23_t deep_24_t;
+typedef deep_24_t deep_25_t;
+typedef deep_25_t deep_26_t;
+typedef deep_26_t deep_27_t;
+typedef deep_27_t deep_28_t;
+typedef deep_28_t deep_29_t;
+typedef deep_29_t deep_30_t;
+typedef deep_30_t deep_31_t;
+typedef deep_31_t deep_32_t;
+typedef deep_32_t deep_33_t;
+typedef deep_33_t deep_34_t;
+typedef deep_34_t deep_35_t;
+typedef deep_35_t deep_36_t;
+typedef deep_36_t deep_37_t;
+typedef deep_37_t deep_38_t;
+typedef deep_38_t deep_39_t;
+typedef deep_39_t deep_40_t;
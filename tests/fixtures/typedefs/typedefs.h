@@ -0,0 +1,54 @@
+#pragma once
+
+// Test fixture: typedef-of-typedef chains.
+
+#include "chain_base.h"
+
+// Three-level chain through out-of-scope aliases: object_id_t is emitted,
+// mid_id_t and base_id_t are not, so references resolve to unsigned int.
+typedef mid_id_t object_id_t;
+
+object_id_t next_id(void);
+int release_id(mid_id_t id);
+
+// Three-level chain fully in scope: every alias is emitted and refers to
+// the previous one.
+typedef struct {
+    int x;
+    int y;
+} Origin;
+typedef Origin Anchor;
+typedef Anchor Pin;
+
+Pin make_pin(int x, int y);
+
+deep_40_t deep_value(void);
+
+// Callback typedefs are emitted as delegates however they are spelled: a
+// function pointer, a function type used through `*`, or an alias of one.
+typedef int (*read_cb)(void *ctx, int len);
+typedef int write_fn(const char *buf, int len);
+typedef read_cb read_cb_alias;
+
+int set_callbacks(read_cb reader, write_fn *writer, read_cb_alias fallback);
+
+// Callbacks referenced only from struct fields are emitted too, and the
+// fields name their delegates. An unnamed function pointer field has no
+// delegate to name.
+typedef void *(*alloc_cb)(void *opaque, unsigned items, unsigned size);
+typedef void free_fn(void *opaque, void *address);
+
+typedef struct {
+    alloc_cb zalloc;
+    free_fn *zfree;
+    int (*flush)(void *opaque);
+    void *opaque;
+} Allocator;
+
+int use_allocator(Allocator *allocator);
+
+// Array typedefs are fixed buffers of the array's size; as parameters they
+// decay to a pointer to the element type.
+typedef unsigned char key_t[32];
+
+int derive_key(const char *pass, key_t out);
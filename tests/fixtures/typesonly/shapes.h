@@ -0,0 +1,11 @@
+/* Types only: no functions or #define constants. */
+
+enum Shape {
+    SHAPE_CIRCLE,
+    SHAPE_SQUARE,
+};
+
+struct Point {
+    int x;
+    int y;
+};
@@ -0,0 +1,22 @@
+#pragma once
+
+// Test fixture: GCC/clang vector types are stored as fixed buffers of their
+// element.
+
+typedef float v4sf __attribute__((vector_size(16)));
+
+typedef struct Particle {
+    int id;
+    v4sf position;
+} Particle;
+
+typedef struct Lanes {
+    unsigned char tag;
+    int lanes __attribute__((vector_size(16)));
+    double weights __attribute__((vector_size(16)));
+} Lanes;
+
+// Passed in a SIMD register: dropped.
+v4sf v4sf_add(v4sf a, v4sf b);
+
+float particle_norm(const Particle *p);